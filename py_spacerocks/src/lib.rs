@@ -28,6 +28,15 @@ use py_nbody::make_nbody_submodule;
 mod py_orbfit;
 use py_orbfit::make_orbfit_submodule;
 
+mod py_ephemeris;
+use py_ephemeris::make_ephemeris_submodule;
+
+mod py_survey;
+use py_survey::make_survey_submodule;
+
+mod py_population;
+use py_population::make_population_submodule;
+
 mod mpc;
 // use mpc::MPC;
 
@@ -59,6 +68,15 @@ pub fn py_spacerocks(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Add the `orbfit` submodule
     make_orbfit_submodule(py, m)?;
 
+    // Add the `ephemeris` submodule
+    make_ephemeris_submodule(py, m)?;
+
+    // Add the `survey` submodule
+    make_survey_submodule(py, m)?;
+
+    // Add the `population` submodule
+    make_population_submodule(py, m)?;
+
 
     m.add_class::<PySpaceRock>()?;
     m.add_class::<RockCollection>()?;
@@ -3,6 +3,8 @@ use pyo3::types::PyType;
 
 use spacerocks::ReferencePlane;
 
+use crate::py_time::time::PyTime;
+
 #[pyclass]
 #[pyo3(name = "ReferencePlane")]
 #[derive(Clone, Debug, PartialEq)]
@@ -54,4 +56,60 @@ impl PyReferencePlane {
         }
     }
 
+    #[classmethod]
+    fn mean_of_date(_cls: Py<PyType>, epoch: PyRef<PyTime>) -> Self {
+        PyReferencePlane {
+            inner: ReferencePlane::MeanOfDate(epoch.inner.clone()),
+        }
+    }
+
+    #[classmethod]
+    fn true_of_date(_cls: Py<PyType>, epoch: PyRef<PyTime>) -> Self {
+        PyReferencePlane {
+            inner: ReferencePlane::TrueOfDate(epoch.inner.clone()),
+        }
+    }
+
+    #[classmethod]
+    fn cirs(_cls: Py<PyType>, epoch: PyRef<PyTime>) -> Self {
+        PyReferencePlane {
+            inner: ReferencePlane::CIRS(epoch.inner.clone()),
+        }
+    }
+
+    #[classmethod]
+    fn tirs(_cls: Py<PyType>, epoch: PyRef<PyTime>) -> Self {
+        PyReferencePlane {
+            inner: ReferencePlane::TIRS(epoch.inner.clone()),
+        }
+    }
+
+    #[classmethod]
+    fn ecliptic_of_date(_cls: Py<PyType>, epoch: PyRef<PyTime>) -> Self {
+        PyReferencePlane {
+            inner: ReferencePlane::EclipticOfDate(epoch.inner.clone()),
+        }
+    }
+
+    #[classmethod]
+    fn itrs(_cls: Py<PyType>, epoch: PyRef<PyTime>) -> Self {
+        PyReferencePlane {
+            inner: ReferencePlane::ITRS(epoch.inner.clone()),
+        }
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn get_rotation_matrix(&self) -> Vec<Vec<f64>> {
+        let m = self.inner.get_rotation_matrix();
+        (0..3).map(|i| (0..3).map(|j| m[(i, j)]).collect()).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ReferencePlane({})", self.inner)
+    }
+
 }
\ No newline at end of file
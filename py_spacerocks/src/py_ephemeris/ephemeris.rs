@@ -0,0 +1,90 @@
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+use pyo3::exceptions::PyValueError;
+
+use rayon::prelude::*;
+use numpy::{PyArray1, IntoPyArray};
+
+use spacerocks::Ephemeris;
+
+use crate::PySpaceRock;
+use crate::spacerock::correction_level;
+use crate::py_time::time::PyTime;
+use crate::py_observing::observer::PyObserver;
+use crate::py_observing::observatory::PyObservatory;
+use crate::py_observing::observation::PyObservation;
+
+#[pyclass]
+#[pyo3(name = "Ephemeris")]
+pub struct PyEphemeris {
+    pub inner: Ephemeris,
+}
+
+#[pymethods]
+impl PyEphemeris {
+
+    /// Fit an ephemeris to the two-body orbit of a SpaceRock between two epochs, to within
+    /// `tolerance` au.
+    #[classmethod]
+    #[pyo3(signature = (rock, start, end, tolerance=1e-9))]
+    fn from_spacerock(_cls: Py<PyType>, rock: PyRef<PySpaceRock>, start: PyRef<PyTime>, end: PyRef<PyTime>, tolerance: f64) -> PyResult<Self> {
+        let ephemeris = Ephemeris::from_spacerock(&rock.inner, &start.inner, &end.inner, tolerance).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyEphemeris { inner: ephemeris })
+    }
+
+    /// Fit an ephemeris to an observatory between two epochs, to within `tolerance` au.
+    #[classmethod]
+    #[pyo3(signature = (observatory, start, end, reference_plane="J2000", origin="SSB", tolerance=1e-9))]
+    fn from_observatory(_cls: Py<PyType>, observatory: PyRef<PyObservatory>, start: PyRef<PyTime>, end: PyRef<PyTime>, reference_plane: &str, origin: &str, tolerance: f64) -> PyResult<Self> {
+        let ephemeris = Ephemeris::from_observatory(&observatory.inner, &start.inner, &end.inner, reference_plane, origin, tolerance).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyEphemeris { inner: ephemeris })
+    }
+
+    /// The SpaceRock at an epoch.
+    fn at(&self, epoch: PyRef<PyTime>) -> PyResult<PySpaceRock> {
+        let rock = self.inner.at(&epoch.inner).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PySpaceRock { inner: rock })
+    }
+
+    /// The astrometric right ascensions and declinations (radians) seen from an observer with its
+    /// own ephemeris, at TDB Julian dates.
+    fn calc_radec(&self, py: Python, observer: PyRef<PyEphemeris>, epochs: Vec<f64>) -> PyResult<(Py<PyArray1<f64>>, Py<PyArray1<f64>>)> {
+        let (body, observer) = (&self.inner, &observer.inner);
+        let radec = epochs.par_iter()
+            .map(|epoch| body.calc_radec(observer, *epoch).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PyValueError::new_err)?;
+        let ra: Vec<f64> = radec.iter().map(|(ra, _)| *ra).collect();
+        let dec: Vec<f64> = radec.iter().map(|(_, dec)| *dec).collect();
+        Ok((ra.into_pyarray(py).to_owned().into(), dec.into_pyarray(py).to_owned().into()))
+    }
+
+    /// Observe the body, propagating it with the ephemeris.
+    #[pyo3(signature = (observer, correction="astrometric", pressure=None, temperature=10.0, relative_humidity=0.5, wavelength=0.55))]
+    fn observe(&self, observer: PyRef<PyObserver>, correction: &str, pressure: Option<f64>, temperature: f64, relative_humidity: f64, wavelength: f64) -> PyResult<PyObservation> {
+        let correction = correction_level(correction, &observer, pressure, temperature, relative_humidity, wavelength)?;
+        let observation = self.inner.observe(&observer.inner, &correction).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyObservation { inner: observation })
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.name.clone()
+    }
+
+    /// The start of the span, as a TDB Julian date.
+    #[getter]
+    fn start(&self) -> f64 {
+        self.inner.start()
+    }
+
+    /// The end of the span, as a TDB Julian date.
+    #[getter]
+    fn end(&self) -> f64 {
+        self.inner.end()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Ephemeris: {} from JD {} to {} TDB in {} segments", self.inner.name, self.inner.start(), self.inner.end(), self.inner.segments.len())
+    }
+}
@@ -0,0 +1,16 @@
+use pyo3::prelude::*;
+
+pub mod ephemeris;
+
+pub fn make_ephemeris_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let submodule = PyModule::new(py, "ephemeris")?;
+
+    submodule.add_class::<ephemeris::PyEphemeris>()?;
+
+    m.add_submodule(&submodule)?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("spacerocks.ephemeris", submodule.clone())?;
+    submodule.setattr("__name__", "spacerocks.ephemeris")?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use pyo3::prelude::*;
+
+use spacerocks::nbody::CloseApproach;
+
+use crate::py_time::time::PyTime;
+
+#[pyclass]
+#[pyo3(name = "CloseApproach")]
+pub struct PyCloseApproach {
+    pub inner: CloseApproach,
+}
+
+#[pymethods]
+impl PyCloseApproach {
+
+    #[getter]
+    pub fn body(&self) -> String {
+        self.inner.body.clone()
+    }
+
+    #[getter]
+    pub fn epoch(&self) -> PyTime {
+        PyTime { inner: self.inner.epoch.clone() }
+    }
+
+    #[getter]
+    pub fn distance(&self) -> f64 {
+        self.inner.distance
+    }
+
+    #[getter]
+    pub fn relative_velocity(&self) -> f64 {
+        self.inner.relative_velocity
+    }
+
+    #[getter]
+    pub fn v_infinity(&self) -> f64 {
+        self.inner.v_infinity
+    }
+
+    #[getter]
+    pub fn impact_parameter(&self) -> f64 {
+        self.inner.impact_parameter
+    }
+
+    #[getter]
+    pub fn xi(&self) -> f64 {
+        self.inner.xi
+    }
+
+    #[getter]
+    pub fn zeta(&self) -> f64 {
+        self.inner.zeta
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("CloseApproach(body: {}, epoch: {}, distance: {}, relative_velocity: {}, xi: {}, zeta: {})",
+                self.inner.body, self.inner.epoch, self.inner.distance, self.inner.relative_velocity, self.inner.xi, self.inner.zeta)
+    }
+
+}
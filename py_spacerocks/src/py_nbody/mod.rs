@@ -3,9 +3,11 @@ use pyo3::prelude::*;
 pub mod simulation;
 pub mod integrator;
 pub mod force;
+pub mod close_approach;
 
 use crate::py_nbody::integrator::PyIntegrator;
 use crate::py_nbody::force::PyForce;
+use crate::py_nbody::close_approach::PyCloseApproach;
 
 
 pub fn make_nbody_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -14,6 +16,7 @@ pub fn make_nbody_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()>
     submodule.add_class::<simulation::PySimulation>()?;
     submodule.add_class::<PyIntegrator>()?;
     submodule.add_class::<PyForce>()?;
+    submodule.add_class::<PyCloseApproach>()?;
 
     m.add_submodule(&submodule)?;
     py.import("sys")?
@@ -4,12 +4,18 @@ use pyo3::types::PyType;
 use spacerocks::nbody::Simulation;
 use spacerocks::coordinates::{ReferencePlane, Origin};
 use spacerocks::Time;   
+use spacerocks::dynamics::calc_impact_probability;
 
 use crate::PySpaceRock;
+use crate::spacerock::correction_level;
+use crate::py_observing::observer::PyObserver;
+use crate::py_observing::observation::PyObservation;
 // use crate::py_spacerock::rockcollection::RockCollection;
+use crate::rockcollection::RockCollection;
 use crate::py_time::time::PyTime;
 use crate::py_nbody::integrator::PyIntegrator;
 use crate::py_nbody::force::PyForce;
+use crate::py_nbody::close_approach::PyCloseApproach;
 use crate::py_coordinates::origin::PyOrigin;
 
 #[pyclass]
@@ -127,6 +133,49 @@ impl PySimulation {
         self.inner.integrate(&epoch.inner.clone());
     }
     
+    /// Integrate the simulation to a specific epoch, recording the close approaches of a particle to a body.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch to integrate to.
+    /// * `name` - The name of the particle.
+    /// * `body` - The name of the body being approached.
+    /// * `threshold` - The maximum distance of the approaches to report (au).
+    ///
+    /// # Returns
+    ///
+    /// * `list[CloseApproach]` - The close approaches, in the order they occurred.
+    pub fn close_approaches(&mut self, epoch: &PyTime, name: &str, body: &str, threshold: f64) -> PyResult<Vec<PyCloseApproach>> {
+        match self.inner.close_approaches(&epoch.inner, name, body, threshold) {
+            Ok(approaches) => Ok(approaches.into_iter().map(|inner| PyCloseApproach { inner }).collect()),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+        }
+    }
+    
+    /// Estimate the probability that a collection of virtual asteroids impacts a body in the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `virtual_asteroids` - The virtual asteroids, at the epoch of the simulation.
+    /// * `epoch` - The epoch to integrate to.
+    /// * `body` - The name of the body being impacted.
+    /// * `radius` - The physical radius of the body (au).
+    ///
+    /// # Returns
+    ///
+    /// * `(float, list[tuple])` - The impact probability, and the (name, epoch, distance, impact_parameter, capture_radius) of each impactor.
+    pub fn impact_probability(&self, virtual_asteroids: &RockCollection, epoch: &PyTime, body: &str, radius: f64) -> PyResult<(f64, Vec<(String, PyTime, f64, f64, f64)>)> {
+        match calc_impact_probability(&self.inner, &virtual_asteroids.rocks, &epoch.inner, body, radius) {
+            Ok(result) => {
+                let impactors = result.impactors.into_iter()
+                    .map(|i| (i.name, PyTime { inner: i.epoch }, i.distance, i.impact_parameter, i.capture_radius))
+                    .collect();
+                Ok((result.probability, impactors))
+            },
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+        }
+    }
+
     /// Step the simulation by one timestep.
     pub fn step(&mut self) {
         self.inner.step();
@@ -217,6 +266,17 @@ impl PySimulation {
         self.inner.energy()
     }
 
+    /// Observe a particle of the simulation, integrating it back to the epoch at which the light
+    /// was emitted. The simulation is integrated to the epoch of the observer.
+    #[pyo3(signature = (name, observer, correction="astrometric", pressure=None, temperature=10.0, relative_humidity=0.5, wavelength=0.55))]
+    pub fn observe(&mut self, name: &str, observer: PyRef<PyObserver>, correction: &str, pressure: Option<f64>, temperature: f64, relative_humidity: f64, wavelength: f64) -> PyResult<PyObservation> {
+        let correction = correction_level(correction, &observer, pressure, temperature, relative_humidity, wavelength)?;
+        match self.inner.observe(name, &observer.inner, &correction) {
+            Ok(obs) => Ok(PyObservation { inner: obs }),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to observe {}: {}", name, e))),
+        }
+    }
+
     /// Get a single particle from the simulation by name.
     pub fn get_particle(&self, name: &str) -> PyResult<PySpaceRock> {
         let rock = self.inner.get_particle(name);
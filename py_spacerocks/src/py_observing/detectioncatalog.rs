@@ -1,31 +1,25 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
 use rayon::prelude::*;
 
-use spacerocks::Detection;
+use spacerocks::Observation;
 
-use pyo3::exceptions::PyIndexError;
+use pyo3::exceptions::{PyIndexError, PyValueError};
 
 use numpy::{PyArray1, IntoPyArray};
 
-use crate::py_observing::detection::PyDetection;
+use crate::py_observing::observation::PyObservation;
 
-pub fn create_mixed_array<T: pyo3::ToPyObject>(data: Vec<Option<T>>, py: Python) -> PyResult<Py<PyArray1<PyObject>>> {
-
-    let numpy_array: Vec<_> = data.into_iter()
-            .map(|opt| match opt {
-                    Some(value) => value.to_object(py),
-                    None => py.None(),
-                }
-            ).collect();
-
-    Ok(numpy_array.into_pyarray(py).to_owned())
-    
+/// Collect an optional quantity into an array, with NaN where it is missing.
+fn optional_array(data: Vec<Option<f64>>, py: Python) -> Py<PyArray1<f64>> {
+    let values: Vec<f64> = data.into_iter().map(|value| value.unwrap_or(f64::NAN)).collect();
+    values.into_pyarray(py).to_owned().into()
 }
 
 #[pyclass]
 pub struct DetectionCatalog {
-   pub observations: Vec<Detection>,
+   pub observations: Vec<Observation>,
 }
 
 #[pymethods]
@@ -36,21 +30,21 @@ impl DetectionCatalog {
         DetectionCatalog { observations: Vec::new() }
     }
 
-    pub fn add(&mut self, observation: PyRef<PyDetection>) -> Result<(), PyErr> {
+    pub fn add(&mut self, observation: PyRef<PyObservation>) -> Result<(), PyErr> {
         self.observations.push(observation.inner.clone());
         Ok(())
     }
 
     // make indexable
-    fn __getitem__(&self, index: usize) -> PyResult<PyDetection> {
+    fn __getitem__(&self, index: usize) -> PyResult<PyObservation> {
         if index < self.observations.len() {
-            Ok(PyDetection { inner: self.observations[index].clone() })
+            Ok(PyObservation { inner: self.observations[index].clone() })
         } else {
             Err(PyIndexError::new_err("Index out of range!"))
         }
     }
 
-    pub fn len(&self) -> usize {
+    pub fn __len__(&self) -> usize {
         self.observations.len()
     }
 
@@ -60,64 +54,90 @@ impl DetectionCatalog {
 
     #[getter]
     pub fn ra(&self, py: Python) -> Py<PyArray1<f64>> {
-        let ra: Vec<f64> = self.observations.par_iter().map(|obs| obs.ra).collect();
-        ra.into_pyarray(py).to_owned()
+        let ra: Vec<f64> = self.observations.par_iter().map(|obs| obs.ra()).collect();
+        ra.into_pyarray(py).to_owned().into()
     }
 
     #[getter]
     pub fn dec(&self, py: Python) -> Py<PyArray1<f64>> {
-        let dec: Vec<f64> = self.observations.par_iter().map(|obs| obs.dec).collect();
-        dec.into_pyarray(py).to_owned()
+        let dec: Vec<f64> = self.observations.par_iter().map(|obs| obs.dec()).collect();
+        dec.into_pyarray(py).to_owned().into()
     }
 
     #[getter]
-    pub fn ra_rate(&self, py: Python) -> PyResult<Py<PyArray1<PyObject>>> {
-        let ra_rates: Vec<Option<f64>> = self.observations.par_iter().map(|obs| obs.ra_rate).collect();
-        create_mixed_array(ra_rates, py)
+    pub fn ra_rate(&self, py: Python) -> Py<PyArray1<f64>> {
+        optional_array(self.observations.par_iter().map(|obs| obs.ra_rate()).collect(), py)
     }
 
     #[getter]
-    pub fn dec_rate(&self, py: Python) -> PyResult<Py<PyArray1<PyObject>>> {
-        let dec_rates: Vec<Option<f64>> = self.observations.par_iter().map(|obs| obs.dec_rate).collect();
-        create_mixed_array(dec_rates, py)
+    pub fn dec_rate(&self, py: Python) -> Py<PyArray1<f64>> {
+        optional_array(self.observations.par_iter().map(|obs| obs.dec_rate()).collect(), py)
     }
 
     #[getter]
-    pub fn rho(&self, py: Python) -> PyResult<Py<PyArray1<PyObject>>> {
-        let rhos: Vec<Option<f64>> = self.observations.par_iter().map(|obs| obs.rho).collect();
-        create_mixed_array(rhos, py)
+    pub fn rho(&self, py: Python) -> Py<PyArray1<f64>> {
+        optional_array(self.observations.par_iter().map(|obs| obs.range()).collect(), py)
     }
 
     #[getter]
-    pub fn rho_rate(&self, py: Python) -> PyResult<Py<PyArray1<PyObject>>> {
-        let rho_rates: Vec<Option<f64>> = self.observations.par_iter().map(|obs| obs.rho_rate).collect();
-        create_mixed_array(rho_rates, py)
+    pub fn rho_rate(&self, py: Python) -> Py<PyArray1<f64>> {
+        optional_array(self.observations.par_iter().map(|obs| obs.range_rate()).collect(), py)
     }
 
     #[getter]
-    pub fn mag(&self, py: Python) -> PyResult<Py<PyArray1<PyObject>>> {
-        let mags: Vec<Option<f64>> = self.observations.par_iter().map(|obs| obs.mag).collect();
-        create_mixed_array(mags, py)
+    pub fn mag(&self, py: Python) -> Py<PyArray1<f64>> {
+        optional_array(self.observations.par_iter().map(|obs| obs.mag()).collect(), py)
+    }
+
+    /// The bands of the magnitudes, or None where they are unknown.
+    #[getter]
+    pub fn band(&self) -> Vec<Option<String>> {
+        self.observations.iter().map(|obs| obs.band.map(|band| band.as_str().to_string())).collect()
     }
 
     #[getter]
     pub fn epoch(&self, py: Python) -> Py<PyArray1<f64>> {
-        let epochs: Vec<f64> = self.observations.par_iter().map(|obs| obs.epoch.epoch).collect();
-        epochs.into_pyarray(py).to_owned()
+        let epochs: Vec<f64> = self.observations.par_iter().map(|obs| obs.epoch.jd()).collect();
+        epochs.into_pyarray(py).to_owned().into()
     }
 
+    /// The geometric altitudes and azimuths (radians) of the detections, which must all be from
+    /// ground observatories.
     pub fn calc_altaz(&self, py: Python) -> PyResult<(Py<PyArray1<f64>>, Py<PyArray1<f64>>)> {
-        
-        // process the altaz in parallel, but hanfle errors
-        let altaz: Vec<_> = self.observations.par_iter().map(|obs| obs.calc_altaz().unwrap()).collect();
-        // let mut alts = altaz.par_iter().map(|(alt, az)| *alt).collect::<Vec<f64>>();
-        // let mut azs = altaz.par_iter().map(|(alt, az)| *az).collect::<Vec<f64>>();
+        let altaz = self.observations.par_iter()
+            .map(|obs| obs.calc_altaz().map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PyValueError::new_err)?;
         let alts: Vec<_> = altaz.par_iter().map(|(alt, _az)| *alt).collect();
         let azs: Vec<_> = altaz.par_iter().map(|(_alt, az)| *az).collect();
-        
-        Ok((alts.into_pyarray(py).to_owned(), azs.into_pyarray(py).to_owned()))
+
+        Ok((alts.into_pyarray(py).to_owned().into(), azs.into_pyarray(py).to_owned().into()))
     }
-    
 
+    /// The geometry of the detections, as a dict of arrays of the distances (au) and angles
+    /// (radians), for filtering. The detections must all have ranges.
+    pub fn calc_geometry(&self, py: Python) -> PyResult<Py<PyDict>> {
+        // the Sun and the Moon come from SPICE, which is not thread-safe
+        let geometries = self.observations.iter()
+            .map(|obs| obs.calc_geometry().map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PyValueError::new_err)?;
+
+        let dict = PyDict::new(py);
+        let column = |f: fn(&spacerocks::observing::Geometry) -> f64| -> Py<PyArray1<f64>> {
+            let values: Vec<f64> = geometries.iter().map(f).collect();
+            values.into_pyarray(py).to_owned().into()
+        };
+        dict.set_item("heliocentric_distance", column(|g| g.heliocentric_distance))?;
+        dict.set_item("observer_distance", column(|g| g.observer_distance))?;
+        dict.set_item("phase_angle", column(|g| g.phase_angle))?;
+        dict.set_item("solar_elongation", column(|g| g.solar_elongation))?;
+        dict.set_item("lunar_elongation", optional_array(geometries.iter().map(|g| g.lunar_elongation).collect(), py))?;
+        dict.set_item("galactic_latitude", column(|g| g.galactic_latitude))?;
+        dict.set_item("sun_position_angle", column(|g| g.sun_position_angle))?;
+        dict.set_item("velocity_position_angle", column(|g| g.velocity_position_angle))?;
+        dict.set_item("out_of_plane_angle", column(|g| g.out_of_plane_angle))?;
+        Ok(dict.into())
+    }
 
 }
@@ -3,6 +3,8 @@ use pyo3::prelude::*;
 pub mod observatory;
 pub mod observer;
 pub mod observation;
+pub mod obscodes;
+pub mod detectioncatalog;
 
 pub fn make_observing_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     let submodule = PyModule::new(py, "observing")?;
@@ -10,6 +12,9 @@ pub fn make_observing_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult
     submodule.add_class::<observatory::PyObservatory>()?;
     submodule.add_class::<observer::PyObserver>()?;
     submodule.add_class::<observation::PyObservation>()?;
+    submodule.add_class::<detectioncatalog::DetectionCatalog>()?;
+    submodule.add_function(wrap_pyfunction!(obscodes::load_obscodes_py, submodule.clone())?)?;
+    submodule.add_function(wrap_pyfunction!(obscodes::obscode_py, submodule.clone())?)?;
 
     m.add_submodule(&submodule)?;
     py.import("sys")?
@@ -0,0 +1,24 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use spacerocks::observing::{load_obscodes, get_obscode, SiteKind};
+
+#[pyfunction]
+#[pyo3(name = "load_obscodes")]
+pub fn load_obscodes_py(path: &str) -> PyResult<()> {
+    load_obscodes(path).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// An observatory code from the loaded or built-in table, as (name, east longitude [rad], rho cos(lat), rho sin(lat), kind),
+/// where kind is "ground", "space" or "roving".
+#[pyfunction]
+#[pyo3(name = "obscode")]
+pub fn obscode_py(code: &str) -> PyResult<(String, f64, f64, f64, String)> {
+    let site = get_obscode(code).ok_or_else(|| PyValueError::new_err(format!("Observatory not found: {}", code)))?;
+    let kind = match site.kind {
+        SiteKind::Ground => "ground",
+        SiteKind::Space => "space",
+        SiteKind::Roving => "roving",
+    };
+    Ok((site.name, site.lon, site.rho_cos_lat, site.rho_sin_lat, kind.to_string()))
+}
@@ -1,10 +1,12 @@
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+use pyo3::types::{PyDict, PyType};
 
-use spacerocks::Observation;
+use spacerocks::{Band, Observation};
+use spacerocks::photometry::Colors;
 
 use crate::py_observing::observer::PyObserver;
 use crate::py_time::time::PyTime;
+use crate::spacerock::atmosphere;
 // use crate::py_spacerock::origin::PyOrigin;
 
 #[pyclass]
@@ -78,6 +80,21 @@ impl PyObservation {
         self.inner.mag()
     }
 
+    /// The band of the magnitude, e.g. "V" or "r".
+    #[getter]
+    fn band(&self) -> Option<String> {
+        self.inner.band.map(|band| band.as_str().to_string())
+    }
+
+    /// A copy of the observation in another band, converting the magnitude with colours g-r,
+    /// r-i, i-z and z-y, which default to solar.
+    #[pyo3(signature = (band, g_r=0.44, r_i=0.11, i_z=0.03, z_y=0.02))]
+    fn to_band(&self, band: &str, g_r: f64, r_i: f64, i_z: f64, z_y: f64) -> PyResult<PyObservation> {
+        let band = Band::from_str(band).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let observation = self.inner.to_band(&band, &Colors { g_r, r_i, i_z, z_y }).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(PyObservation { inner: observation })
+    }
+
     #[getter]
     fn epoch(&self) -> PyTime {
         PyTime { inner: self.inner.epoch.clone() }
@@ -88,6 +105,57 @@ impl PyObservation {
         PyObserver { inner: self.inner.observer.clone() }
     }
 
+    /// The altitude and azimuth (radians), seen from a ground observatory. With `refraction`, the
+    /// altitude is raised by the atmosphere; the pressure (hPa) defaults to the standard
+    /// atmosphere at the height of the observatory.
+    #[pyo3(signature = (refraction=false, pressure=None, temperature=10.0, relative_humidity=0.5, wavelength=0.55))]
+    fn calc_altaz(&self, refraction: bool, pressure: Option<f64>, temperature: f64, relative_humidity: f64, wavelength: f64) -> PyResult<(f64, f64)> {
+        let result = if refraction {
+            self.inner.calc_refracted_altaz(&atmosphere(&self.inner.observer, pressure, temperature, relative_humidity, wavelength))
+        } else {
+            self.inner.calc_altaz()
+        };
+        result.map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// The local hour angle (radians), westwards from the meridian.
+    fn calc_hour_angle(&self) -> PyResult<f64> {
+        self.inner.calc_hour_angle().map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// The parallactic angle (radians).
+    fn calc_parallactic_angle(&self) -> PyResult<f64> {
+        self.inner.calc_parallactic_angle().map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// The airmass, from the geometric altitude or, with `refraction`, the refracted one.
+    #[pyo3(signature = (refraction=false, pressure=None, temperature=10.0, relative_humidity=0.5, wavelength=0.55))]
+    fn calc_airmass(&self, refraction: bool, pressure: Option<f64>, temperature: f64, relative_humidity: f64, wavelength: f64) -> PyResult<f64> {
+        let atmosphere = if refraction {
+            Some(atmosphere(&self.inner.observer, pressure, temperature, relative_humidity, wavelength))
+        } else {
+            None
+        };
+        self.inner.calc_airmass(atmosphere.as_ref()).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// The geometry of the observation, as a dict of the distances (au) and angles (radians). The
+    /// observation must have a range, and the Sun and the Moon come from SPICE.
+    fn calc_geometry(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let geometry = self.inner.calc_geometry().map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let dict = PyDict::new(py);
+        dict.set_item("heliocentric_distance", geometry.heliocentric_distance)?;
+        dict.set_item("observer_distance", geometry.observer_distance)?;
+        dict.set_item("phase_angle", geometry.phase_angle)?;
+        dict.set_item("solar_elongation", geometry.solar_elongation)?;
+        dict.set_item("lunar_elongation", geometry.lunar_elongation)?;
+        dict.set_item("galactic_latitude", geometry.galactic_latitude)?;
+        dict.set_item("sun_position_angle", geometry.sun_position_angle)?;
+        dict.set_item("velocity_position_angle", geometry.velocity_position_angle)?;
+        dict.set_item("out_of_plane_angle", geometry.out_of_plane_angle)?;
+        Ok(dict.into())
+    }
+
 
     
 
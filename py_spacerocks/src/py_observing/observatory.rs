@@ -1,11 +1,14 @@
 use pyo3::prelude::*;
 use pyo3::types::PyType;
 use pyo3::exceptions::PyValueError;
+use nalgebra::Vector3;
 
-use spacerocks::Observatory;
+use spacerocks::{Observatory, ReferencePlane};
 
 use crate::py_observing::observer::PyObserver;
 use crate::py_time::time::PyTime;
+use crate::spacerock::PySpaceRock;
+use crate::py_nbody::simulation::PySimulation;
 // use crate::py_spacerock::origin::PyOrigin;
 
 #[pyclass]
@@ -26,6 +29,33 @@ impl PyObservatory {
         }
     }
 
+    #[classmethod]
+    #[pyo3(signature = (lon, lat, height=0.0, name="custom"))]
+    fn from_geodetic(_cls: Py<PyType>, lon: f64, lat: f64, height: f64, name: &str) -> Self {
+        PyObservatory { inner: Observatory::from_geodetic(name, lon, lat, height) }
+    }
+
+    /// An observatory carried by a SpaceRock, such as a spacecraft, propagated to each epoch.
+    #[classmethod]
+    fn from_spacerock(_cls: Py<PyType>, rock: PyRef<PySpaceRock>) -> Self {
+        PyObservatory { inner: Observatory::from_spacerock(rock.inner.clone()) }
+    }
+
+    /// An observatory at a fixed geocentric J2000 position (au), as reported for a satellite observation.
+    #[classmethod]
+    fn from_satellite_position(_cls: Py<PyType>, obscode: &str, x: f64, y: f64, z: f64) -> Self {
+        PyObservatory { inner: Observatory::from_satellite_position(obscode, Vector3::new(x, y, z)) }
+    }
+
+    /// An observatory from the second line of an 80-column MPC satellite or roving observation.
+    #[classmethod]
+    fn from_mpc_second_line(_cls: Py<PyType>, line: &str) -> PyResult<Self> {
+        match Observatory::from_mpc_second_line(line) {
+            Ok(o) => Ok(PyObservatory { inner: o }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
     #[pyo3(signature = (epoch, reference_plane="J2000", origin="SSB"))]
     fn at(&self, epoch: &PyTime, reference_plane: &str, origin: &str) -> PyResult<PyObserver> {
         let ep = &epoch.inner;
@@ -35,6 +65,31 @@ impl PyObservatory {
         }
     }
 
+    /// Like `at`, but a SpaceRock observatory is integrated through the simulation rather than
+    /// propagated on a two-body orbit.
+    #[pyo3(signature = (epoch, simulation, reference_plane="J2000", origin="SSB"))]
+    fn at_with_simulation(&self, epoch: &PyTime, simulation: PyRef<PySimulation>, reference_plane: &str, origin: &str) -> PyResult<PyObserver> {
+        match self.inner.at_with_simulation(&epoch.inner, &simulation.inner, reference_plane, origin) {
+            Ok(o) => Ok(PyObserver { inner: o }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    /// The position (au) and velocity (au/day) of a ground observatory relative to the geocenter,
+    /// from the native model of the Earth's rotation.
+    #[pyo3(signature = (epoch, reference_plane="J2000"))]
+    fn geocentric_state(&self, epoch: &PyTime, reference_plane: &str) -> PyResult<(Vec<f64>, Vec<f64>)> {
+        let reference_plane = ReferencePlane::from_str_at(reference_plane, &epoch.inner).map_err(PyValueError::new_err)?;
+        match self.inner.geocentric_state(&epoch.inner, &reference_plane) {
+            Ok((position, velocity)) => Ok((position.iter().copied().collect(), velocity.iter().copied().collect())),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    fn tdb(&self, epoch: &PyTime) -> PyTime {
+        PyTime { inner: self.inner.tdb(&epoch.inner) }
+    }
+
     #[getter]
     fn lat(&self) -> Option<f64> {
         self.inner.lat()
@@ -50,5 +105,15 @@ impl PyObservatory {
         self.inner.rho()
     }
 
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    #[getter]
+    fn geodetic(&self) -> Option<(f64, f64, f64)> {
+        self.inner.geodetic()
+    }
+
 
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+use pyo3::exceptions::PyValueError;
+
+use spacerocks::HDistribution;
+
+#[pyclass]
+#[pyo3(name = "HDistribution")]
+pub struct PyHDistribution {
+    pub inner: HDistribution,
+}
+
+#[pymethods]
+impl PyHDistribution {
+
+    /// A power law, dN/dH ∝ 10^(slope H), between two absolute magnitudes.
+    #[classmethod]
+    fn power_law(_cls: Py<PyType>, slope: f64, h_min: f64, h_max: f64) -> PyResult<Self> {
+        let distribution = HDistribution::power_law(slope, h_min, h_max).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyHDistribution { inner: distribution })
+    }
+
+    /// A power law whose slope changes at the breaks, with one more slope than breaks.
+    #[classmethod]
+    fn broken_power_law(_cls: Py<PyType>, slopes: Vec<f64>, breaks: Vec<f64>, h_min: f64, h_max: f64) -> PyResult<Self> {
+        let distribution = HDistribution::broken_power_law(slopes, breaks, h_min, h_max).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyHDistribution { inner: distribution })
+    }
+
+    /// The fraction of the distribution brighter than an absolute magnitude.
+    fn fraction_brighter(&self, h: f64) -> f64 {
+        self.inner.fraction_brighter(h)
+    }
+
+    #[getter]
+    fn h_min(&self) -> f64 {
+        self.inner.h_min
+    }
+
+    #[getter]
+    fn h_max(&self) -> f64 {
+        self.inner.h_max
+    }
+
+    #[getter]
+    fn slopes(&self) -> Vec<f64> {
+        self.inner.slopes.clone()
+    }
+
+    #[getter]
+    fn breaks(&self) -> Vec<f64> {
+        self.inner.breaks.clone()
+    }
+}
@@ -0,0 +1,18 @@
+use pyo3::prelude::*;
+
+pub mod h_distribution;
+pub mod population;
+
+pub fn make_population_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let submodule = PyModule::new(py, "population")?;
+
+    submodule.add_class::<h_distribution::PyHDistribution>()?;
+    submodule.add_class::<population::PyPopulation>()?;
+
+    m.add_submodule(&submodule)?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("spacerocks.population", submodule.clone())?;
+    submodule.setattr("__name__", "spacerocks.population")?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+use pyo3::exceptions::PyValueError;
+
+use spacerocks::population::{Histogram, Perturber, Population};
+use spacerocks::population::{NearEarthObjects, MainBelt, JupiterTrojans, ClassicalTnos, ResonantTnos, ScatteredTnos};
+
+use crate::rockcollection::RockCollection;
+use crate::py_time::time::PyTime;
+use crate::py_population::h_distribution::PyHDistribution;
+
+#[pyclass]
+#[pyo3(name = "Population")]
+pub struct PyPopulation {
+    pub inner: Population,
+}
+
+#[pymethods]
+impl PyPopulation {
+
+    /// Near-Earth objects from a histogram, with the edges of its bins in (a, e, inc) or
+    /// (a, e, inc, H) and the weight of each bin, last axis fastest. Without a histogram, a coarse
+    /// stand-in for the Granvik et al. (2018) model is used.
+    #[classmethod]
+    #[pyo3(signature = (edges=None, weights=None))]
+    fn neo(_cls: Py<PyType>, edges: Option<Vec<Vec<f64>>>, weights: Option<Vec<f64>>) -> PyResult<Self> {
+        let neos = match (edges, weights) {
+            (Some(edges), Some(weights)) => {
+                let histogram = Histogram::new(edges, weights).map_err(|e| PyValueError::new_err(e.to_string()))?;
+                NearEarthObjects { histogram }
+            },
+            (None, None) => NearEarthObjects::default(),
+            _ => return Err(PyValueError::new_err("Give both the edges and the weights of a histogram, or neither")),
+        };
+        Ok(PyPopulation { inner: Population::NearEarthObjects(neos) })
+    }
+
+    /// Main-belt asteroids, uniform in a, with normally distributed e.
+    #[classmethod]
+    #[pyo3(signature = (a_min=None, a_max=None, e_mean=None, e_width=None, inc_width=None))]
+    fn main_belt(_cls: Py<PyType>, a_min: Option<f64>, a_max: Option<f64>, e_mean: Option<f64>, e_width: Option<f64>, inc_width: Option<f64>) -> Self {
+        let default = MainBelt::default();
+        let belt = MainBelt {
+            a_min: a_min.unwrap_or(default.a_min),
+            a_max: a_max.unwrap_or(default.a_max),
+            e_mean: e_mean.unwrap_or(default.e_mean),
+            e_width: e_width.unwrap_or(default.e_width),
+            inc_width: inc_width.unwrap_or(default.inc_width),
+        };
+        PyPopulation { inner: Population::MainBelt(belt) }
+    }
+
+    /// Jupiter trojans, librating about L4 and L5.
+    #[classmethod]
+    #[pyo3(signature = (l4_fraction=None, max_amplitude=None, e_max=None, inc_width=None))]
+    fn jupiter_trojans(_cls: Py<PyType>, l4_fraction: Option<f64>, max_amplitude: Option<f64>, e_max: Option<f64>, inc_width: Option<f64>) -> Self {
+        let default = JupiterTrojans::default();
+        let trojans = JupiterTrojans {
+            l4_fraction: l4_fraction.unwrap_or(default.l4_fraction),
+            max_amplitude: max_amplitude.unwrap_or(default.max_amplitude),
+            e_max: e_max.unwrap_or(default.e_max),
+            inc_width: inc_width.unwrap_or(default.inc_width),
+        };
+        PyPopulation { inner: Population::JupiterTrojans(trojans) }
+    }
+
+    /// Classical TNOs, a mix of a cold and a hot component.
+    #[classmethod]
+    #[pyo3(signature = (a_min=None, a_max=None, e_max=None, cold_fraction=None, cold_inc_width=None, hot_inc_width=None))]
+    fn classical_tnos(_cls: Py<PyType>, a_min: Option<f64>, a_max: Option<f64>, e_max: Option<f64>, cold_fraction: Option<f64>, cold_inc_width: Option<f64>, hot_inc_width: Option<f64>) -> Self {
+        let default = ClassicalTnos::default();
+        let classicals = ClassicalTnos {
+            a_min: a_min.unwrap_or(default.a_min),
+            a_max: a_max.unwrap_or(default.a_max),
+            e_max: e_max.unwrap_or(default.e_max),
+            cold_fraction: cold_fraction.unwrap_or(default.cold_fraction),
+            cold_inc_width: cold_inc_width.unwrap_or(default.cold_inc_width),
+            hot_inc_width: hot_inc_width.unwrap_or(default.hot_inc_width),
+        };
+        PyPopulation { inner: Population::ClassicalTnos(classicals) }
+    }
+
+    /// Objects in the p:q resonance with a planet, librating about `center`. The defaults are
+    /// the plutinos, in the 3:2 resonance with Neptune.
+    #[classmethod]
+    #[pyo3(signature = (perturber=None, p=None, q=None, center=None, max_amplitude=None, e_min=None, e_max=None, inc_width=None))]
+    fn resonant_tnos(_cls: Py<PyType>, perturber: Option<&str>, p: Option<u32>, q: Option<u32>, center: Option<f64>, max_amplitude: Option<f64>, e_min: Option<f64>, e_max: Option<f64>, inc_width: Option<f64>) -> PyResult<Self> {
+        let default = ResonantTnos::default();
+        let perturber = match perturber {
+            Some(perturber) => Perturber::from_str(perturber).map_err(|e| PyValueError::new_err(e.to_string()))?,
+            None => default.perturber,
+        };
+        let resonants = ResonantTnos {
+            perturber,
+            p: p.unwrap_or(default.p),
+            q: q.unwrap_or(default.q),
+            center: center.unwrap_or(default.center),
+            max_amplitude: max_amplitude.unwrap_or(default.max_amplitude),
+            e_min: e_min.unwrap_or(default.e_min),
+            e_max: e_max.unwrap_or(default.e_max),
+            inc_width: inc_width.unwrap_or(default.inc_width),
+        };
+        Ok(PyPopulation { inner: Population::ResonantTnos(resonants) })
+    }
+
+    /// Scattered TNOs, uniform in q, with dN/da ∝ a^a_slope.
+    #[classmethod]
+    #[pyo3(signature = (q_min=None, q_max=None, a_min=None, a_max=None, a_slope=None, inc_width=None))]
+    fn scattered_tnos(_cls: Py<PyType>, q_min: Option<f64>, q_max: Option<f64>, a_min: Option<f64>, a_max: Option<f64>, a_slope: Option<f64>, inc_width: Option<f64>) -> Self {
+        let default = ScatteredTnos::default();
+        let scattered = ScatteredTnos {
+            q_min: q_min.unwrap_or(default.q_min),
+            q_max: q_max.unwrap_or(default.q_max),
+            a_min: a_min.unwrap_or(default.a_min),
+            a_max: a_max.unwrap_or(default.a_max),
+            a_slope: a_slope.unwrap_or(default.a_slope),
+            inc_width: inc_width.unwrap_or(default.inc_width),
+        };
+        PyPopulation { inner: Population::ScatteredTnos(scattered) }
+    }
+
+    /// Draw `n` rocks at an epoch, heliocentric in the ecliptic of J2000, with absolute magnitudes
+    /// from `h`.
+    #[pyo3(signature = (n, h, epoch, seed=None))]
+    fn sample(&self, n: usize, h: PyRef<PyHDistribution>, epoch: PyRef<PyTime>, seed: Option<u64>) -> PyResult<RockCollection> {
+        let rocks = self.inner.sample(n, &h.inner, &epoch.inner, seed).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(RockCollection { rocks })
+    }
+
+    #[getter]
+    fn kind(&self) -> String {
+        self.inner.as_str().to_string()
+    }
+}
@@ -0,0 +1,76 @@
+use pyo3::prelude::*;
+
+use spacerocks::survey::DetectionModel;
+
+use crate::py_survey::exposure::PyExposure;
+
+#[pyclass]
+#[pyo3(name = "DetectionModel")]
+#[derive(Clone)]
+pub struct PyDetectionModel {
+    pub inner: DetectionModel,
+}
+
+#[pymethods]
+impl PyDetectionModel {
+
+    /// The efficiency of a survey: `fill_factor` times `peak_efficiency` for bright sources, falling
+    /// about the limiting magnitude over `width` magnitudes, for rates between `min_rate` and
+    /// `max_rate` (radians/day). Astrometric errors have a floor of `astrometric_floor` (radians).
+    #[new]
+    #[pyo3(signature = (fill_factor=None, peak_efficiency=None, width=None, min_rate=None, max_rate=None, astrometric_floor=None, trailing_losses=None))]
+    fn new(fill_factor: Option<f64>, peak_efficiency: Option<f64>, width: Option<f64>, min_rate: Option<f64>, max_rate: Option<f64>, astrometric_floor: Option<f64>, trailing_losses: Option<bool>) -> Self {
+        let default = DetectionModel::default();
+        PyDetectionModel {
+            inner: DetectionModel {
+                fill_factor: fill_factor.unwrap_or(default.fill_factor),
+                peak_efficiency: peak_efficiency.unwrap_or(default.peak_efficiency),
+                width: width.unwrap_or(default.width),
+                min_rate: min_rate.unwrap_or(default.min_rate),
+                max_rate: max_rate.unwrap_or(default.max_rate),
+                astrometric_floor: astrometric_floor.unwrap_or(default.astrometric_floor),
+                trailing_losses: trailing_losses.unwrap_or(default.trailing_losses),
+            }
+        }
+    }
+
+    /// The probability of detecting a source of a magnitude and rate (radians/day) in an exposure.
+    fn efficiency(&self, mag: f64, rate: f64, exposure: PyRef<PyExposure>) -> f64 {
+        self.inner.efficiency(mag, rate, &exposure.inner)
+    }
+
+    #[getter]
+    fn fill_factor(&self) -> f64 {
+        self.inner.fill_factor
+    }
+
+    #[getter]
+    fn peak_efficiency(&self) -> f64 {
+        self.inner.peak_efficiency
+    }
+
+    #[getter]
+    fn width(&self) -> f64 {
+        self.inner.width
+    }
+
+    #[getter]
+    fn min_rate(&self) -> f64 {
+        self.inner.min_rate
+    }
+
+    #[getter]
+    fn max_rate(&self) -> f64 {
+        self.inner.max_rate
+    }
+
+    #[getter]
+    fn astrometric_floor(&self) -> f64 {
+        self.inner.astrometric_floor
+    }
+
+    #[getter]
+    fn trailing_losses(&self) -> bool {
+        self.inner.trailing_losses
+    }
+}
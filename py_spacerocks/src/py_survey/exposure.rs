@@ -0,0 +1,77 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use spacerocks::Band;
+use spacerocks::observing::Field;
+use spacerocks::survey::Exposure;
+
+use crate::py_time::time::PyTime;
+
+#[pyclass]
+#[pyo3(name = "Exposure")]
+#[derive(Clone)]
+pub struct PyExposure {
+    pub inner: Exposure,
+}
+
+#[pymethods]
+impl PyExposure {
+
+    /// An exposure pointed at (ra, dec) in J2000, taken from the observatory with an MPC code.
+    /// The footprint is a circle of `radius`, a rectangle of `width` and `height`, or a polygon of
+    /// `vertices` (x, y) in the tangent plane, all in radians, turned by `rotation` east of north.
+    /// The seeing is the FWHM of the point spread function (radians), and the exposure time is
+    /// in seconds.
+    #[new]
+    #[pyo3(signature = (epoch, ra, dec, band, limiting_magnitude, seeing, exposure_time, obscode, radius=None, width=None, height=None, vertices=None, rotation=0.0))]
+    fn new(epoch: PyRef<PyTime>, ra: f64, dec: f64, band: &str, limiting_magnitude: f64, seeing: f64, exposure_time: f64, obscode: &str, radius: Option<f64>, width: Option<f64>, height: Option<f64>, vertices: Option<Vec<(f64, f64)>>, rotation: f64) -> PyResult<Self> {
+        let field = match (radius, width, height, vertices) {
+            (Some(radius), None, None, None) => Field::circle(ra, dec, radius),
+            (None, Some(width), Some(height), None) => Field::rectangle(ra, dec, width, height, rotation),
+            (None, None, None, Some(vertices)) => Field::polygon(ra, dec, vertices, rotation),
+            _ => return Err(PyValueError::new_err("Give either a radius, a width and a height, or vertices")),
+        };
+        let band = Band::from_str(band).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let exposure = Exposure::new(epoch.inner.clone(), field, band, limiting_magnitude, seeing, exposure_time, obscode).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyExposure { inner: exposure })
+    }
+
+    #[getter]
+    fn epoch(&self) -> PyTime {
+        PyTime { inner: self.inner.epoch.clone() }
+    }
+
+    #[getter]
+    fn ra(&self) -> f64 {
+        self.inner.field.ra
+    }
+
+    #[getter]
+    fn dec(&self) -> f64 {
+        self.inner.field.dec
+    }
+
+    #[getter]
+    fn band(&self) -> String {
+        self.inner.band.as_str().to_string()
+    }
+
+    #[getter]
+    fn limiting_magnitude(&self) -> f64 {
+        self.inner.limiting_magnitude
+    }
+
+    #[getter]
+    fn seeing(&self) -> f64 {
+        self.inner.seeing
+    }
+
+    #[getter]
+    fn exposure_time(&self) -> f64 {
+        self.inner.exposure_time
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Exposure(epoch={}, ra={}, dec={}, band={}, limiting_magnitude={})", self.inner.epoch.jd(), self.inner.field.ra, self.inner.field.dec, self.inner.band.as_str(), self.inner.limiting_magnitude)
+    }
+}
@@ -0,0 +1,21 @@
+use pyo3::prelude::*;
+
+pub mod exposure;
+pub mod detection_model;
+pub mod simulate_survey;
+
+pub fn make_survey_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let submodule = PyModule::new(py, "survey")?;
+
+    submodule.add_class::<exposure::PyExposure>()?;
+    submodule.add_class::<detection_model::PyDetectionModel>()?;
+    submodule.add_function(wrap_pyfunction!(simulate_survey::simulate_survey_py, submodule.clone())?)?;
+    submodule.add_function(wrap_pyfunction!(simulate_survey::simulate_survey_from_ephemerides_py, submodule.clone())?)?;
+
+    m.add_submodule(&submodule)?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("spacerocks.survey", submodule.clone())?;
+    submodule.setattr("__name__", "spacerocks.survey")?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use spacerocks::survey::{Detection, simulate_survey, simulate_survey_from_ephemerides};
+
+use crate::rockcollection::RockCollection;
+use crate::py_observing::detectioncatalog::DetectionCatalog;
+use crate::py_ephemeris::ephemeris::PyEphemeris;
+use crate::py_survey::exposure::PyExposure;
+use crate::py_survey::detection_model::PyDetectionModel;
+
+/// Simulate the detections of a collection of rocks in a list of exposures. Returns the detections
+/// as a DetectionCatalog, with the index of the rock and of the exposure of each.
+#[pyfunction]
+#[pyo3(name = "simulate_survey", signature = (rocks, exposures, model=None, seed=None))]
+pub fn simulate_survey_py(rocks: PyRef<RockCollection>, exposures: Vec<PyExposure>, model: Option<PyRef<PyDetectionModel>>, seed: Option<u64>) -> PyResult<(DetectionCatalog, Vec<usize>, Vec<usize>)> {
+    let exposures: Vec<_> = exposures.into_iter().map(|exposure| exposure.inner).collect();
+    let model = model.map(|model| model.inner).unwrap_or_default();
+    let detections = simulate_survey(&rocks.rocks, &exposures, &model, seed).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(split_detections(detections))
+}
+
+/// Simulate the detections of rocks in a list of exposures from their ephemerides and that of the
+/// observatory, which is much faster for large populations. Returns the same as simulate_survey.
+#[pyfunction]
+#[pyo3(name = "simulate_survey_from_ephemerides", signature = (rocks, observer, exposures, model=None, seed=None))]
+pub fn simulate_survey_from_ephemerides_py(rocks: Vec<PyRef<PyEphemeris>>, observer: PyRef<PyEphemeris>, exposures: Vec<PyExposure>, model: Option<PyRef<PyDetectionModel>>, seed: Option<u64>) -> PyResult<(DetectionCatalog, Vec<usize>, Vec<usize>)> {
+    let rocks: Vec<_> = rocks.iter().map(|rock| rock.inner.clone()).collect();
+    let exposures: Vec<_> = exposures.into_iter().map(|exposure| exposure.inner).collect();
+    let model = model.map(|model| model.inner).unwrap_or_default();
+    let detections = simulate_survey_from_ephemerides(&rocks, &observer.inner, &exposures, &model, seed).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(split_detections(detections))
+}
+
+fn split_detections(detections: Vec<Detection>) -> (DetectionCatalog, Vec<usize>, Vec<usize>) {
+    let mut catalog = DetectionCatalog { observations: Vec::with_capacity(detections.len()) };
+    let mut rock_indices = Vec::with_capacity(detections.len());
+    let mut exposure_indices = Vec::with_capacity(detections.len());
+    for detection in detections {
+        rock_indices.push(detection.rock);
+        exposure_indices.push(detection.exposure);
+        catalog.observations.push(detection.observation);
+    }
+    (catalog, rock_indices, exposure_indices)
+}
@@ -1,6 +1,8 @@
 use pyo3::prelude::*;
 
 pub mod time;
+pub mod tables;
+pub mod timerange;
 
 pub fn make_time_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Create a submodule named "time"
@@ -8,6 +10,10 @@ pub fn make_time_submodule(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()>
     
     // Register your time::PyTime class with the submodule
     submodule.add_class::<time::PyTime>()?;
+    submodule.add_class::<timerange::PyTimeRange>()?;
+    submodule.add_function(wrap_pyfunction!(tables::load_leap_seconds_py, submodule.clone())?)?;
+    submodule.add_function(wrap_pyfunction!(tables::load_eop_py, submodule.clone())?)?;
+    submodule.add_function(wrap_pyfunction!(tables::earth_orientation_py, submodule.clone())?)?;
 
     // Add the submodule to the parent module (requires PyO3 ≥ 0.14)
     m.add_submodule(&submodule)?;
@@ -0,0 +1,24 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use spacerocks::time::{load_leap_seconds, load_eop, get_earth_orientation};
+
+#[pyfunction]
+#[pyo3(name = "load_leap_seconds")]
+pub fn load_leap_seconds_py(path: &str) -> PyResult<()> {
+    load_leap_seconds(path).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(name = "load_eop")]
+pub fn load_eop_py(path: &str) -> PyResult<()> {
+    load_eop(path).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// The Earth orientation at a UTC JD, as (UT1 - UTC [s], x_pole [arcsec], y_pole [arcsec], dX [mas], dY [mas]).
+#[pyfunction]
+#[pyo3(name = "earth_orientation")]
+pub fn earth_orientation_py(jd: f64) -> (f64, f64, f64, f64, f64) {
+    let eop = get_earth_orientation(jd);
+    (eop.ut1_minus_utc, eop.x_pole, eop.y_pole, eop.dx, eop.dy)
+}
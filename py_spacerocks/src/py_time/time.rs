@@ -2,7 +2,7 @@ use pyo3::prelude::*;
 use pyo3::types::PyType;
 use pyo3::exceptions::PyValueError;
 
-use spacerocks::time::Time;
+use spacerocks::time::{Time, TimeScale};
 
 #[pyclass]
 #[pyo3(name = "Time")]
@@ -22,6 +22,14 @@ impl PyTime {
         }
     }
 
+    #[classmethod]
+    fn from_parts(_cls: Py<PyType>, day: f64, fraction: f64, timescale: &str, format: &str) -> PyResult<Self> {
+        match Time::from_parts(day, fraction, timescale, format) {
+            Ok(time) => Ok(PyTime { inner: time }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
     #[classmethod]
     fn now(_cls: Py<PyType>) -> PyResult<Self> {
         Ok(PyTime { inner: Time::now() })
@@ -52,6 +60,52 @@ impl PyTime {
         }
     }
 
+    #[classmethod]
+    #[pyo3(signature = (iso, timescale="utc"))]
+    fn from_iso(_cls: Py<PyType>, iso: &str, timescale: &str) -> PyResult<Self> {
+        match Time::from_iso(iso, timescale) {
+            Ok(time) => Ok(PyTime { inner: time }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (date, timescale="utc"))]
+    fn from_calendar(_cls: Py<PyType>, date: &str, timescale: &str) -> PyResult<Self> {
+        match Time::from_calendar(date, timescale) {
+            Ok(time) => Ok(PyTime { inner: time }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (packed, timescale="tt"))]
+    fn from_mpc_packed(_cls: Py<PyType>, packed: &str, timescale: &str) -> PyResult<Self> {
+        match Time::from_mpc_packed(packed, timescale) {
+            Ok(time) => Ok(PyTime { inner: time }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (year, timescale="utc"))]
+    fn from_decimal_year(_cls: Py<PyType>, year: f64, timescale: &str) -> PyResult<Self> {
+        match Time::from_decimal_year(year, timescale) {
+            Ok(time) => Ok(PyTime { inner: time }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[classmethod]
+    fn from_unix(_cls: Py<PyType>, seconds: f64) -> PyResult<Self> {
+        Ok(PyTime { inner: Time::from_unix(seconds) })
+    }
+
+    #[classmethod]
+    fn from_gps_seconds(_cls: Py<PyType>, seconds: f64) -> PyResult<Self> {
+        Ok(PyTime { inner: Time::from_gps_seconds(seconds) })
+    }
+
 
     // Methods that return new objects with converted timescale
 
@@ -71,6 +125,38 @@ impl PyTime {
         PyTime { inner: self.inner.tai() }
     }
 
+    fn tcb(&self) -> PyTime {
+        PyTime { inner: self.inner.tcb() }
+    }
+
+    fn tcg(&self) -> PyTime {
+        PyTime { inner: self.inner.tcg() }
+    }
+
+    fn ut1(&self) -> PyTime {
+        PyTime { inner: self.inner.ut1() }
+    }
+
+    fn gps(&self) -> PyTime {
+        PyTime { inner: self.inner.gps() }
+    }
+
+    fn tdb_topocentric(&self, elong: f64, u: f64, v: f64) -> PyTime {
+        PyTime { inner: self.inner.tdb_topocentric(elong, u, v) }
+    }
+
+    fn earth_rotation_angle(&self) -> f64 {
+        self.inner.earth_rotation_angle()
+    }
+
+    fn gmst(&self) -> f64 {
+        self.inner.gmst()
+    }
+
+    fn local_sidereal_time(&self, lon: f64) -> f64 {
+        self.inner.local_sidereal_time(lon)
+    }
+
     // Timescale conversion methods
 
     // fn to_utc(&mut self) -> PyResult<()> {
@@ -103,23 +189,30 @@ impl PyTime {
         Ok(self.clone())
     }
 
+    fn to_tcb(&mut self) -> PyResult<Self> {
+        self.inner.to_tcb();
+        Ok(self.clone())
+    }
+
+    fn to_tcg(&mut self) -> PyResult<Self> {
+        self.inner.to_tcg();
+        Ok(self.clone())
+    }
+
+    fn to_ut1(&mut self) -> PyResult<Self> {
+        self.inner.to_ut1();
+        Ok(self.clone())
+    }
+
+    fn to_gps(&mut self) -> PyResult<Self> {
+        self.inner.to_gps();
+        Ok(self.clone())
+    }
+
 
     pub fn change_timescale(&mut self, timescale: &str) -> PyResult<()> {
-        match timescale.to_lowercase().as_str() {
-            "utc" => {
-                self.to_utc()?;
-            },
-            "tdb" => {
-                self.to_tdb()?;
-            },
-            "tt" => {
-                self.to_tt()?;
-            },
-            "tai" => {
-                self.to_tai()?;
-            },
-            _ => return Err(PyValueError::new_err(format!("Invalid timescale: {}", timescale))),
-        }
+        let timescale: TimeScale = timescale.parse().map_err(|e: spacerocks::errors::TimeError| PyValueError::new_err(e.to_string()))?;
+        self.inner.change_timescale(timescale);
         Ok(())
     }
 
@@ -133,6 +226,14 @@ impl PyTime {
         self.inner.mjd()
     }
 
+    fn jd_parts(&self) -> (f64, f64) {
+        self.inner.jd_parts()
+    }
+
+    fn mjd_parts(&self) -> (f64, f64) {
+        self.inner.mjd_parts()
+    }
+
     fn calendar(&self) -> String {
         self.inner.calendar()
     }
@@ -141,31 +242,48 @@ impl PyTime {
         self.inner.iso()
     }
 
+    #[pyo3(signature = (precision=3))]
+    fn format_iso(&self, precision: usize) -> String {
+        self.inner.format_iso(precision)
+    }
+
+    #[pyo3(signature = (precision=5))]
+    fn format_calendar(&self, precision: usize) -> String {
+        self.inner.format_calendar(precision)
+    }
+
+    #[pyo3(signature = (precision=0))]
+    fn format_mpc_packed(&self, precision: usize) -> PyResult<String> {
+        self.inner.format_mpc_packed(precision).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn decimal_year(&self) -> f64 {
+        self.inner.decimal_year()
+    }
+
+    fn unix(&self) -> f64 {
+        self.inner.unix()
+    }
+
+    fn gps_seconds(&self) -> f64 {
+        self.inner.gps_seconds()
+    }
+
     // Attribute getters
 
     #[getter]
     fn epoch(&self) -> f64 {
-        self.inner.epoch
+        self.inner.epoch()
     }
 
     #[getter]
     fn timescale(&self) -> &str {
-        // &self.inner.timescale
-        match self.inner.timescale {
-            spacerocks::time::TimeScale::TDB => "TDB",
-            spacerocks::time::TimeScale::UTC => "UTC",
-            spacerocks::time::TimeScale::TT => "TT",
-            spacerocks::time::TimeScale::TAI => "TAI",
-        }
+        self.inner.timescale.to_str()
     }
 
     #[getter]
     fn format(&self) -> &str {
-        // &self.inner.format
-        match self.inner.format {
-            spacerocks::time::TimeFormat::JD => "JD",
-            spacerocks::time::TimeFormat::MJD => "MJD",
-        }
+        self.inner.format.to_str()
     }
 
     // fn iso(&self) -> String {
@@ -177,12 +295,30 @@ impl PyTime {
         PyTime { inner: self.inner.clone() + dt }
     }
 
-    fn __sub__(&self, dt: f64) -> PyTime {
-        PyTime { inner: self.inner.clone() - dt }
+    /// Subtracting a number of days gives a new Time, while subtracting another Time gives
+    /// the difference in days, at full precision. Both times must be in the same timescale.
+    fn __sub__(&self, other: &Bound<'_, PyAny>, py: Python) -> PyResult<Py<PyAny>> {
+        if let Ok(other) = other.extract::<PyRef<PyTime>>() {
+            if self.inner.timescale != other.inner.timescale {
+                return Err(PyValueError::new_err(format!("Cannot subtract timescales: {} and {}", self.inner.timescale, other.inner.timescale)));
+            }
+            let dt = &self.inner - &other.inner;
+            return Ok(dt.into_pyobject(py)?.into_any().unbind());
+        }
+        let dt: f64 = other.extract()?;
+        Ok(Py::new(py, PyTime { inner: self.inner.clone() - dt })?.into_any())
+    }
+
+    fn __iadd__(&mut self, dt: f64) {
+        self.inner += dt;
+    }
+
+    fn __isub__(&mut self, dt: f64) {
+        self.inner -= dt;
     }
 
     fn __repr__(&self) -> String {
-        format!("Time: {} {:?} {:?}", self.inner.epoch, self.inner.timescale, self.inner.format)
+        format!("Time: {} {:?} {:?}", self.inner.epoch(), self.inner.timescale, self.inner.format)
     }
 
     
@@ -0,0 +1,70 @@
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+
+use spacerocks::time::TimeRange;
+
+use crate::py_time::time::PyTime;
+
+#[pyclass]
+#[pyo3(name = "TimeRange")]
+#[derive(Clone)]
+pub struct PyTimeRange {
+    pub inner: TimeRange,
+}
+
+#[pymethods]
+impl PyTimeRange {
+
+    #[new]
+    fn new(start: PyRef<PyTime>, step: f64, count: usize) -> Self {
+        PyTimeRange { inner: TimeRange::new(&start.inner, step, count) }
+    }
+
+    #[classmethod]
+    fn from_step(_cls: Py<PyType>, start: PyRef<PyTime>, stop: PyRef<PyTime>, step: f64) -> PyResult<Self> {
+        match TimeRange::from_step(&start.inner, &stop.inner, step) {
+            Ok(range) => Ok(PyTimeRange { inner: range }),
+            Err(e) => Err(PyValueError::new_err(e.to_string()))
+        }
+    }
+
+    #[classmethod]
+    fn from_count(_cls: Py<PyType>, start: PyRef<PyTime>, stop: PyRef<PyTime>, count: usize) -> Self {
+        PyTimeRange { inner: TimeRange::from_count(&start.inner, &stop.inner, count) }
+    }
+
+    #[classmethod]
+    fn utc_midnights(_cls: Py<PyType>, start: PyRef<PyTime>, stop: PyRef<PyTime>) -> Self {
+        PyTimeRange { inner: TimeRange::utc_midnights(&start.inner, &stop.inner) }
+    }
+
+    #[classmethod]
+    fn sidereal(_cls: Py<PyType>, start: PyRef<PyTime>, stop: PyRef<PyTime>, lon: f64, lst: f64) -> Self {
+        PyTimeRange { inner: TimeRange::sidereal(&start.inner, &stop.inner, lon, lst) }
+    }
+
+    fn times(&self) -> Vec<PyTime> {
+        self.inner.clone().map(|time| PyTime { inner: time }).collect()
+    }
+
+    #[getter]
+    fn step(&self) -> f64 {
+        self.inner.step()
+    }
+
+    fn __getitem__(&self, index: usize) -> PyResult<PyTime> {
+        match self.inner.get(index) {
+            Some(time) => Ok(PyTime { inner: time }),
+            None => Err(PyIndexError::new_err("Index out of range!"))
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TimeRange: {} epochs, {} days apart", self.inner.len(), self.inner.step())
+    }
+}
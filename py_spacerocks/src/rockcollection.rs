@@ -7,11 +7,13 @@ use pyo3::types::PySequence;
 
 use spacerocks::spacerock::SpaceRock;
 use spacerocks::Time;
+use spacerocks::observing::{CorrectionLevel, Field, calc_rocks_in_field};
 
 use crate::py_time::time::PyTime;
 use crate::PySpaceRock;
 use crate::py_observing::observer::PyObserver;
 use crate::py_observing::observation::PyObservation;
+use crate::py_observing::observatory::PyObservatory;
 
 use numpy::{PyArray1, IntoPyArray};
 
@@ -152,12 +154,35 @@ impl RockCollection {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Observer frame is not J2000. Cannot observe rocks.")));
         }
 
-        let observations: Vec<_> = self.rocks.par_iter_mut().map(|rock| rock.observe(&o).unwrap()).collect();   
+        // SPICE is not thread-safe, so the velocity of the origin is looked up before going parallel
+        let origin_velocity = o.spacerock.origin_velocity().map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let observations: Vec<_> = self.rocks.par_iter_mut().map(|rock| {
+            let start = rock.clone();
+            rock.observe_with_propagator(&o, &CorrectionLevel::Astrometric, &origin_velocity, |epoch| start.analytic_at(epoch)).unwrap()
+        }).collect();
         let py_observations: Vec<_> = observations.into_iter().map(|obs| PyObservation { inner: obs }).collect();
         Ok(py_observations)
            
     }
 
+    /// Find the rocks which fall inside a field of view centred on `ra`, `dec` (radians, J2000),
+    /// seen from an observatory at an epoch. The field is a circle of `radius`, a rectangle of
+    /// `width` and `height`, or a polygon of `vertices` (x, y) in the tangent plane, all in
+    /// radians, turned by `rotation` east of north. Returns the indices of the rocks and their
+    /// observations.
+    #[pyo3(signature = (ra, dec, observatory, epoch, radius=None, width=None, height=None, vertices=None, rotation=0.0))]
+    pub fn in_field(&self, ra: f64, dec: f64, observatory: PyRef<PyObservatory>, epoch: PyRef<PyTime>, radius: Option<f64>, width: Option<f64>, height: Option<f64>, vertices: Option<Vec<(f64, f64)>>, rotation: f64) -> PyResult<(Vec<usize>, Vec<PyObservation>)> {
+        let field = match (radius, width, height, vertices) {
+            (Some(radius), None, None, None) => Field::circle(ra, dec, radius),
+            (None, Some(width), Some(height), None) => Field::rectangle(ra, dec, width, height, rotation),
+            (None, None, None, Some(vertices)) => Field::polygon(ra, dec, vertices, rotation),
+            _ => return Err(PyValueError::new_err("Give either a radius, a width and a height, or vertices")),
+        };
+
+        let found = calc_rocks_in_field(&self.rocks, &field, &observatory.inner, &epoch.inner).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(found.into_iter().map(|(index, observation)| (index, PyObservation { inner: observation })).unzip())
+    }
+
     pub fn analytic_propagate(&mut self, epoch: PyRef<PyTime>) -> PyResult<()> {
         let ep = &epoch.inner;
     
@@ -297,6 +322,19 @@ impl RockCollection {
         conic_anomaly_values.into_pyarray(py).to_owned().into()
     }
 
+    pub fn tisserand(&self, py: Python, planet: &str) -> PyResult<Py<PyArray1<f64>>> {
+        let tisserand_values: Result<Vec<f64>, String> = self.rocks.par_iter().map(|rock| rock.tisserand(planet).map_err(|e| e.to_string())).collect();
+        match tisserand_values {
+            Ok(values) => Ok(values.into_pyarray(py).to_owned().into()),
+            Err(e) => Err(PyValueError::new_err(e)),
+        }
+    }
+
+    pub fn dynamical_class(&self) -> PyResult<Vec<String>> {
+        let classes: Result<Vec<String>, String> = self.rocks.par_iter().map(|rock| rock.dynamical_class().map(|c| c.to_string()).map_err(|e| e.to_string())).collect();
+        classes.map_err(PyValueError::new_err)
+    }
+
 
     #[getter]
     pub fn epoch(&self) -> Vec<PyTime> {
@@ -1,13 +1,18 @@
 use pyo3::prelude::*;
 use pyo3::types::PyType;
 
-use spacerocks::SpaceRock;
+use spacerocks::{SpaceRock, Observer, PhaseFunction, Band};
+use spacerocks::photometry::Colors;
+use spacerocks::dynamics::sample_virtual_asteroids;
+use spacerocks::observing::{Atmosphere, CorrectionLevel, VisibilityConstraints};
 
-use nalgebra::Vector3;
+use nalgebra::{Matrix6, Vector3};
 
 use crate::py_time::time::PyTime;
+use crate::rockcollection::RockCollection;
 use crate::py_coordinates::origin::PyOrigin;
 use crate::py_observing::observer::{PyObserver};
+use crate::py_observing::observatory::PyObservatory;
 use crate::py_observing::observation::{PyObservation};
 
 
@@ -78,17 +83,118 @@ impl PySpaceRock {
     }
 
 
-    fn observe(&mut self, observer: &PyObserver) -> PyResult<PyObservation> {
+    /// Observe the rock. `correction` is "astrometric" (light time only), "apparent" (also light
+    /// deflection and aberration) or "observed" (also refraction, for ground observatories). The
+    /// pressure (hPa) defaults to the standard atmosphere at the height of the observatory. The
+    /// magnitude is in V, or in `band` ("V", "G", "g", "r", "i", "z", "y" or "w") using the colours
+    /// of the rock.
+    #[pyo3(signature = (observer, correction="astrometric", pressure=None, temperature=10.0, relative_humidity=0.5, wavelength=0.55, band=None))]
+    fn observe(&mut self, observer: &PyObserver, correction: &str, pressure: Option<f64>, temperature: f64, relative_humidity: f64, wavelength: f64, band: Option<&str>) -> PyResult<PyObservation> {
         // if observer.inner.frame != ReferencePlane::J2000 {
         //     return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Observer frame is not J2000. Cannot observe rocks.")));
         // }
 
-        match self.inner.observe(&observer.inner) {
+        let correction = correction_level(correction, observer, pressure, temperature, relative_humidity, wavelength)?;
+        let observation = match band {
+            Some(band) => {
+                let band = Band::from_str(band).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+                self.inner.observe_in_band(&observer.inner, &correction, &band)
+            }
+            None => self.inner.observe_with(&observer.inner, &correction),
+        };
+        match observation {
             Ok(obs) => Ok(PyObservation { inner: obs }),
             Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to observe rock: {}", e))),
         }
     }
 
+    /// The times at which the rock rises above an altitude (radians), transits and sets, seen
+    /// from a ground observatory in the day after `start`. Events which do not happen are None.
+    #[pyo3(signature = (observatory, start, altitude=0.0))]
+    fn rise_transit_set(&self, observatory: PyRef<PyObservatory>, start: PyRef<PyTime>, altitude: f64) -> PyResult<(Option<PyTime>, Option<PyTime>, Option<PyTime>)> {
+        match self.inner.rise_transit_set(&observatory.inner, &start.inner, altitude) {
+            Ok(events) => Ok((events.rise.map(|t| PyTime { inner: t }), events.transit.map(|t| PyTime { inner: t }), events.set.map(|t| PyTime { inner: t }))),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to find rise, transit and set: {}", e))),
+        }
+    }
+
+    /// The (start, end) windows between two times in which the rock is above `min_altitude`, the
+    /// Sun is below `max_sun_altitude` and the Moon is at least `min_moon_separation` away (radians).
+    #[pyo3(signature = (observatory, start, end, min_altitude=None, max_sun_altitude=None, min_moon_separation=None))]
+    fn observable_windows(&self, observatory: PyRef<PyObservatory>, start: PyRef<PyTime>, end: PyRef<PyTime>, min_altitude: Option<f64>, max_sun_altitude: Option<f64>, min_moon_separation: Option<f64>) -> PyResult<Vec<(PyTime, PyTime)>> {
+        let constraints = visibility_constraints(min_altitude, max_sun_altitude, min_moon_separation);
+        match self.inner.observable_windows(&observatory.inner, &start.inner, &end.inner, &constraints) {
+            Ok(windows) => Ok(windows.into_iter().map(|(a, b)| (PyTime { inner: a }, PyTime { inner: b })).collect()),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to find observable windows: {}", e))),
+        }
+    }
+
+    /// The observable windows in the 24 hours after `epoch`, which is usually around local noon.
+    #[pyo3(signature = (observatory, epoch, min_altitude=None, max_sun_altitude=None, min_moon_separation=None))]
+    fn observable_tonight(&self, observatory: PyRef<PyObservatory>, epoch: PyRef<PyTime>, min_altitude: Option<f64>, max_sun_altitude: Option<f64>, min_moon_separation: Option<f64>) -> PyResult<Vec<(PyTime, PyTime)>> {
+        let constraints = visibility_constraints(min_altitude, max_sun_altitude, min_moon_separation);
+        match self.inner.observable_tonight(&observatory.inner, &epoch.inner, &constraints) {
+            Ok(windows) => Ok(windows.into_iter().map(|(a, b)| (PyTime { inner: a }, PyTime { inner: b })).collect()),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to find observable windows: {}", e))),
+        }
+    }
+
+    /// Calculate the minimum orbit intersection distance with another SpaceRock.
+    ///
+    /// Returns a tuple of (moid, true_anomaly, other_true_anomaly).
+    fn moid(&self, other: PyRef<PySpaceRock>) -> PyResult<(f64, f64, f64)> {
+        match self.inner.moid(&other.inner) {
+            Ok(m) => Ok((m.distance, m.true_anomaly_1, m.true_anomaly_2)),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to calculate MOID: {}", e))),
+        }
+    }
+
+    fn earth_moid(&self) -> PyResult<(f64, f64, f64)> {
+        match self.inner.earth_moid() {
+            Ok(m) => Ok((m.distance, m.true_anomaly_1, m.true_anomaly_2)),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to calculate Earth MOID: {}", e))),
+        }
+    }
+
+    /// Calculate the Tisserand parameter with respect to a planet.
+    fn tisserand(&self, planet: &str) -> PyResult<f64> {
+        match self.inner.tisserand(planet) {
+            Ok(t) => Ok(t),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to calculate Tisserand parameter: {}", e))),
+        }
+    }
+
+    /// Assign a dynamical class (Apollo, Main Belt, Jupiter-family Comet, ...) from the orbit.
+    fn dynamical_class(&self) -> PyResult<String> {
+        match self.inner.dynamical_class() {
+            Ok(c) => Ok(c.to_string()),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to classify rock: {}", e))),
+        }
+    }
+
+    /// Calculate the intrinsic collision probability with another SpaceRock, assuming uniformly precessing orbits.
+    ///
+    /// Returns a tuple of (intrinsic_probability, impact_velocity), in km^-2 yr^-1 and km/s.
+    fn collision_probability(&self, other: PyRef<PySpaceRock>) -> PyResult<(f64, f64)> {
+        match self.inner.collision_probability(&other.inner) {
+            Ok(p) => Ok((p.intrinsic_probability, p.impact_velocity)),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to calculate collision probability: {}", e))),
+        }
+    }
+
+    /// Sample virtual asteroids from the 6x6 covariance of the cartesian state (au, au/day).
+    #[pyo3(signature = (covariance, n, seed=None))]
+    fn sample_virtual_asteroids(&self, covariance: Vec<Vec<f64>>, n: usize, seed: Option<u64>) -> PyResult<RockCollection> {
+        if covariance.len() != 6 || covariance.iter().any(|row| row.len() != 6) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Covariance must be a 6x6 matrix"));
+        }
+        let covariance = Matrix6::from_fn(|i, j| covariance[i][j]);
+        match sample_virtual_asteroids(&self.inner, &covariance, n, seed) {
+            Ok(rocks) => Ok(RockCollection { rocks }),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to sample virtual asteroids: {}", e))),
+        }
+    }
+
     fn analytic_propagate(&mut self, epoch: PyRef<PyTime>) -> PyResult<()> {
         match self.inner.analytic_propagate(&epoch.inner) {
             Ok(_) => Ok(()),
@@ -272,6 +378,60 @@ impl PySpaceRock {
         self.inner.gslope()
     }
 
+    fn set_g1g2(&mut self, g1: f64, g2: f64) -> PyResult<()> {
+        self.inner.set_g1g2(g1, g2);
+        Ok(())
+    }
+
+    fn set_g12(&mut self, g12: f64) -> PyResult<()> {
+        self.inner.set_g12(g12);
+        Ok(())
+    }
+
+    /// Set the linear phase coefficient, in magnitudes per degree.
+    fn set_phase_coefficient(&mut self, phase_coefficient: f64) -> PyResult<()> {
+        self.inner.set_phase_coefficient(phase_coefficient);
+        Ok(())
+    }
+
+    /// Set the total magnitude parameters M1 and K1 of a comet.
+    fn set_total_magnitude(&mut self, m1: f64, k1: f64) -> PyResult<()> {
+        self.inner.set_total_magnitude(m1, k1);
+        Ok(())
+    }
+
+    /// Set the nuclear magnitude parameters M2 and K2 of a comet.
+    fn set_nuclear_magnitude(&mut self, m2: f64, k2: f64) -> PyResult<()> {
+        self.inner.set_nuclear_magnitude(m2, k2);
+        Ok(())
+    }
+
+    /// Set the phase function used for magnitudes: "HG", "HG1G2", "HG12", "linear",
+    /// "comet_total" or "comet_nuclear".
+    fn set_phase_function(&mut self, phase_function: &str) -> PyResult<()> {
+        let phase_function = PhaseFunction::from_str(phase_function).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        self.inner.set_phase_function(phase_function);
+        Ok(())
+    }
+
+    /// Set the colours g-r, r-i, i-z and z-y, used to convert magnitudes between bands.
+    fn set_colors(&mut self, g_r: f64, r_i: f64, i_z: f64, z_y: f64) -> PyResult<()> {
+        self.inner.set_colors(Colors { g_r, r_i, i_z, z_y });
+        Ok(())
+    }
+
+    /// The colours g-r, r-i, i-z and z-y, which are solar unless set.
+    #[getter]
+    fn colors(&self) -> (f64, f64, f64, f64) {
+        let colors = self.inner.colors();
+        (colors.g_r, colors.r_i, colors.i_z, colors.z_y)
+    }
+
+    #[getter]
+    fn phase_function(&self) -> Option<String> {
+        self.inner.phase_function().map(|phase_function| phase_function.as_str().to_string())
+    }
+
     #[getter]
     fn evec(&self) -> (f64, f64, f64) {
         let e = self.inner.evec();
@@ -327,3 +487,35 @@ impl PySpaceRock {
 
 }
 
+/// Build a CorrectionLevel from the arguments of the observe methods. The pressure defaults to the
+/// standard atmosphere at the height of the observatory.
+pub(crate) fn correction_level(correction: &str, observer: &PyObserver, pressure: Option<f64>, temperature: f64, relative_humidity: f64, wavelength: f64) -> PyResult<CorrectionLevel> {
+    match CorrectionLevel::from_str(correction).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))? {
+        CorrectionLevel::Observed(_) => Ok(CorrectionLevel::Observed(atmosphere(&observer.inner, pressure, temperature, relative_humidity, wavelength))),
+        correction => Ok(correction),
+    }
+}
+
+/// Build the Atmosphere at an observatory. The pressure defaults to the standard atmosphere at the
+/// height of the observatory.
+pub(crate) fn atmosphere(observer: &Observer, pressure: Option<f64>, temperature: f64, relative_humidity: f64, wavelength: f64) -> Atmosphere {
+    let height = observer.observatory.geodetic().map(|(_, _, h)| h).unwrap_or(0.0);
+    let mut atmosphere = Atmosphere::standard(height);
+    if let Some(pressure) = pressure {
+        atmosphere.pressure = pressure;
+    }
+    atmosphere.temperature = temperature;
+    atmosphere.relative_humidity = relative_humidity;
+    atmosphere.wavelength = wavelength;
+    atmosphere
+}
+
+/// Build VisibilityConstraints, with the defaults for any limit not given.
+fn visibility_constraints(min_altitude: Option<f64>, max_sun_altitude: Option<f64>, min_moon_separation: Option<f64>) -> VisibilityConstraints {
+    let defaults = VisibilityConstraints::default();
+    VisibilityConstraints {
+        min_altitude: min_altitude.unwrap_or(defaults.min_altitude),
+        max_sun_altitude: max_sun_altitude.unwrap_or(defaults.max_sun_altitude),
+        min_moon_separation: min_moon_separation.unwrap_or(defaults.min_moon_separation),
+    }
+}
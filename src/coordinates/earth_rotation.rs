@@ -0,0 +1,102 @@
+use nalgebra::{Matrix3, Vector3};
+
+use crate::constants::ARCSEC_TO_RAD;
+use crate::coordinates::precession_nutation::{calc_celestial_to_intermediate_matrix, calc_celestial_to_intermediate_matrix_with_offsets, centuries, rotate_x, rotate_y, rotate_z};
+use crate::time::{Time, calc_earth_rotation_angle, get_earth_orientation, EARTH_ROTATION_RATE};
+
+/// Calculate the TIO locator s', which positions the terrestrial intermediate origin on the
+/// equator of the celestial intermediate pole. Follows the SOFA routine iauSp00.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the epoch (TT Julian Date)
+/// * `fraction` - The second part of the epoch (TT Julian Date)
+///
+/// # Returns
+///
+/// * The TIO locator s' (radians)
+pub fn calc_tio_locator(day: f64, fraction: f64) -> f64 {
+    -47e-6 * centuries(day, fraction) * ARCSEC_TO_RAD
+}
+
+/// Calculate the polar motion matrix, which rotates vectors from the terrestrial intermediate
+/// reference system (TIRS) to the ITRS. Follows the SOFA routine iauPom00.
+///
+/// # Arguments
+///
+/// * `xp` - The x coordinate of the pole (radians)
+/// * `yp` - The y coordinate of the pole (radians)
+/// * `sp` - The TIO locator s' (radians)
+///
+/// # Returns
+///
+/// * The rotation matrix
+pub fn calc_polar_motion_matrix(xp: f64, yp: f64, sp: f64) -> Matrix3<f64> {
+    let r = rotate_z(sp, Matrix3::identity());
+    let r = rotate_y(-xp, r);
+    rotate_x(-yp, r)
+}
+
+/// Calculate the matrix which rotates vectors from the GCRS (J2000) to the ITRS, from
+/// precession-nutation, the Earth rotation angle and polar motion. Follows the SOFA routine
+/// iauC2t06a.
+///
+/// # Arguments
+///
+/// * `tt_day` - The first part of the epoch (TT Julian Date)
+/// * `tt_fraction` - The second part of the epoch (TT Julian Date)
+/// * `ut1_day` - The first part of the epoch (UT1 Julian Date)
+/// * `ut1_fraction` - The second part of the epoch (UT1 Julian Date)
+/// * `xp` - The x coordinate of the pole (radians)
+/// * `yp` - The y coordinate of the pole (radians)
+///
+/// # Returns
+///
+/// * The rotation matrix
+pub fn calc_celestial_to_terrestrial_matrix(tt_day: f64, tt_fraction: f64, ut1_day: f64, ut1_fraction: f64, xp: f64, yp: f64) -> Matrix3<f64> {
+    let c2i = calc_celestial_to_intermediate_matrix(tt_day, tt_fraction);
+    let era = calc_earth_rotation_angle(ut1_day, ut1_fraction);
+    calc_polar_motion_matrix(xp, yp, calc_tio_locator(tt_day, tt_fraction)) * rotate_z(era, c2i)
+}
+
+/// Calculate the matrix which rotates vectors from the GCRS (J2000) to the ITRS at a time. UT1,
+/// polar motion and the celestial pole offsets come from the loaded Earth orientation table. With
+/// no table loaded, UT1 is taken to be UTC and the pole offsets are zero, which places a ground
+/// station to within about half a kilometre.
+///
+/// # Arguments
+///
+/// * `epoch` - The time
+///
+/// # Returns
+///
+/// * The rotation matrix
+pub fn calc_gcrs_to_itrs_matrix(epoch: &Time) -> Matrix3<f64> {
+    let tt = epoch.tt();
+    let ut1 = epoch.ut1();
+    let eop = get_earth_orientation(epoch.utc().jd());
+
+    let mas_to_rad = ARCSEC_TO_RAD / 1000.0;
+    let c2i = calc_celestial_to_intermediate_matrix_with_offsets(tt.day, tt.fraction, eop.dx * mas_to_rad, eop.dy * mas_to_rad);
+    let era = calc_earth_rotation_angle(ut1.day, ut1.fraction);
+    let pom = calc_polar_motion_matrix(eop.x_pole * ARCSEC_TO_RAD, eop.y_pole * ARCSEC_TO_RAD, calc_tio_locator(tt.day, tt.fraction));
+    pom * rotate_z(era, c2i)
+}
+
+/// Calculate the GCRS (J2000) position and velocity of a point fixed to the Earth. The velocity
+/// comes from the Earth's rotation alone; precession, nutation and polar motion change too slowly
+/// to matter.
+///
+/// # Arguments
+///
+/// * `epoch` - The time
+/// * `position` - The position in the ITRS
+///
+/// # Returns
+///
+/// * The position, and the velocity in the units of the position per day
+pub fn calc_itrs_to_gcrs_state(epoch: &Time, position: &Vector3<f64>) -> (Vector3<f64>, Vector3<f64>) {
+    let rotation = calc_gcrs_to_itrs_matrix(epoch).transpose();
+    let omega = Vector3::new(0.0, 0.0, EARTH_ROTATION_RATE * std::f64::consts::TAU);
+    (rotation * position, rotation * omega.cross(position))
+}
@@ -3,3 +3,9 @@ pub mod origin;
 
 pub mod reference_plane;
     pub use self::reference_plane::ReferencePlane;
+
+pub mod precession_nutation;
+    pub use self::precession_nutation::{calc_mean_obliquity, calc_nutation, calc_precession_matrix, calc_precession_nutation_matrix, calc_ecliptic_of_date_matrix, calc_cio_locator, calc_celestial_to_intermediate_matrix, calc_celestial_to_intermediate_matrix_with_offsets};
+
+pub mod earth_rotation;
+    pub use self::earth_rotation::{calc_tio_locator, calc_polar_motion_matrix, calc_celestial_to_terrestrial_matrix, calc_gcrs_to_itrs_matrix, calc_itrs_to_gcrs_state};
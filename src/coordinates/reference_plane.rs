@@ -1,5 +1,8 @@
-use nalgebra::Matrix3;
+use nalgebra::{Matrix3, Vector3};
 use crate::constants::{ROTATION_J2000, ROTATION_ECLIPJ2000, ROTATION_INVARIABLE, ROTATION_GALACTIC, ROTATION_FK4};
+use crate::coordinates::earth_rotation::calc_gcrs_to_itrs_matrix;
+use crate::coordinates::precession_nutation::{calc_precession_matrix, calc_precession_nutation_matrix, calc_celestial_to_intermediate_matrix, calc_ecliptic_of_date_matrix, rotate_z};
+use crate::time::{Time, EARTH_ROTATION_RATE};
 
 use serde::{Serialize, Deserialize};
 
@@ -12,10 +15,23 @@ pub enum ReferencePlane {
     INVARIABLE,
     GALACTIC,
     FK4,
+    /// The mean equator and equinox of date (IAU 2006 precession)
+    MeanOfDate(Time),
+    /// The true equator and equinox of date (IAU 2006 precession, IAU 2000A nutation)
+    TrueOfDate(Time),
+    /// The celestial intermediate reference system of date
+    CIRS(Time),
+    /// The terrestrial intermediate reference system of date, which turns with the Earth
+    TIRS(Time),
+    /// The mean ecliptic and equinox of date
+    EclipticOfDate(Time),
+    /// The international terrestrial reference system, fixed to the Earth at the epoch
+    ITRS(Time),
 }
 
 /// The reference plane is the frame of reference in which the coordinates are specified.
-/// The reference plane can be J2000, ECLIPJ2000, INVARIABLE, GALACTIC, or FK4.
+/// The reference plane can be J2000, ECLIPJ2000, INVARIABLE, GALACTIC, or FK4, or one of the
+/// frames of date (MOD, TOD, CIRS, TIRS, ECLIPDATE or ITRS), which are tied to an epoch.
 impl ReferencePlane {
 
     /// Create a new ReferencePlane from a string.
@@ -35,13 +51,49 @@ impl ReferencePlane {
             "INVARIABLE" => Ok(ReferencePlane::INVARIABLE),
             "GALACTIC" => Ok(ReferencePlane::GALACTIC),
             "FK4" => Ok(ReferencePlane::FK4),
+            "MOD" | "TOD" | "CIRS" | "TIRS" | "ECLIPDATE" | "ITRS" => Err(format!("Frame {} needs an epoch", s)),
             _ => Err(format!("Invalid frame: {}", s))
         }
     }
 
+    /// Create a new ReferencePlane from a string, tying any frame of date to an epoch.
+    ///
+    /// # Arguments
+    /// * `s` - The string representation of the ReferencePlane (J2000, ECLIPJ2000, INVARIABLE, GALACTIC, FK4, MOD, TOD, CIRS, TIRS, ECLIPDATE, or ITRS).
+    /// * `epoch` - The epoch of the frames of date. The inertial frames ignore it.
+    ///
+    /// # Example
+    /// ```
+    /// use spacerocks::{ReferencePlane, Time};
+    /// let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+    /// let reference_plane = ReferencePlane::from_str_at("TOD", &epoch).unwrap();
+    /// ```
+    pub fn from_str_at(s: &str, epoch: &Time) -> Result<Self, String> {
+        match s.to_uppercase().as_str() {
+            "MOD" => Ok(ReferencePlane::MeanOfDate(epoch.clone())),
+            "TOD" => Ok(ReferencePlane::TrueOfDate(epoch.clone())),
+            "CIRS" => Ok(ReferencePlane::CIRS(epoch.clone())),
+            "TIRS" => Ok(ReferencePlane::TIRS(epoch.clone())),
+            "ECLIPDATE" => Ok(ReferencePlane::EclipticOfDate(epoch.clone())),
+            "ITRS" => Ok(ReferencePlane::ITRS(epoch.clone())),
+            _ => ReferencePlane::from_str(s),
+        }
+    }
+
+    /// Return the epoch of a frame of date, or None for an inertial frame.
+    pub fn epoch(&self) -> Option<&Time> {
+        match self {
+            ReferencePlane::MeanOfDate(epoch) | ReferencePlane::TrueOfDate(epoch) | ReferencePlane::CIRS(epoch)
+            | ReferencePlane::TIRS(epoch) | ReferencePlane::EclipticOfDate(epoch) | ReferencePlane::ITRS(epoch) => Some(epoch),
+            _ => None,
+        }
+    }
+
     /// Return the rotation matrix of the ReferencePlane.
     /// These rotation matrices are used to transform the coordinates from the specified reference plane to the J2000 reference plane, 
-    /// and can be found in the constants module.
+    /// and can be found in the constants module. The matrices of the frames of date are computed from the
+    /// precession-nutation models at their epoch. They only rotate positions; the turning of TIRS and the
+    /// ITRS is added to velocities with `angular_velocity`.
     ///
     /// # Example
     /// ```
@@ -55,6 +107,37 @@ impl ReferencePlane {
             ReferencePlane::INVARIABLE => ROTATION_INVARIABLE,
             ReferencePlane::GALACTIC => ROTATION_GALACTIC,
             ReferencePlane::FK4 => ROTATION_FK4,
+            ReferencePlane::MeanOfDate(epoch) => {
+                let tt = epoch.tt();
+                calc_precession_matrix(tt.day, tt.fraction)
+            },
+            ReferencePlane::TrueOfDate(epoch) => {
+                let tt = epoch.tt();
+                calc_precession_nutation_matrix(tt.day, tt.fraction)
+            },
+            ReferencePlane::CIRS(epoch) => {
+                let tt = epoch.tt();
+                calc_celestial_to_intermediate_matrix(tt.day, tt.fraction)
+            },
+            ReferencePlane::TIRS(epoch) => {
+                let tt = epoch.tt();
+                rotate_z(epoch.earth_rotation_angle(), calc_celestial_to_intermediate_matrix(tt.day, tt.fraction))
+            },
+            ReferencePlane::EclipticOfDate(epoch) => {
+                let tt = epoch.tt();
+                calc_ecliptic_of_date_matrix(tt.day, tt.fraction)
+            },
+            ReferencePlane::ITRS(epoch) => calc_gcrs_to_itrs_matrix(epoch),
+        }
+    }
+
+    /// Return the angular velocity of the ReferencePlane relative to the inertial frames, in its own
+    /// coordinates (radians per day). TIRS and the ITRS turn with the Earth. The other frames of date
+    /// turn so slowly with precession and nutation that they are taken to be inertial.
+    pub fn angular_velocity(&self) -> Vector3<f64> {
+        match self {
+            ReferencePlane::TIRS(_) | ReferencePlane::ITRS(_) => Vector3::new(0.0, 0.0, EARTH_ROTATION_RATE * std::f64::consts::TAU),
+            _ => Vector3::zeros(),
         }
     }
 
@@ -72,6 +155,12 @@ impl ReferencePlane {
             ReferencePlane::INVARIABLE => "INVARIABLE",
             ReferencePlane::GALACTIC => "GALACTIC",
             ReferencePlane::FK4 => "FK4",
+            ReferencePlane::MeanOfDate(_) => "MOD",
+            ReferencePlane::TrueOfDate(_) => "TOD",
+            ReferencePlane::CIRS(_) => "CIRS",
+            ReferencePlane::TIRS(_) => "TIRS",
+            ReferencePlane::EclipticOfDate(_) => "ECLIPDATE",
+            ReferencePlane::ITRS(_) => "ITRS",
         }
     }
 
@@ -81,12 +170,6 @@ impl ReferencePlane {
 
 impl std::fmt::Display for ReferencePlane {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            ReferencePlane::J2000 => write!(f, "J2000"),
-            ReferencePlane::ECLIPJ2000 => write!(f, "ECLIPJ2000"),
-            ReferencePlane::INVARIABLE => write!(f, "INVARIABLE"),
-            ReferencePlane::GALACTIC => write!(f, "GALACTIC"),
-            ReferencePlane::FK4 => write!(f, "FK4"),
-        }
+        write!(f, "{}", self.as_str())
     }
-}
\ No newline at end of file
+}
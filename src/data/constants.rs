@@ -15,6 +15,7 @@ pub const EQUAT_RAD: f64 = 6378137.0;
 pub const FLATTEN: f64 = 1.0 / 298.257223563;
 pub const O_M_FLATTEN: f64 = 1.0 - FLATTEN;
 pub const DEG_TO_RAD: f64 = std::f64::consts::PI / 180.0;
+pub const ARCSEC_TO_RAD: f64 = DEG_TO_RAD / 3600.0;
 
 pub const MU_BARY: f64 = 0.00029630927493457475;
 // pub const SPEED_OF_LIGHT: f64 = 173.14463268466926; // speed of light in au/day
@@ -56,6 +57,22 @@ lazy_static! {
     };
 }
 
+// semimajor axes of the planets (au), from the J2000 mean elements of Standish & Williams
+lazy_static! {
+    pub static ref PLANET_SEMIMAJOR_AXES: HashMap<String, f64> = {
+        let mut m = HashMap::new();
+        m.insert("mercury".to_string(), 0.38709927);
+        m.insert("venus".to_string(), 0.72333566);
+        m.insert("earth".to_string(), 1.00000261);
+        m.insert("mars".to_string(), 1.52371034);
+        m.insert("jupiter".to_string(), 5.20288700);
+        m.insert("saturn".to_string(), 9.53667594);
+        m.insert("uranus".to_string(), 19.18916464);
+        m.insert("neptune".to_string(), 30.06992276);
+        m
+    };
+}
+
 const KM_PER_AU: f64 = 149597870.700;
 // const SECONDS_PER_DAY: f64 = 86400.0
 const KM3_PER_SECOND2_TO_AU3_PER_DAY2: f64 = (1.0 / KM_PER_AU) * (1.0 / KM_PER_AU) * (1.0 / KM_PER_AU) * (SECONDS_PER_DAY * SECONDS_PER_DAY);
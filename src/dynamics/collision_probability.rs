@@ -0,0 +1,158 @@
+use crate::SpaceRock;
+use crate::constants::{KM_TO_AU, SECONDS_PER_DAY};
+
+use nalgebra::Vector3;
+
+use std::f64::consts::PI;
+
+/// The intrinsic collision probability and mean impact velocity of a pair of orbits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionProbability {
+    /// The intrinsic collision probability (km^-2 yr^-1). The collision rate of the pair is this times the
+    /// square of the sum of their radii.
+    pub intrinsic_probability: f64,
+    /// The collision-rate weighted mean impact velocity (km/s). NaN if the orbits never cross.
+    pub impact_velocity: f64,
+}
+
+/// The shape of an orbit whose node, argument of pericenter and mean anomaly are uniformly distributed.
+struct PrecessingOrbit {
+    a: f64,
+    h: f64,
+    q: f64,
+    big_q: f64,
+    cos_inc: f64,
+    sin_inc: f64,
+}
+
+impl PrecessingOrbit {
+
+    fn from_rock(rock: &SpaceRock) -> Result<Self, Box<dyn std::error::Error>> {
+        let e = rock.e();
+        if e >= 1.0 {
+            return Err(format!("{} is not on a bound orbit", rock.name).into());
+        }
+        let inc = rock.inc();
+        if inc.sin() == 0.0 {
+            return Err(format!("{} has zero inclination, so its spatial density is degenerate", rock.name).into());
+        }
+        let a = rock.a();
+        Ok(PrecessingOrbit {
+            a,
+            h: (rock.origin.mu() * a * (1.0 - e * e)).sqrt(),
+            q: a * (1.0 - e),
+            big_q: a * (1.0 + e),
+            cos_inc: inc.cos(),
+            sin_inc: inc.sin(),
+        })
+    }
+
+    /// The four possible velocities (outbound/inbound, northbound/southbound) at a heliocentric distance
+    /// and latitude, as (radial, east, north) components.
+    fn velocities(&self, r: f64, sin_beta: f64, mu: f64) -> [Vector3<f64>; 4] {
+        let v2 = mu * (2.0 / r - 1.0 / self.a);
+        let vh = self.h / r;
+        let vr = (v2 - vh * vh).max(0.0).sqrt();
+
+        let cos_beta = (1.0 - sin_beta * sin_beta).sqrt();
+        let cos_psi = (self.cos_inc / cos_beta).clamp(-1.0, 1.0);
+        let sin_psi = (1.0 - cos_psi * cos_psi).sqrt();
+
+        let east = vh * cos_psi;
+        let north = vh * sin_psi;
+        [Vector3::new(vr, east, north),
+         Vector3::new(vr, east, -north),
+         Vector3::new(-vr, east, north),
+         Vector3::new(-vr, east, -north)]
+    }
+}
+
+const N_NODES: usize = 200;
+
+/// Calculate the intrinsic collision probability and mean impact velocity of two orbits, assuming their
+/// nodes, arguments of pericenter and mean anomalies are uniformly and independently distributed
+/// (the Öpik/Wetherill/Greenberg formalism). The semimajor axes, eccentricities and inclinations are held fixed.
+///
+/// The collision rate is the integral of the product of the two spatial densities, weighted by the mean relative
+/// speed, over the region where the orbits can meet. The integrable singularities of the densities at the
+/// turning points (in heliocentric distance and in latitude) are removed with a change of variables, and the
+/// remaining integral is evaluated with a Gauss-Chebyshev rule. As in Öpik's theory, the probability diverges
+/// for pairs with a shared pericenter or apocenter distance, or a shared inclination. The inclinations are
+/// measured from the common reference plane of the SpaceRocks, which is usually ECLIPJ2000.
+///
+/// # Arguments
+/// * `rock_1` - The first SpaceRock
+/// * `rock_2` - The second SpaceRock
+///
+/// # Returns
+/// * The intrinsic collision probability (km^-2 yr^-1) and the mean impact velocity (km/s)
+pub fn calc_collision_probability(rock_1: &SpaceRock, rock_2: &SpaceRock) -> Result<CollisionProbability, Box<dyn std::error::Error>> {
+
+    if rock_1.reference_plane != rock_2.reference_plane {
+        return Err("SpaceRocks have different reference planes".into());
+    }
+
+    if rock_1.origin != rock_2.origin {
+        return Err("SpaceRocks have different origins".into());
+    }
+    let mu = rock_1.origin.mu();
+
+    let orbit_1 = PrecessingOrbit::from_rock(rock_1)?;
+    let orbit_2 = PrecessingOrbit::from_rock(rock_2)?;
+
+    // the range of heliocentric distance that both orbits span
+    let lo = orbit_1.q.max(orbit_2.q);
+    let hi = orbit_1.big_q.min(orbit_2.big_q);
+    if lo >= hi {
+        return Ok(CollisionProbability { intrinsic_probability: 0.0, impact_velocity: f64::NAN });
+    }
+    let q_min = orbit_1.q.min(orbit_2.q);
+    let big_q_max = orbit_1.big_q.max(orbit_2.big_q);
+
+    // the range of latitude that both orbits span
+    let sin_min = orbit_1.sin_inc.min(orbit_2.sin_inc);
+    let sin_max = orbit_1.sin_inc.max(orbit_2.sin_inc);
+
+    let centre = 0.5 * (hi + lo);
+    let half_width = 0.5 * (hi - lo);
+    let step = PI / (N_NODES as f64);
+
+    let mut rate = 0.0;
+    let mut weighted_speed = 0.0;
+    for jdx in 0..N_NODES {
+        // r = centre - half_width cos(theta) absorbs the density singularities at lo and hi
+        let theta = (jdx as f64 + 0.5) * step;
+        let r = centre - half_width * theta.cos();
+        let radial_weight = 1.0 / ((r - q_min) * (big_q_max - r)).sqrt();
+
+        for kdx in 0..N_NODES {
+            // sin(beta) = sin_min sin(phi) absorbs the singularities at the smaller maximum latitude
+            let phi = -0.5 * PI + (kdx as f64 + 0.5) * step;
+            let sin_beta = sin_min * phi.sin();
+            let latitude_weight = 1.0 / (sin_max * sin_max - sin_beta * sin_beta).sqrt();
+
+            let velocities_1 = orbit_1.velocities(r, sin_beta, mu);
+            let velocities_2 = orbit_2.velocities(r, sin_beta, mu);
+            let mut speed = 0.0;
+            let mut speed_squared = 0.0;
+            for v1 in &velocities_1 {
+                for v2 in &velocities_2 {
+                    let u = (v1 - v2).norm();
+                    speed += u;
+                    speed_squared += u * u;
+                }
+            }
+            let weight = radial_weight * latitude_weight / 16.0;
+            rate += weight * speed;
+            weighted_speed += weight * speed_squared;
+        }
+    }
+
+    let impact_velocity = weighted_speed / rate;
+    rate *= step * step / (2.0 * PI.powi(4) * orbit_1.a * orbit_2.a);
+
+    Ok(CollisionProbability {
+        intrinsic_probability: rate * KM_TO_AU * KM_TO_AU * 365.25,
+        impact_velocity: impact_velocity / KM_TO_AU / SECONDS_PER_DAY,
+    })
+}
@@ -0,0 +1,173 @@
+use crate::SpaceRock;
+use crate::constants::{GRAVITATIONAL_CONSTANT, KM_TO_AU, PLANET_SEMIMAJOR_AXES, SECONDS_PER_DAY};
+
+/// The smallest hyperbolic excess velocity of an interstellar object (au/day). Planetary perturbations
+/// leave some long-period comets slightly hyperbolic, with excess velocities of a few km/s, while the
+/// known interstellar objects arrived at over 25 km/s.
+const INTERSTELLAR_V_INF: f64 = 10.0 * KM_TO_AU * SECONDS_PER_DAY;
+
+/// The semimajor axis corresponding to an orbital period of 200 years (au), which separates
+/// Halley-type from long-period comets, and bounds the near-Earth comets.
+const A_HALLEY_TYPE: f64 = 34.2;
+
+/// How far inside Jupiter's orbit the aphelion of a Jupiter-family comet may lie (au), so that
+/// asteroids with T_J < 3 which never come near Jupiter are not taken for comets.
+const JUPITER_APPROACH: f64 = 1.0;
+
+/// The dynamical class of a small body's orbit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DynamicalClass {
+    Atira,
+    Aten,
+    Apollo,
+    Amor,
+    MainBelt,
+    Hilda,
+    JupiterTrojan,
+    Centaur,
+    JupiterFamilyComet,
+    HalleyTypeComet,
+    LongPeriodComet,
+    ClassicalTNO,
+    ScatteredTNO,
+    DetachedTNO,
+    Interstellar,
+    Unclassified,
+}
+
+impl DynamicalClass {
+
+    /// Assign a dynamical class from heliocentric osculating elements and the Tisserand parameter with respect
+    /// to Jupiter. The cuts are the conventional ones: the MPC near-Earth definitions, the Levison (1996) Tisserand
+    /// boundaries for comets, and the Gladman et al. (2008) eccentricity and perihelion cuts for the trans-Neptunian region.
+    /// The near-Earth cuts take precedence, so that near-Earth comets with periods under 200 years are classified by
+    /// their orbits, and objects with T_J < 2 are classified as comets next. Jupiter-family comets must reach inside Jupiter's orbit from beyond it,
+    /// or have their aphelion near it; Centaurs stay outside Jupiter's orbit. Resonant TNOs can't be identified from
+    /// osculating elements alone, and fall into the classical or scattered classes. Unbound orbits are interstellar only
+    /// if their hyperbolic excess velocity is over 10 km/s; slower ones are long-period comets made slightly hyperbolic
+    /// by the planets.
+    ///
+    /// # Arguments
+    /// * `a` - The semimajor axis (au). Negative for hyperbolic orbits.
+    /// * `e` - The eccentricity
+    /// * `inc` - The inclination to the ecliptic (radians)
+    /// * `tisserand` - The Tisserand parameter with respect to Jupiter
+    ///
+    /// # Returns
+    /// * The DynamicalClass
+    pub fn from_elements(a: f64, e: f64, inc: f64, tisserand: f64) -> DynamicalClass {
+
+        let a_jupiter = PLANET_SEMIMAJOR_AXES["jupiter"];
+        let a_neptune = PLANET_SEMIMAJOR_AXES["neptune"];
+
+        if e >= 1.0 {
+            let v_inf = if a < 0.0 { (-GRAVITATIONAL_CONSTANT / a).sqrt() } else { 0.0 };
+            if v_inf > INTERSTELLAR_V_INF {
+                return DynamicalClass::Interstellar;
+            }
+            return DynamicalClass::LongPeriodComet;
+        }
+
+        let q = a * (1.0 - e);
+        let big_q = a * (1.0 + e);
+
+        if q < 1.3 && a < A_HALLEY_TYPE {
+            if big_q < 0.983 {
+                return DynamicalClass::Atira;
+            }
+            if a < 1.0 {
+                return DynamicalClass::Aten;
+            }
+            if q < 1.017 {
+                return DynamicalClass::Apollo;
+            }
+            return DynamicalClass::Amor;
+        }
+
+        if tisserand < 2.0 {
+            if a < A_HALLEY_TYPE {
+                return DynamicalClass::HalleyTypeComet;
+            }
+            return DynamicalClass::LongPeriodComet;
+        }
+
+        if (5.05..5.35).contains(&a) && e < 0.3 {
+            return DynamicalClass::JupiterTrojan;
+        }
+
+        if (3.7..4.2).contains(&a) && e < 0.3 && inc < 20_f64.to_radians() {
+            return DynamicalClass::Hilda;
+        }
+
+        if tisserand < 3.0 && q < a_jupiter && (a > a_jupiter || big_q > a_jupiter - JUPITER_APPROACH) {
+            return DynamicalClass::JupiterFamilyComet;
+        }
+
+        if (1.78..3.7).contains(&a) {
+            return DynamicalClass::MainBelt;
+        }
+
+        if a > a_jupiter && a < a_neptune && q > a_jupiter {
+            return DynamicalClass::Centaur;
+        }
+
+        if a >= a_neptune {
+            if e < 0.24 && a < 48.0 {
+                return DynamicalClass::ClassicalTNO;
+            }
+            if q > 40.0 {
+                return DynamicalClass::DetachedTNO;
+            }
+            return DynamicalClass::ScatteredTNO;
+        }
+
+        DynamicalClass::Unclassified
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            DynamicalClass::Atira => "Atira",
+            DynamicalClass::Aten => "Aten",
+            DynamicalClass::Apollo => "Apollo",
+            DynamicalClass::Amor => "Amor",
+            DynamicalClass::MainBelt => "Main Belt",
+            DynamicalClass::Hilda => "Hilda",
+            DynamicalClass::JupiterTrojan => "Jupiter Trojan",
+            DynamicalClass::Centaur => "Centaur",
+            DynamicalClass::JupiterFamilyComet => "Jupiter-family Comet",
+            DynamicalClass::HalleyTypeComet => "Halley-type Comet",
+            DynamicalClass::LongPeriodComet => "Long-period Comet",
+            DynamicalClass::ClassicalTNO => "Classical TNO",
+            DynamicalClass::ScatteredTNO => "Scattered TNO",
+            DynamicalClass::DetachedTNO => "Detached TNO",
+            DynamicalClass::Interstellar => "Interstellar",
+            DynamicalClass::Unclassified => "Unclassified",
+        }
+    }
+
+    /// Whether the class is one of the near-Earth classes (Atira, Aten, Apollo, Amor).
+    pub fn is_near_earth(&self) -> bool {
+        matches!(self, DynamicalClass::Atira | DynamicalClass::Aten | DynamicalClass::Apollo | DynamicalClass::Amor)
+    }
+}
+
+impl std::fmt::Display for DynamicalClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Classify a SpaceRock. The elements are taken in the ecliptic, relative to the SpaceRock's origin,
+/// so the SpaceRock should be heliocentric for the class boundaries to be meaningful.
+///
+/// # Arguments
+/// * `rock` - The SpaceRock to classify
+///
+/// # Returns
+/// * The DynamicalClass
+pub fn calc_dynamical_class(rock: &SpaceRock) -> Result<DynamicalClass, Box<dyn std::error::Error>> {
+    let mut rock = rock.clone();
+    rock.change_reference_plane("ECLIPJ2000")?;
+    let tisserand = rock.tisserand_from_semimajor_axis(PLANET_SEMIMAJOR_AXES["jupiter"]);
+    Ok(DynamicalClass::from_elements(rock.a(), rock.e(), rock.inc(), tisserand))
+}
@@ -0,0 +1,137 @@
+use crate::{SpaceRock, Simulation};
+use crate::time::Time;
+use crate::constants::GRAVITATIONAL_CONSTANT;
+use crate::utils::standard_normal;
+
+use nalgebra::{Matrix6, Vector3, Vector6};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+
+/// A virtual asteroid that falls within the capture cross-section of a body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualImpactor {
+    /// The name of the virtual asteroid
+    pub name: String,
+    /// The time of closest approach (TDB)
+    pub epoch: Time,
+    /// The nominal distance at closest approach (au)
+    pub distance: f64,
+    /// The impact parameter of the encounter (au)
+    pub impact_parameter: f64,
+    /// The radius of the gravitationally focused capture cross-section for the encounter (au)
+    pub capture_radius: f64,
+}
+
+/// The outcome of a Monte Carlo impact probability calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpactProbability {
+    /// The name of the body being impacted
+    pub body: String,
+    /// The fraction of virtual asteroids that impact the body
+    pub probability: f64,
+    /// The number of virtual asteroids that were integrated
+    pub n_virtual_asteroids: usize,
+    /// The virtual asteroids that impact, in the order they were given
+    pub impactors: Vec<VirtualImpactor>,
+}
+
+/// Sample virtual asteroids from the covariance of a fitted orbit. The covariance is that of the
+/// cartesian state (x, y, z, vx, vy, vz), in au and au/day, in the reference plane and origin of the rock.
+///
+/// # Arguments
+/// * `rock` - The nominal orbit
+/// * `covariance` - The 6x6 covariance matrix of the cartesian state
+/// * `n` - The number of virtual asteroids to draw
+/// * `seed` - An optional seed for the random number generator, for reproducible draws
+///
+/// # Returns
+/// * The virtual asteroids, named `{rock.name}_{idx}`
+pub fn sample_virtual_asteroids(rock: &SpaceRock, covariance: &Matrix6<f64>, n: usize, seed: Option<u64>) -> Result<Vec<SpaceRock>, Box<dyn std::error::Error>> {
+
+    let cholesky = covariance.cholesky().ok_or("Covariance matrix is not positive definite")?;
+    let l = cholesky.l();
+
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut virtual_asteroids = Vec::with_capacity(n);
+    for idx in 0..n {
+        let deviates = Vector6::from_fn(|_, _| standard_normal(&mut rng));
+        let offset = l * deviates;
+
+        let mut va = rock.clone();
+        va.name = format!("{}_{}", rock.name, idx);
+        va.position += Vector3::new(offset[0], offset[1], offset[2]);
+        va.velocity += Vector3::new(offset[3], offset[4], offset[5]);
+        virtual_asteroids.push(va);
+    }
+    Ok(virtual_asteroids)
+}
+
+
+/// Estimate the probability that an object impacts a body, by integrating each of its virtual asteroids
+/// (drawn from a covariance with `sample_virtual_asteroids`, or taken from an MCMC posterior) through a copy of
+/// a Simulation. A virtual asteroid impacts if its closest approach falls within the body's radius, or its
+/// impact parameter falls within the capture cross-section enlarged by gravitational focusing,
+/// b < R sqrt(1 + v_esc^2 / v_inf^2). Only the first impact of each virtual asteroid is counted.
+///
+/// # Arguments
+/// * `sim` - The Simulation with the perturbers, at the epoch of the virtual asteroids
+/// * `virtual_asteroids` - The virtual asteroids. Their names must be unique.
+/// * `epoch` - The epoch to integrate to
+/// * `body` - The name of the body in the simulation, e.g. "earth"
+/// * `radius` - The physical radius of the body (au)
+///
+/// # Returns
+/// * The impact probability, along with the dates and distances of the impacting virtual asteroids
+pub fn calc_impact_probability(sim: &Simulation, virtual_asteroids: &[SpaceRock], epoch: &Time, body: &str, radius: f64) -> Result<ImpactProbability, Box<dyn std::error::Error>> {
+
+    if virtual_asteroids.is_empty() {
+        return Err("No virtual asteroids were given".into());
+    }
+
+    let mu = sim.get_particle(body)?.mass() * GRAVITATIONAL_CONSTANT;
+    let escape_velocity_squared = 2.0 * mu / radius;
+
+    let results: Vec<Result<Option<VirtualImpactor>, String>> = virtual_asteroids.par_iter().map(|va| {
+        let mut sim = sim.clone();
+        sim.add(va.clone()).map_err(|e| e.to_string())?;
+
+        // the capture radius depends on the encounter velocity, so collect every approach and filter afterwards
+        let approaches = sim.close_approaches(epoch, &va.name, body, f64::INFINITY).map_err(|e| e.to_string())?;
+
+        let impact = approaches.into_iter().find_map(|approach| {
+            let capture_radius = if approach.v_infinity > 0.0 {
+                radius * (1.0 + escape_velocity_squared / approach.v_infinity.powi(2)).sqrt()
+            } else {
+                radius
+            };
+            let captured = approach.distance <= radius || approach.impact_parameter <= capture_radius;
+            captured.then(|| VirtualImpactor {
+                name: va.name.clone(),
+                epoch: approach.epoch,
+                distance: approach.distance,
+                impact_parameter: approach.impact_parameter,
+                capture_radius,
+            })
+        });
+        Ok(impact)
+    }).collect();
+
+    let mut impactors = Vec::new();
+    for result in results {
+        if let Some(impactor) = result? {
+            impactors.push(impactor);
+        }
+    }
+
+    Ok(ImpactProbability {
+        body: body.to_string(),
+        probability: impactors.len() as f64 / virtual_asteroids.len() as f64,
+        n_virtual_asteroids: virtual_asteroids.len(),
+        impactors,
+    })
+}
@@ -0,0 +1,11 @@
+pub mod moid;
+    pub use self::moid::{calc_moid, Moid};
+
+pub mod impact_probability;
+    pub use self::impact_probability::{calc_impact_probability, sample_virtual_asteroids, ImpactProbability, VirtualImpactor};
+
+pub mod collision_probability;
+    pub use self::collision_probability::{calc_collision_probability, CollisionProbability};
+
+pub mod dynamical_class;
+    pub use self::dynamical_class::{calc_dynamical_class, DynamicalClass};
@@ -0,0 +1,239 @@
+use crate::SpaceRock;
+
+use nalgebra::Vector3;
+
+use std::f64::consts::PI;
+
+/// The minimum orbit intersection distance between two orbits, along with the
+/// true anomalies on each orbit at which the minimum occurs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moid {
+    pub distance: f64,
+    pub true_anomaly_1: f64,
+    pub true_anomaly_2: f64,
+}
+
+/// The geometry of a fixed conic section, described by its semi-latus rectum, its eccentricity,
+/// and the perifocal unit vectors (towards periapsis, and 90 degrees ahead of periapsis).
+#[derive(Debug, Clone, Copy)]
+struct Conic {
+    p: f64,
+    e: f64,
+    phat: Vector3<f64>,
+    qhat: Vector3<f64>,
+}
+
+impl Conic {
+
+    fn from_rock(rock: &SpaceRock) -> Result<Self, Box<dyn std::error::Error>> {
+        let hvec = rock.hvec();
+        let h = hvec.norm();
+        if h == 0.0 {
+            return Err(format!("{} is on a radial orbit, MOID is undefined", rock.name).into());
+        }
+        let hhat = hvec / h;
+
+        let evec = rock.evec();
+        let e = evec.norm();
+
+        // for circular orbits the periapsis is undefined, so measure the anomaly from the
+        // ascending node (or the x-axis for equatorial orbits), as SpaceRock::true_anomaly does
+        let phat = if e < 1e-10 {
+            let nvec = rock.nvec();
+            if nvec.norm() > 0.0 { nvec / nvec.norm() } else { Vector3::x() }
+        } else {
+            evec / e
+        };
+        let qhat = hhat.cross(&phat);
+
+        Ok(Conic { p: h * h / rock.origin.mu(), e, phat, qhat })
+    }
+
+    /// The range of true anomalies that the conic spans. Unbound orbits are truncated just
+    /// short of their asymptotes.
+    fn anomaly_range(&self) -> (f64, f64) {
+        if self.e < 1.0 {
+            (0.0, 2.0 * PI)
+        } else {
+            let nu_max = (-1.0 / self.e).acos() * (1.0 - 1e-6);
+            (-nu_max, nu_max)
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.e < 1.0
+    }
+
+    /// Position on the conic, and its first and second derivatives with respect to the true anomaly.
+    fn position(&self, nu: f64) -> (Vector3<f64>, Vector3<f64>, Vector3<f64>) {
+        let (s, c) = nu.sin_cos();
+        let r = self.p / (1.0 + self.e * c);
+        let dr = self.e * s * r * r / self.p;
+        let ddr = self.e / self.p * (c * r * r + 2.0 * s * r * dr);
+
+        let u = c * self.phat + s * self.qhat;
+        let u_perp = -s * self.phat + c * self.qhat;
+
+        let pos = r * u;
+        let dpos = dr * u + r * u_perp;
+        let ddpos = ddr * u + 2.0 * dr * u_perp - r * u;
+        (pos, dpos, ddpos)
+    }
+
+    fn wrap(&self, nu: f64) -> f64 {
+        if self.is_closed() {
+            nu.rem_euclid(2.0 * PI)
+        } else {
+            let (lo, hi) = self.anomaly_range();
+            nu.clamp(lo, hi)
+        }
+    }
+}
+
+const GRID_SIZE: usize = 360;
+
+/// Calculate the minimum orbit intersection distance (MOID) between the osculating orbits of two SpaceRocks.
+/// Both orbits are treated as fixed conics, so the epochs of the two rocks do not need to match, but they
+/// must share an origin and a reference plane. Elliptical, parabolic and hyperbolic orbits are all supported.
+///
+/// The distance function is first evaluated on a grid of true anomalies to bracket every local minimum,
+/// and each candidate is then polished with a damped Newton iteration.
+///
+/// # Arguments
+/// * `rock_1` - The first SpaceRock
+/// * `rock_2` - The second SpaceRock
+///
+/// # Returns
+/// * The MOID (au), and the true anomalies (radians) on each orbit at which it occurs
+pub fn calc_moid(rock_1: &SpaceRock, rock_2: &SpaceRock) -> Result<Moid, Box<dyn std::error::Error>> {
+
+    if rock_1.reference_plane != rock_2.reference_plane {
+        return Err("SpaceRocks have different reference planes".into());
+    }
+
+    if rock_1.origin != rock_2.origin {
+        return Err("SpaceRocks have different origins".into());
+    }
+
+    let conic_1 = Conic::from_rock(rock_1)?;
+    let conic_2 = Conic::from_rock(rock_2)?;
+
+    let grid_1 = anomaly_grid(&conic_1);
+    let grid_2 = anomaly_grid(&conic_2);
+    let points_2: Vec<Vector3<f64>> = grid_2.iter().map(|nu| conic_2.position(*nu).0).collect();
+
+    let mut distances = vec![vec![0.0; GRID_SIZE]; GRID_SIZE];
+    for (idx, nu_1) in grid_1.iter().enumerate() {
+        let point_1 = conic_1.position(*nu_1).0;
+        for (jdx, point_2) in points_2.iter().enumerate() {
+            distances[idx][jdx] = (point_1 - point_2).norm_squared();
+        }
+    }
+
+    let mut best = Moid { distance: f64::INFINITY, true_anomaly_1: 0.0, true_anomaly_2: 0.0 };
+    for (idx, nu_1) in grid_1.iter().enumerate() {
+        for (jdx, nu_2) in grid_2.iter().enumerate() {
+            if !is_local_minimum(&distances, idx, jdx, conic_1.is_closed(), conic_2.is_closed()) {
+                continue;
+            }
+            let (nu_1, nu_2, d2) = refine(&conic_1, &conic_2, *nu_1, *nu_2);
+            if d2 < best.distance {
+                best = Moid { distance: d2, true_anomaly_1: nu_1, true_anomaly_2: nu_2 };
+            }
+        }
+    }
+
+    best.distance = best.distance.sqrt();
+    Ok(best)
+}
+
+fn anomaly_grid(conic: &Conic) -> Vec<f64> {
+    let (lo, hi) = conic.anomaly_range();
+    let n = if conic.is_closed() { GRID_SIZE } else { GRID_SIZE - 1 };
+    (0..GRID_SIZE).map(|idx| lo + (hi - lo) * (idx as f64) / (n as f64)).collect()
+}
+
+/// Check whether a grid cell is no larger than any of its eight neighbours. Closed orbits wrap around.
+fn is_local_minimum(distances: &[Vec<f64>], idx: usize, jdx: usize, closed_1: bool, closed_2: bool) -> bool {
+    let value = distances[idx][jdx];
+    for di in [-1_i64, 0, 1] {
+        for dj in [-1_i64, 0, 1] {
+            if di == 0 && dj == 0 {
+                continue;
+            }
+            let Some(ii) = neighbour(idx, di, closed_1) else { continue };
+            let Some(jj) = neighbour(jdx, dj, closed_2) else { continue };
+            if distances[ii][jj] < value {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn neighbour(idx: usize, offset: i64, closed: bool) -> Option<usize> {
+    let n = GRID_SIZE as i64;
+    let k = idx as i64 + offset;
+    if closed {
+        Some(k.rem_euclid(n) as usize)
+    } else if (0..n).contains(&k) {
+        Some(k as usize)
+    } else {
+        None
+    }
+}
+
+/// Polish a candidate minimum of the squared distance with a damped Newton iteration,
+/// falling back to gradient descent where the Hessian is not positive definite.
+fn refine(conic_1: &Conic, conic_2: &Conic, nu_1: f64, nu_2: f64) -> (f64, f64, f64) {
+
+    let squared_distance = |a: f64, b: f64| (conic_1.position(a).0 - conic_2.position(b).0).norm_squared();
+
+    let mut nu_1 = nu_1;
+    let mut nu_2 = nu_2;
+    let mut d2 = squared_distance(nu_1, nu_2);
+
+    for _ in 0..100 {
+        let (r1, dr1, ddr1) = conic_1.position(nu_1);
+        let (r2, dr2, ddr2) = conic_2.position(nu_2);
+        let delta = r1 - r2;
+
+        let g1 = 2.0 * delta.dot(&dr1);
+        let g2 = -2.0 * delta.dot(&dr2);
+
+        let h11 = 2.0 * (dr1.dot(&dr1) + delta.dot(&ddr1));
+        let h22 = 2.0 * (dr2.dot(&dr2) - delta.dot(&ddr2));
+        let h12 = -2.0 * dr1.dot(&dr2);
+        let det = h11 * h22 - h12 * h12;
+
+        let (mut step_1, mut step_2) = if h11 > 0.0 && det > 0.0 {
+            (-(h22 * g1 - h12 * g2) / det, -(h11 * g2 - h12 * g1) / det)
+        } else {
+            let scale = 1e-3 / (g1.abs() + g2.abs()).max(1e-300);
+            (-g1 * scale, -g2 * scale)
+        };
+
+        // backtrack until the step reduces the distance
+        let mut improved = false;
+        for _ in 0..50 {
+            let trial_1 = conic_1.wrap(nu_1 + step_1);
+            let trial_2 = conic_2.wrap(nu_2 + step_2);
+            let trial = squared_distance(trial_1, trial_2);
+            if trial <= d2 {
+                improved = trial < d2;
+                nu_1 = trial_1;
+                nu_2 = trial_2;
+                d2 = trial;
+                break;
+            }
+            step_1 *= 0.5;
+            step_2 *= 0.5;
+        }
+
+        if !improved || (step_1.abs() + step_2.abs()) < 1e-14 {
+            break;
+        }
+    }
+
+    (nu_1, nu_2, d2)
+}
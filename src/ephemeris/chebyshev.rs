@@ -0,0 +1,113 @@
+use nalgebra::Vector3;
+use serde::{Serialize, Deserialize};
+
+/// Calculate the Chebyshev nodes of a span, at which a series of a given degree is fit.
+///
+/// # Arguments
+///
+/// * `start` - The start of the span (TDB Julian date)
+/// * `end` - The end of the span (TDB Julian date)
+/// * `degree` - The degree of the series
+///
+/// # Returns
+///
+/// * The degree + 1 nodes (TDB Julian dates)
+pub fn calc_chebyshev_nodes(start: f64, end: f64, degree: usize) -> Vec<f64> {
+    let (mid, half) = (0.5 * (start + end), 0.5 * (end - start));
+    (0..=degree).map(|k| {
+        let x = (std::f64::consts::PI * (k as f64 + 0.5) / (degree as f64 + 1.0)).cos();
+        mid + half * x
+    }).collect()
+}
+
+/// A Chebyshev series for a position over a span of time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChebyshevSegment {
+    /// The start of the span (TDB Julian date)
+    pub start: f64,
+    /// The end of the span (TDB Julian date)
+    pub end: f64,
+    /// The coefficients of the series, from degree zero up
+    pub coefficients: Vec<Vector3<f64>>,
+}
+
+impl ChebyshevSegment {
+
+    /// Fit a series to positions sampled at the Chebyshev nodes of a span.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the span (TDB Julian date)
+    /// * `end` - The end of the span (TDB Julian date)
+    /// * `samples` - The positions at the nodes from `calc_chebyshev_nodes`, whose number sets the degree
+    ///
+    /// # Returns
+    ///
+    /// * The ChebyshevSegment
+    pub fn from_samples(start: f64, end: f64, samples: &[Vector3<f64>]) -> Self {
+        let n = samples.len();
+        let coefficients = (0..n).map(|j| {
+            let mut sum = Vector3::zeros();
+            for (k, sample) in samples.iter().enumerate() {
+                sum += sample * (std::f64::consts::PI * j as f64 * (k as f64 + 0.5) / n as f64).cos();
+            }
+            let scale = if j == 0 { 1.0 } else { 2.0 } / n as f64;
+            sum * scale
+        }).collect();
+        ChebyshevSegment { start, end, coefficients }
+    }
+
+    /// Whether an epoch falls inside the span.
+    pub fn contains(&self, epoch: f64) -> bool {
+        epoch >= self.start && epoch <= self.end
+    }
+
+    /// Evaluate the position and velocity at an epoch inside the span.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch (TDB Julian date)
+    ///
+    /// # Returns
+    ///
+    /// * The position (au) and velocity (au/day)
+    pub fn state(&self, epoch: f64) -> (Vector3<f64>, Vector3<f64>) {
+        let half = 0.5 * (self.end - self.start);
+        let x = (epoch - 0.5 * (self.start + self.end)) / half;
+
+        // T_k and its derivative by the recurrences T_k+1 = 2x T_k - T_k-1 and T'_k+1 = 2 T_k + 2x T'_k - T'_k-1
+        let (mut t_prev, mut t) = (1.0, x);
+        let (mut dt_prev, mut dt) = (0.0, 1.0);
+        let mut position = self.coefficients[0];
+        let mut velocity = Vector3::zeros();
+        for coefficient in self.coefficients.iter().skip(1) {
+            position += coefficient * t;
+            velocity += coefficient * dt;
+            let t_next = 2.0 * x * t - t_prev;
+            let dt_next = 2.0 * t + 2.0 * x * dt - dt_prev;
+            (t_prev, t) = (t, t_next);
+            (dt_prev, dt) = (dt, dt_next);
+        }
+        (position, velocity / half)
+    }
+
+    /// Evaluate the position at an epoch inside the span.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch (TDB Julian date)
+    ///
+    /// # Returns
+    ///
+    /// * The position (au)
+    pub fn position(&self, epoch: f64) -> Vector3<f64> {
+        let x = (epoch - 0.5 * (self.start + self.end)) / (0.5 * (self.end - self.start));
+        let (mut t_prev, mut t) = (1.0, x);
+        let mut position = self.coefficients[0];
+        for coefficient in self.coefficients.iter().skip(1) {
+            position += coefficient * t;
+            (t_prev, t) = (t, 2.0 * x * t - t_prev);
+        }
+        position
+    }
+}
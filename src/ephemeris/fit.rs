@@ -0,0 +1,264 @@
+use nalgebra::Vector3;
+use serde::{Serialize, Deserialize};
+
+use crate::{Observation, Observatory, Observer, Origin, Properties, ReferencePlane, SpaceRock, Time};
+use crate::constants::SPEED_OF_LIGHT;
+use crate::ephemeris::chebyshev::{ChebyshevSegment, calc_chebyshev_nodes};
+use crate::observing::CorrectionLevel;
+
+/// The degree of the Chebyshev series of each segment.
+const DEGREE: usize = 12;
+
+/// The longest segment (days). Segments are halved from this until they meet the tolerance.
+const MAX_SEGMENT: f64 = 32.0;
+
+/// The shortest segment (days), below which a fit fails.
+const MIN_SEGMENT: f64 = 1.0 / 1440.0;
+
+/// The tolerance on the light travel time when observing from an ephemeris (days).
+const LIGHT_TIME_TOLERANCE: f64 = 1e-12;
+
+/// How far from the origin an observer may be and still see the body from the start of its
+/// ephemeris (au). The span is extended back by the light travel time over this distance
+/// and the distance of the body from the origin.
+const LIGHT_TIME_REACH: f64 = 2.0;
+
+/// A precomputed ephemeris of a body or an observer: piecewise Chebyshev series for its position
+/// over a span of time, fit to a propagator to within a tolerance. Evaluating it costs a few
+/// dozen multiplications, so it replaces repeated propagations and SPICE calls when the same
+/// bodies are observed at many epochs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ephemeris {
+    pub name: String,
+    pub reference_plane: ReferencePlane,
+    pub origin: Origin,
+    pub properties: Option<Properties>,
+    pub segments: Vec<ChebyshevSegment>,
+}
+
+impl Ephemeris {
+
+    /// Fit an ephemeris to a propagator over a span of time. Each segment is checked against the
+    /// propagator between its nodes, and halved until its error is below the tolerance. The span
+    /// starts early by the light travel time to the body, so that it can be observed at `start`
+    /// by observers within a few au of the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the body
+    /// * `start` - The start of the span
+    /// * `end` - The end of the span
+    /// * `tolerance` - The largest error allowed in the position (au)
+    /// * `propagate` - Gives the state of the body at an epoch, always in the same reference plane and origin
+    ///
+    /// # Returns
+    ///
+    /// * The Ephemeris
+    pub fn fit<F>(name: &str, start: &Time, end: &Time, tolerance: f64, mut propagate: F) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: FnMut(&Time) -> Result<SpaceRock, Box<dyn std::error::Error>>,
+    {
+        let (t0, t1) = (start.tdb().jd(), end.tdb().jd());
+        if t1 <= t0 {
+            return Err("The end of an ephemeris must be after its start".into());
+        }
+
+        let first = propagate(&Time::new(t0, "tdb", "jd")?)?;
+        let t0 = t0 - (first.position.norm() + LIGHT_TIME_REACH) / SPEED_OF_LIGHT;
+        let mut sample = |epoch: f64| -> Result<Vector3<f64>, Box<dyn std::error::Error>> {
+            let rock = propagate(&Time::new(epoch, "tdb", "jd")?)?;
+            if rock.reference_plane != first.reference_plane || rock.origin != first.origin {
+                return Err(format!("The propagator of {} changed its reference plane or origin", name).into());
+            }
+            Ok(rock.position)
+        };
+
+        // work through the spans in order, splitting those which miss the tolerance
+        let pieces = ((t1 - t0) / MAX_SEGMENT).ceil() as usize;
+        let width = (t1 - t0) / pieces as f64;
+        let mut spans: Vec<(f64, f64)> = (0..pieces).rev().map(|i| (t0 + i as f64 * width, t0 + (i + 1) as f64 * width)).collect();
+        let mut segments = Vec::new();
+
+        while let Some((a, b)) = spans.pop() {
+            let samples = calc_chebyshev_nodes(a, b, DEGREE).into_iter().map(&mut sample).collect::<Result<Vec<_>, _>>()?;
+            let segment = ChebyshevSegment::from_samples(a, b, &samples);
+
+            // the error is largest between the nodes and at the ends
+            let mut error: f64 = 0.0;
+            for k in 0..=DEGREE + 1 {
+                let x = (std::f64::consts::PI * k as f64 / (DEGREE as f64 + 1.0)).cos();
+                let epoch = 0.5 * (a + b) + 0.5 * (b - a) * x;
+                error = error.max((segment.position(epoch) - sample(epoch)?).norm());
+            }
+
+            if error <= tolerance {
+                segments.push(segment);
+            } else if 0.5 * (b - a) < MIN_SEGMENT {
+                return Err(format!("Could not fit an ephemeris of {} to within {} au", name, tolerance).into());
+            } else {
+                let mid = 0.5 * (a + b);
+                spans.push((mid, b));
+                spans.push((a, mid));
+            }
+        }
+
+        Ok(Ephemeris {
+            name: name.to_string(),
+            reference_plane: first.reference_plane,
+            origin: first.origin,
+            properties: first.properties,
+            segments,
+        })
+    }
+
+    /// Fit an ephemeris to the two-body orbit of a SpaceRock.
+    ///
+    /// # Arguments
+    ///
+    /// * `rock` - The SpaceRock
+    /// * `start` - The start of the span
+    /// * `end` - The end of the span
+    /// * `tolerance` - The largest error allowed in the position (au)
+    ///
+    /// # Returns
+    ///
+    /// * The Ephemeris
+    pub fn from_spacerock(rock: &SpaceRock, start: &Time, end: &Time, tolerance: f64) -> Result<Self, Box<dyn std::error::Error>> {
+        Ephemeris::fit(&rock.name, start, end, tolerance, |epoch| rock.analytic_at(epoch))
+    }
+
+    /// Fit an ephemeris to an observatory, to stand in for its SPICE lookups.
+    ///
+    /// # Arguments
+    ///
+    /// * `observatory` - The observatory
+    /// * `start` - The start of the span
+    /// * `end` - The end of the span
+    /// * `reference_plane` - The reference plane of the ephemeris
+    /// * `origin` - The origin of the ephemeris
+    /// * `tolerance` - The largest error allowed in the position (au)
+    ///
+    /// # Returns
+    ///
+    /// * The Ephemeris
+    pub fn from_observatory(observatory: &Observatory, start: &Time, end: &Time, reference_plane: &str, origin: &str, tolerance: f64) -> Result<Self, Box<dyn std::error::Error>> {
+        Ephemeris::fit(&observatory.name(), start, end, tolerance, |epoch| Ok(observatory.at(epoch, reference_plane, origin)?.spacerock))
+    }
+
+    /// The start of the span of the ephemeris (TDB Julian date).
+    pub fn start(&self) -> f64 {
+        self.segments.first().map(|segment| segment.start).unwrap_or(f64::NAN)
+    }
+
+    /// The end of the span of the ephemeris (TDB Julian date).
+    pub fn end(&self) -> f64 {
+        self.segments.last().map(|segment| segment.end).unwrap_or(f64::NAN)
+    }
+
+    fn segment(&self, epoch: f64) -> Result<&ChebyshevSegment, Box<dyn std::error::Error>> {
+        let index = self.segments.partition_point(|segment| segment.end < epoch);
+        match self.segments.get(index) {
+            Some(segment) if segment.contains(epoch) => Ok(segment),
+            _ => Err(format!("Epoch {} is outside the ephemeris of {}", epoch, self.name).into()),
+        }
+    }
+
+    /// Evaluate the position at an epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch (TDB Julian date)
+    ///
+    /// # Returns
+    ///
+    /// * The position (au)
+    pub fn position(&self, epoch: f64) -> Result<Vector3<f64>, Box<dyn std::error::Error>> {
+        Ok(self.segment(epoch)?.position(epoch))
+    }
+
+    /// Evaluate the position and velocity at an epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch (TDB Julian date)
+    ///
+    /// # Returns
+    ///
+    /// * The position (au) and velocity (au/day)
+    pub fn state(&self, epoch: f64) -> Result<(Vector3<f64>, Vector3<f64>), Box<dyn std::error::Error>> {
+        Ok(self.segment(epoch)?.state(epoch))
+    }
+
+    /// The SpaceRock at an epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch
+    ///
+    /// # Returns
+    ///
+    /// * The SpaceRock
+    pub fn at(&self, epoch: &Time) -> Result<SpaceRock, Box<dyn std::error::Error>> {
+        let (position, velocity) = self.state(epoch.tdb().jd())?;
+        Ok(SpaceRock {
+            name: self.name.clone(),
+            epoch: epoch.clone(),
+            reference_plane: self.reference_plane.clone(),
+            origin: self.origin.clone(),
+            position,
+            velocity,
+            properties: self.properties.clone(),
+        })
+    }
+
+    /// Calculate the astrometric right ascension and declination of the body, seen from an
+    /// observer with its own ephemeris, solving for the light travel time. The motion of the
+    /// common origin over the light travel time is neglected, which is exact for the SSB and
+    /// good to a few milliarcseconds for the Sun.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The ephemeris of the observer, in the same reference plane and origin
+    /// * `epoch` - The epoch of the observation (TDB Julian date)
+    ///
+    /// # Returns
+    ///
+    /// * The right ascension and declination in the reference plane (radians)
+    pub fn calc_radec(&self, observer: &Ephemeris, epoch: f64) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+        if self.reference_plane != observer.reference_plane || self.origin != observer.origin {
+            return Err("The ephemerides of the body and the observer have different reference planes or origins".into());
+        }
+
+        let observer_position = observer.position(epoch)?;
+        let mut ltt = 0.0;
+        let mut direction = self.position(epoch)? - observer_position;
+        for _ in 0..10 {
+            let new_ltt = direction.norm() / SPEED_OF_LIGHT;
+            if (new_ltt - ltt).abs() < LIGHT_TIME_TOLERANCE {
+                break;
+            }
+            ltt = new_ltt;
+            direction = self.position(epoch - ltt)? - observer_position;
+        }
+
+        let ra = direction.y.atan2(direction.x).rem_euclid(std::f64::consts::TAU);
+        let dec = (direction.z / direction.norm()).asin();
+        Ok((ra, dec))
+    }
+
+    /// Observe the body from an observer, propagating it with the ephemeris.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer
+    /// * `correction` - The level of corrections to apply
+    ///
+    /// # Returns
+    ///
+    /// * The Observation, with a magnitude if the body has photometric properties
+    pub fn observe(&self, observer: &Observer, correction: &CorrectionLevel) -> Result<Observation, Box<dyn std::error::Error>> {
+        let mut rock = self.at(&observer.epoch())?;
+        let origin_velocity = rock.origin_velocity()?;
+        rock.observe_with_propagator(observer, correction, &origin_velocity, |epoch| self.at(epoch))
+    }
+}
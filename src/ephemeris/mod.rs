@@ -0,0 +1,5 @@
+pub mod chebyshev;
+    pub use chebyshev::{ChebyshevSegment, calc_chebyshev_nodes};
+
+pub mod fit;
+    pub use fit::Ephemeris;
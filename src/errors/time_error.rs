@@ -2,15 +2,17 @@
 pub enum TimeError {
     InvalidTimeScale(String),
     InvalidTimeFormat(String),
+    ParseError(String),
 }
 
 impl std::fmt::Display for TimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TimeError::InvalidTimeScale(s) => write!(f, "Invalid timescale: {}. Needs to be 'utc', 'tdb', 'tt', or 'tai'.", s),
-            TimeError::InvalidTimeFormat(s) => write!(f, "Invalid time format: {}. Needs to be 'jd' or 'mjd'.", s),
+            TimeError::InvalidTimeScale(s) => write!(f, "Invalid timescale: {}. Needs to be 'utc', 'tdb', 'tt', 'tai', 'tcb', 'tcg', 'ut1' or 'gps'.", s),
+            TimeError::InvalidTimeFormat(s) => write!(f, "Invalid time format: {}. Needs to be 'jd', 'mjd', 'decimalyear', 'unix' or 'gps'.", s),
+            TimeError::ParseError(s) => write!(f, "Could not parse time {}", s),
         }
     }
 }
 
-impl std::error::Error for TimeError {}
\ No newline at end of file
+impl std::error::Error for TimeError {}
@@ -1,5 +1,6 @@
 
 pub mod transforms;
+    #[allow(deprecated)]
     pub use transforms::correct_for_ltt;
 
 pub mod time;
@@ -25,6 +26,9 @@ pub mod data;
 pub mod properties;
     pub use properties::Properties;
 
+pub mod photometry;
+    pub use photometry::{PhaseFunction, Band};
+
 pub mod errors;
     pub use errors::OriginError;
     pub use errors::OrbitError;
@@ -40,8 +44,21 @@ pub mod structs;
 pub mod observing;
     pub use observing::{Observatory, Observer, Observation};
 
+pub mod ephemeris;
+    pub use ephemeris::Ephemeris;
+
+pub mod survey;
+    pub use survey::{Exposure, DetectionModel};
+
+pub mod population;
+    pub use population::{Population, HDistribution};
+
 pub mod utils; // Putting the 'find_closest_match' function in a separate module
 
 pub mod orbfit;
     pub use orbfit::gauss;
-//     pub use orbfit::fitter;
\ No newline at end of file
+//     pub use orbfit::fitter;
+
+pub mod dynamics;
+    pub use dynamics::Moid;
+    pub use dynamics::DynamicalClass;
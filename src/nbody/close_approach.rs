@@ -0,0 +1,92 @@
+use crate::time::Time;
+
+use nalgebra::Vector3;
+
+/// A close approach of a particle to a massive body in a Simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloseApproach {
+    /// The name of the body being approached
+    pub body: String,
+    /// The time of closest approach (TDB)
+    pub epoch: Time,
+    /// The nominal distance at closest approach (au)
+    pub distance: f64,
+    /// The relative speed at closest approach (au/day)
+    pub relative_velocity: f64,
+    /// The hyperbolic excess velocity of the encounter (au/day). Zero if the particle is bound to the body.
+    pub v_infinity: f64,
+    /// The impact parameter, i.e. the length of the B-vector (au)
+    pub impact_parameter: f64,
+    /// The target-plane coordinate perpendicular to the projection of the body's velocity (au)
+    pub xi: f64,
+    /// The target-plane coordinate anti-parallel to the projection of the body's velocity (au)
+    pub zeta: f64,
+}
+
+/// Calculate the target-plane (B-plane) coordinates of an encounter.
+///
+/// The particle's planetocentric state is converted to a hyperbola about the body. The η-axis points along
+/// the incoming asymptote, the ζ-axis points opposite to the projection of the body's velocity onto the
+/// target plane, and the ξ-axis completes the right-handed triad, following Valsecchi et al. (2003).
+///
+/// # Arguments
+/// * `position` - The position of the particle relative to the body (au)
+/// * `velocity` - The velocity of the particle relative to the body (au/day)
+/// * `body_velocity` - The velocity of the body in the frame of the simulation (au/day)
+/// * `mu` - The gravitational parameter of the body (au^3/day^2)
+///
+/// # Returns
+/// * `(v_infinity, impact_parameter, xi, zeta)`. If the particle is bound to the body the target plane is
+///   undefined, and `(0.0, NaN, NaN, NaN)` is returned.
+pub fn calc_b_plane(position: &Vector3<f64>, velocity: &Vector3<f64>, body_velocity: &Vector3<f64>, mu: f64) -> (f64, f64, f64, f64) {
+
+    let r = position.norm();
+    let v_infinity_squared = velocity.norm_squared() - 2.0 * mu / r;
+    if v_infinity_squared <= 0.0 {
+        return (0.0, f64::NAN, f64::NAN, f64::NAN);
+    }
+    let v_infinity = v_infinity_squared.sqrt();
+
+    let hvec = position.cross(velocity);
+    let h = hvec.norm();
+    let impact_parameter = h / v_infinity;
+
+    // incoming asymptote and B-vector directions. For a very weak encounter (or a rectilinear one) the
+    // hyperbola degenerates, and the straight-line geometry is used instead.
+    let evec = velocity.cross(&hvec) / mu - position / r;
+    let e = evec.norm();
+    let (s_hat, b_hat) = if mu > 0.0 && h > 0.0 && e.is_finite() && e > 1.0 {
+        let hhat = hvec / h;
+        let phat = evec / e;
+        let qhat = hhat.cross(&phat);
+        let s_hat = phat / e + qhat * (e * e - 1.0).sqrt() / e;
+        (s_hat, s_hat.cross(&hhat))
+    } else {
+        let s_hat = velocity.normalize();
+        let b = position - s_hat * position.dot(&s_hat);
+        let b_hat = if b.norm() > 0.0 { b.normalize() } else { Vector3::zeros() };
+        (s_hat, b_hat)
+    };
+    let bvec = impact_parameter * b_hat;
+
+    let projected = body_velocity - s_hat * body_velocity.dot(&s_hat);
+    let zeta_hat = -projected.normalize();
+    let xi_hat = s_hat.cross(&zeta_hat);
+
+    (v_infinity, impact_parameter, bvec.dot(&xi_hat), bvec.dot(&zeta_hat))
+}
+
+/// The relative state of a particle and a body at a single integrator step.
+#[derive(Debug, Clone)]
+pub(crate) struct EncounterState {
+    pub epoch: Time,
+    pub position: Vector3<f64>,
+    pub velocity: Vector3<f64>,
+    pub body_velocity: Vector3<f64>,
+}
+
+impl EncounterState {
+    pub fn range_rate(&self) -> f64 {
+        self.position.dot(&self.velocity)
+    }
+}
@@ -290,7 +290,7 @@ impl Integrator for IAS15 {
                 perturber.position = initial_positions[idx];
                 perturber.velocity = initial_velocities[idx];
                 accelerations[idx] = initial_accelerations[idx];
-                perturber.epoch.epoch = epoch.epoch;
+                (perturber.epoch.day, perturber.epoch.fraction) = epoch.jd_parts();
             }
 
             if self.last_timestep != 0.0 {
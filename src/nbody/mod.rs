@@ -2,6 +2,9 @@ pub mod simulation;
 
 pub mod forces;
 
+pub mod close_approach;
+    pub use self::close_approach::{CloseApproach, calc_b_plane};
+
 pub mod integrators;
     pub use self::integrators::Integrator;
     pub use self::integrators::Leapfrog;
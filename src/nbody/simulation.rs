@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use crate::SpaceRock;
+use crate::{SpaceRock, Observer, Observation};
 use crate::constants::GRAVITATIONAL_CONSTANT;
+use crate::observing::CorrectionLevel;
 use crate::time::Time;
 use crate::{ReferencePlane, Origin};
 use crate::errors::SimulationError;
@@ -9,11 +10,13 @@ use crate::errors::SimulationError;
 
 use crate::nbody::forces::{Force, NewtonianGravity};
 use crate::nbody::integrators::{Integrator, IAS15};
+use crate::nbody::close_approach::{CloseApproach, EncounterState, calc_b_plane};
 
 
 use nalgebra::Vector3;
 
 
+#[derive(Clone)]
 pub struct Simulation {
     pub particles: Vec<SpaceRock>,
     pub epoch: Time,
@@ -312,31 +315,9 @@ impl Simulation {
 
 
         loop {
-            let dt = epoch.tdb().jd() - self.epoch.tdb().jd();
-
-            // done integrating
-            if dt.abs() < 1e-16 {
+            if self.step_towards(epoch) {
                 break;
             }
-
-            // if we're within a timestep of the epoch, just take a step of that size
-            if dt.abs() < self.integrator.timestep().abs() {
-                let last_timestep = self.integrator.timestep().clone();
-                self.integrator.set_timestep(dt);
-                self.step();
-                self.integrator.set_timestep(last_timestep);
-                break;
-            }
-
-            // if the timestep is negative, make sure the integrator is set to negative
-            if dt < 0.0 {
-                if self.integrator.timestep() > 0.0 {
-                    self.integrator.set_timestep(-self.integrator.timestep());
-                }
-            } else if self.integrator.timestep() < 0.0 {
-                self.integrator.set_timestep(-self.integrator.timestep());
-            }
-            self.step();
         }
         
         // let dt = &epoch - &self.epoch;
@@ -352,6 +333,178 @@ impl Simulation {
         // self.integrator.set_timestep(old_timestep);
     }
 
+    /// Take a single step towards an epoch. If we're within a timestep of the epoch, the step is shortened
+    /// to land on it exactly.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch to step towards.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the simulation has reached the epoch.
+    fn step_towards(&mut self, epoch: &Time) -> bool {
+        let dt = epoch.tdb().jd() - self.epoch.tdb().jd();
+
+        // done integrating
+        if dt.abs() < 1e-16 {
+            return true;
+        }
+
+        // if we're within a timestep of the epoch, just take a step of that size
+        if dt.abs() < self.integrator.timestep().abs() {
+            let last_timestep = self.integrator.timestep();
+            self.integrator.set_timestep(dt);
+            self.step();
+            self.integrator.set_timestep(last_timestep);
+            return true;
+        }
+
+        // if the timestep is negative, make sure the integrator is set to negative
+        if dt < 0.0 {
+            if self.integrator.timestep() > 0.0 {
+                self.integrator.set_timestep(-self.integrator.timestep());
+            }
+        } else if self.integrator.timestep() < 0.0 {
+            self.integrator.set_timestep(-self.integrator.timestep());
+        }
+        self.step();
+        false
+    }
+
+    /// Integrate the simulation to a new epoch, recording every close approach of a particle to a body.
+    /// The approaches are found by monitoring the range-rate between the two at each step, and the time of
+    /// closest approach is located by interpolating the relative motion across the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The new epoch to integrate to.
+    /// * `name` - The name of the particle, e.g. a test particle for an NEO.
+    /// * `body` - The name of the massive body being approached, e.g. "earth".
+    /// * `threshold` - The maximum distance of the approaches to report (au).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<CloseApproach>, Box<dyn std::error::Error>>` - The close approaches, in the order they occurred.
+    pub fn close_approaches(&mut self, epoch: &Time, name: &str, body: &str, threshold: f64) -> Result<Vec<CloseApproach>, Box<dyn std::error::Error>> {
+
+        self.get_particle(name)?;
+        let mu = self.get_particle(body)?.mass() * GRAVITATIONAL_CONSTANT;
+        if mu == 0.0 {
+            return Err(format!("The body {} is massless, so it has no target plane", body).into());
+        }
+
+        let dt = epoch.tdb().jd() - self.epoch.tdb().jd();
+        if dt < 0.0 && self.integrator.timestep() > 0.0 {
+            self.integrator.set_timestep(-self.integrator.timestep());
+        }
+
+        let mut approaches = Vec::new();
+        let mut previous = self.encounter_state(&self.particles, &self.epoch, name, body);
+        loop {
+            let particles = self.particles.clone();
+            let integrator = self.integrator.clone();
+
+            let done = self.step_towards(epoch);
+            let current = self.encounter_state(&self.particles, &self.epoch, name, body);
+            let dt = current.epoch.jd() - previous.epoch.jd();
+
+            // the range-rate changes sign from negative to positive at a minimum, whichever direction we integrate in
+            if previous.range_rate() * dt < 0.0 && current.range_rate() * dt >= 0.0 {
+                let closest = self.refine_closest_approach(&particles, integrator.as_ref(), &previous, dt, name, body);
+                let distance = closest.position.norm();
+                if distance <= threshold {
+                    let (v_infinity, impact_parameter, xi, zeta) = calc_b_plane(&closest.position, &closest.velocity, &closest.body_velocity, mu);
+                    approaches.push(CloseApproach {
+                        body: body.to_string(),
+                        epoch: closest.epoch,
+                        distance,
+                        relative_velocity: closest.velocity.norm(),
+                        v_infinity,
+                        impact_parameter,
+                        xi,
+                        zeta,
+                    });
+                }
+            }
+
+            if done {
+                break;
+            }
+            previous = current;
+        }
+
+        Ok(approaches)
+    }
+
+    fn encounter_state(&self, particles: &[SpaceRock], epoch: &Time, name: &str, body: &str) -> EncounterState {
+        let particle = &particles[self.particle_index_map[name]];
+        let target = &particles[self.particle_index_map[body]];
+        EncounterState {
+            epoch: epoch.clone(),
+            position: particle.position - target.position,
+            velocity: particle.velocity - target.velocity,
+            body_velocity: target.velocity,
+        }
+    }
+
+    /// Locate the closest approach within a step by re-integrating from the start of the step with a shortened
+    /// timestep, and solving for the zero of the range-rate with the Illinois variant of regula falsi.
+    fn refine_closest_approach(&self, particles: &[SpaceRock], integrator: &(dyn Integrator + Send + Sync),
+                               start: &EncounterState, dt: f64, name: &str, body: &str) -> EncounterState {
+
+        let substep = |tau: f64| -> EncounterState {
+            if tau == 0.0 {
+                return start.clone();
+            }
+            let mut particles = particles.to_vec();
+            let mut integrator = integrator.clone_box();
+            let mut epoch = start.epoch.clone();
+            integrator.set_timestep(tau);
+            integrator.step(&mut particles, &mut epoch, &self.forces);
+            self.encounter_state(&particles, &epoch, name, body)
+        };
+
+        // scaled so that f(lo) < 0 <= f(hi)
+        let f = |state: &EncounterState| state.range_rate() * dt.signum();
+
+        let mut lo = 0.0;
+        let mut hi = dt;
+        let mut f_lo = f(start);
+        let mut f_hi = f(&substep(dt));
+        let mut side = 0;
+
+        let mut best = start.clone();
+        let mut last_tau = f64::NAN;
+        for _ in 0..100 {
+            let tau = (lo * f_hi - hi * f_lo) / (f_hi - f_lo);
+            best = substep(tau);
+            let f_tau = f(&best);
+
+            if f_tau == 0.0 || (tau - last_tau).abs() < 1e-12 {
+                break;
+            }
+            last_tau = tau;
+
+            if f_tau < 0.0 {
+                lo = tau;
+                f_lo = f_tau;
+                if side == -1 {
+                    f_hi *= 0.5;
+                }
+                side = -1;
+            } else {
+                hi = tau;
+                f_hi = f_tau;
+                if side == 1 {
+                    f_lo *= 0.5;
+                }
+                side = 1;
+            }
+        }
+        best
+    }
+
     /// Get a particle from the simulation by name.
     ///
     /// # Arguments
@@ -370,6 +523,37 @@ impl Simulation {
         Err(SimulationError::ParticleNotFound(name.to_string()))
     }
 
+    /// Observe a particle of the simulation. The simulation is integrated to the epoch of the
+    /// observer, and a copy of it is integrated back to the epoch at which the light was emitted,
+    /// so the light travel time correction includes the perturbations.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the particle to observe.
+    /// * `observer` - The observer, in the reference plane and origin of the simulation.
+    /// * `correction` - The corrections to apply.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Observation, Box<dyn std::error::Error>>` - The observation of the particle.
+    pub fn observe(&mut self, name: &str, observer: &Observer, correction: &CorrectionLevel) -> Result<Observation, Box<dyn std::error::Error>> {
+        let epoch = observer.epoch();
+        self.integrate(&epoch);
+
+        let mut rock = self.get_particle(name)?.clone();
+        rock.epoch = epoch.clone();
+
+        let origin_velocity = rock.origin_velocity()?;
+        let sim = &*self;
+        rock.observe_with_propagator(observer, correction, &origin_velocity, |emission| {
+            let mut sim = sim.clone();
+            sim.integrate(emission);
+            let mut particle = sim.get_particle(name)?.clone();
+            particle.epoch = emission.clone();
+            Ok(particle)
+        })
+    }
+
     /// Get the energy of the simulation.
     pub fn energy(&self) -> f64 {
         let mut kinetic_energy = 0.0;
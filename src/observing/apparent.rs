@@ -0,0 +1,328 @@
+use nalgebra::Vector3;
+
+use crate::Observer;
+use crate::constants::{KM_TO_AU, SPEED_OF_LIGHT};
+use crate::observing::horizon::calc_horizon_matrix;
+
+/// The Schwarzschild radius of the Sun (au), 2GM/c^2.
+const SCHWARZSCHILD_RADIUS: f64 = 1.97412574336e-8;
+
+/// The smallest horizontal component of a direction before refraction is computed, which keeps
+/// the zenith well behaved.
+const MIN_HORIZONTAL: f64 = 1e-6;
+
+/// The smallest sine of the elevation used in the refraction model. Below about 3 degrees the
+/// model is held fixed rather than diverging.
+const MIN_ELEVATION: f64 = 0.05;
+
+/// A body which deflects light on its way to the observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deflector {
+    /// The SPICE name of the body
+    pub name: &'static str,
+    /// The mass of the body (solar masses)
+    pub mass: f64,
+    /// The radius of the body (au), which limits the deflection of grazing rays
+    pub radius: f64,
+}
+
+/// The Sun and the planets other than the Earth, whose light deflection is applied to apparent
+/// places. Together they account for everything above a microarcsecond away from the limbs.
+pub const DEFLECTORS: [Deflector; 8] = [
+    Deflector { name: "sun", mass: 1.0, radius: 696_000.0 * KM_TO_AU },
+    Deflector { name: "mercury barycenter", mass: 1.0 / 6_023_600.0, radius: 2_439.7 * KM_TO_AU },
+    Deflector { name: "venus barycenter", mass: 1.0 / 408_523.71, radius: 6_051.8 * KM_TO_AU },
+    Deflector { name: "mars barycenter", mass: 1.0 / 3_098_708.0, radius: 3_389.5 * KM_TO_AU },
+    Deflector { name: "jupiter barycenter", mass: 1.0 / 1_047.348_6, radius: 71_492.0 * KM_TO_AU },
+    Deflector { name: "saturn barycenter", mass: 1.0 / 3_497.898, radius: 60_268.0 * KM_TO_AU },
+    Deflector { name: "uranus barycenter", mass: 1.0 / 22_902.98, radius: 25_559.0 * KM_TO_AU },
+    Deflector { name: "neptune barycenter", mass: 1.0 / 19_412.24, radius: 24_764.0 * KM_TO_AU },
+];
+
+/// The weather at the observer, for atmospheric refraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Atmosphere {
+    /// The pressure at the observer (hPa)
+    pub pressure: f64,
+    /// The ambient temperature at the observer (degrees Celsius)
+    pub temperature: f64,
+    /// The relative humidity at the observer (0 to 1)
+    pub relative_humidity: f64,
+    /// The effective wavelength of the observation (micrometers)
+    pub wavelength: f64,
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Atmosphere::standard(0.0)
+    }
+}
+
+impl Atmosphere {
+
+    /// A standard atmosphere at a height above sea level: the pressure of the standard
+    /// atmosphere, 10 degrees Celsius, 50% humidity and visible light.
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - The height of the observer above sea level (meters)
+    ///
+    /// # Returns
+    ///
+    /// * The Atmosphere
+    pub fn standard(height: f64) -> Self {
+        Atmosphere {
+            pressure: 1013.25 * (-height / (29.3 * 288.15)).exp(),
+            temperature: 10.0,
+            relative_humidity: 0.5,
+            wavelength: 0.55,
+        }
+    }
+
+    /// Calculate the constants A and B of the refraction model dZ = A tan Z + B tan^3 Z.
+    ///
+    /// # Returns
+    ///
+    /// * The constants A and B (radians)
+    pub fn refraction_constants(&self) -> (f64, f64) {
+        calc_refraction_constants(self.pressure, self.temperature, self.relative_humidity, self.wavelength)
+    }
+}
+
+/// How much of the path from the target to the detector is corrected for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CorrectionLevel {
+    /// Light-time corrected positions, to compare with catalog astrometry
+    Astrometric,
+    /// Astrometric positions with gravitational light deflection and stellar (annual and
+    /// diurnal) aberration, to compare with the true direction of the target
+    Apparent,
+    /// Apparent positions with atmospheric refraction, to point a telescope on the ground
+    Observed(Atmosphere),
+}
+
+impl CorrectionLevel {
+
+    /// Create a new CorrectionLevel from a string. The observed level uses a standard atmosphere
+    /// at sea level.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - One of "astrometric", "apparent" or "observed"
+    ///
+    /// # Returns
+    ///
+    /// * The CorrectionLevel
+    pub fn from_str(s: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match s.to_lowercase().as_str() {
+            "astrometric" => Ok(CorrectionLevel::Astrometric),
+            "apparent" => Ok(CorrectionLevel::Apparent),
+            "observed" => Ok(CorrectionLevel::Observed(Atmosphere::default())),
+            _ => Err(format!("Invalid correction level: {}", s).into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            CorrectionLevel::Astrometric => "astrometric",
+            CorrectionLevel::Apparent => "apparent",
+            CorrectionLevel::Observed(_) => "observed",
+        }
+    }
+}
+
+impl std::fmt::Display for CorrectionLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Deflect a direction for the gravity of a single body. Follows the SOFA routine iauLd.
+///
+/// # Arguments
+///
+/// * `mass` - The mass of the deflecting body (solar masses)
+/// * `p` - The direction from the observer to the source (unit vector)
+/// * `q` - The direction from the body to the source (unit vector)
+/// * `e` - The direction from the body to the observer (unit vector)
+/// * `em` - The distance from the body to the observer (au)
+/// * `dlim` - The deflection limiter, which keeps rays grazing the body finite
+///
+/// # Returns
+///
+/// * The deflected direction from the observer to the source
+pub fn calc_light_deflection(mass: f64, p: &Vector3<f64>, q: &Vector3<f64>, e: &Vector3<f64>, em: f64, dlim: f64) -> Vector3<f64> {
+    let qpe = q + e;
+    let qdqpe = q.dot(&qpe);
+    let w = mass * SCHWARZSCHILD_RADIUS / em / qdqpe.max(dlim);
+    let eq = e.cross(q);
+    let peq = p.cross(&eq);
+    p + w * peq
+}
+
+/// Apply stellar aberration to a direction, including the gravitational time dilation of the
+/// Sun. Follows the SOFA routine iauAb.
+///
+/// # Arguments
+///
+/// * `pnat` - The natural direction from the observer to the source (unit vector)
+/// * `v` - The barycentric velocity of the observer (units of c)
+/// * `s` - The distance from the Sun to the observer (au)
+/// * `bm1` - The reciprocal of the Lorentz factor, sqrt(1 - |v|^2)
+///
+/// # Returns
+///
+/// * The proper direction from the observer to the source (unit vector)
+pub fn calc_aberration(pnat: &Vector3<f64>, v: &Vector3<f64>, s: f64, bm1: f64) -> Vector3<f64> {
+    let pdv = pnat.dot(v);
+    let w1 = 1.0 + pdv / (1.0 + bm1);
+    let w2 = SCHWARZSCHILD_RADIUS / s;
+    let p = pnat * bm1 + w1 * v + w2 * (v - pdv * pnat);
+    p.normalize()
+}
+
+/// Calculate the constants A and B of the refraction model dZ = A tan Z + B tan^3 Z. Follows the
+/// SOFA routine iauRefco, which switches to a radio model above 100 micrometers.
+///
+/// # Arguments
+///
+/// * `pressure` - The pressure at the observer (hPa)
+/// * `temperature` - The ambient temperature at the observer (degrees Celsius)
+/// * `relative_humidity` - The relative humidity at the observer (0 to 1)
+/// * `wavelength` - The wavelength (micrometers)
+///
+/// # Returns
+///
+/// * The constants A and B (radians)
+pub fn calc_refraction_constants(pressure: f64, temperature: f64, relative_humidity: f64, wavelength: f64) -> (f64, f64) {
+    let optic = wavelength <= 100.0;
+
+    let t = temperature.clamp(-150.0, 200.0);
+    let p = pressure.clamp(0.0, 10000.0);
+    let r = relative_humidity.clamp(0.0, 1.0);
+    let w = wavelength.clamp(0.1, 1e6);
+
+    // water vapour pressure at the observer
+    let pw = if p > 0.0 {
+        let ps = 10.0_f64.powf((0.7859 + 0.03477 * t) / (1.0 + 0.00412 * t)) * (1.0 + p * (4.5e-6 + 6e-10 * t * t));
+        r * ps / (1.0 - (1.0 - r) * ps / p)
+    } else {
+        0.0
+    };
+
+    // refractive index minus 1 at the observer
+    let tk = t + 273.15;
+    let gamma = if optic {
+        let wlsq = w * w;
+        ((77.53484e-6 + (4.39108e-7 + 3.666e-9 / wlsq) / wlsq) * p - 11.2684e-6 * pw) / tk
+    } else {
+        (77.6890e-6 * p - (6.3938e-6 - 0.375463 / tk) * pw) / tk
+    };
+
+    // Stone's formula for beta, with empirical adjustments
+    let mut beta = 4.4474e-6 * tk;
+    if !optic {
+        beta -= 0.0074 * pw * beta;
+    }
+
+    (gamma * (1.0 - beta), -gamma * (beta - gamma / 2.0))
+}
+
+/// Refract a topocentric direction, raising it towards the zenith. Follows the refraction step of
+/// the SOFA routine iauAtioq.
+///
+/// # Arguments
+///
+/// * `direction` - The unrefracted direction in a horizon frame, with z towards the zenith (unit vector)
+/// * `a` - The refraction constant A (radians)
+/// * `b` - The refraction constant B (radians)
+///
+/// # Returns
+///
+/// * The refracted direction in the same frame
+pub fn calc_refraction(direction: &Vector3<f64>, a: f64, b: f64) -> Vector3<f64> {
+    let r = direction.x.hypot(direction.y);
+    let r = if r != 0.0 { r } else { MIN_HORIZONTAL };
+    let z = direction.z.max(MIN_ELEVATION);
+
+    // A tan Z + B tan^3 Z, with a Newton-Raphson correction
+    let tz = r / z;
+    let w = b * tz * tz;
+    let del = (a + w) * tz / (1.0 + (a + 3.0 * w) / (z * z));
+
+    let cosdel = 1.0 - del * del / 2.0;
+    let f = cosdel - del * direction.z / r;
+    Vector3::new(direction.x * f, direction.y * f, cosdel * direction.z + del * r)
+}
+
+/// Calculate the apparent direction of a target from its astrometric position, applying light
+/// deflection by the Sun and planets and then stellar aberration. This follows the SOFA routines
+/// iauLdn and iauAb, with the bodies backtracked to when the light passed them. The result is in
+/// the reference plane of the observer. The Sun and planets come from the loaded SPICE kernels.
+///
+/// # Arguments
+///
+/// * `position` - The light-time corrected position of the target relative to the observer (au)
+/// * `observer` - The observer
+///
+/// # Returns
+///
+/// * The apparent direction from the observer to the target (unit vector)
+pub fn calc_apparent_direction(position: &Vector3<f64>, observer: &Observer) -> Result<Vector3<f64>, Box<dyn std::error::Error>> {
+    let p = position.normalize();
+    let target = observer.position() + position;
+
+    let mut direction = p;
+    let mut sun_distance = 1.0;
+    for deflector in DEFLECTORS.iter() {
+        let body = observer.spacerock.spice_body(deflector.name)?;
+
+        // minus the time since the light passed the body, or zero if it has not reached it yet
+        let v = observer.position() - body.position;
+        let dt = (p.dot(&v) / SPEED_OF_LIGHT).min(0.0);
+        let passed = body.position + dt * body.velocity;
+
+        let ev = observer.position() - passed;
+        let em = ev.norm();
+        if deflector.name == "sun" {
+            sun_distance = em;
+        }
+
+        // a body does not deflect its own light
+        let qv = target - passed;
+        if qv.norm() < deflector.radius {
+            continue;
+        }
+
+        let dlim = 0.5 * (deflector.radius / em).powi(2);
+        direction = calc_light_deflection(deflector.mass, &direction, &qv.normalize(), &(ev / em), em, dlim);
+    }
+
+    // aberration depends on the velocity of the observer relative to the barycenter
+    let mut velocity = observer.velocity();
+    if observer.spacerock.origin.as_str() != "SSB" {
+        velocity -= observer.spacerock.spice_body("ssb")?.velocity;
+    }
+    let v = velocity / SPEED_OF_LIGHT;
+    let bm1 = (1.0 - v.norm_squared()).sqrt();
+    Ok(calc_aberration(&direction.normalize(), &v, sun_distance, bm1))
+}
+
+/// Calculate the observed direction of a target from a ground observatory, by refracting its
+/// apparent direction in the local horizon frame.
+///
+/// # Arguments
+///
+/// * `apparent` - The apparent direction from the observer to the target, in the reference plane of the observer
+/// * `observer` - The observer, which must be on the ground
+/// * `atmosphere` - The weather at the observer
+///
+/// # Returns
+///
+/// * The observed direction, in the reference plane of the observer (unit vector)
+pub fn calc_observed_direction(apparent: &Vector3<f64>, observer: &Observer, atmosphere: &Atmosphere) -> Result<Vector3<f64>, Box<dyn std::error::Error>> {
+    let rotation = calc_horizon_matrix(observer).map_err(|_| "Refraction needs an observatory on the ground")?;
+    let (a, b) = atmosphere.refraction_constants();
+    let observed = calc_refraction(&(rotation * apparent.normalize()), a, b);
+    Ok((rotation.transpose() * observed).normalize())
+}
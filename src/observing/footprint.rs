@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use nalgebra::Vector3;
+use rayon::prelude::*;
+
+use crate::{Observation, Observatory, Observer, SpaceRock, Time};
+use crate::observing::CorrectionLevel;
+
+/// How far outside a field a rock may fall in the analytic prefilter and still be observed
+/// (radians). This covers the shift from light travel time, which is at most v/c.
+pub(crate) const PREFILTER_MARGIN: f64 = 1e-3;
+
+/// The shape of a field of view. Sizes and vertices are in the tangent plane at the centre of the
+/// field (radians), with x along the rows and y along the columns of the field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldShape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+    Polygon { vertices: Vec<(f64, f64)> },
+}
+
+/// A field of view on the sky.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// The right ascension of the centre (radians)
+    pub ra: f64,
+    /// The declination of the centre (radians)
+    pub dec: f64,
+    /// The position angle of the y axis of the field, east of north (radians)
+    pub rotation: f64,
+    pub shape: FieldShape,
+}
+
+impl Field {
+
+    pub fn circle(ra: f64, dec: f64, radius: f64) -> Self {
+        Field { ra, dec, rotation: 0.0, shape: FieldShape::Circle { radius } }
+    }
+
+    pub fn rectangle(ra: f64, dec: f64, width: f64, height: f64, rotation: f64) -> Self {
+        Field { ra, dec, rotation, shape: FieldShape::Rectangle { width, height } }
+    }
+
+    pub fn polygon(ra: f64, dec: f64, vertices: Vec<(f64, f64)>, rotation: f64) -> Self {
+        Field { ra, dec, rotation, shape: FieldShape::Polygon { vertices } }
+    }
+
+    /// The unit vector to the centre of the field.
+    pub fn center(&self) -> Vector3<f64> {
+        Vector3::new(self.dec.cos() * self.ra.cos(), self.dec.cos() * self.ra.sin(), self.dec.sin())
+    }
+
+    /// The angular radius of a circle about the centre which holds the whole field (radians).
+    pub fn bounding_radius(&self) -> f64 {
+        match &self.shape {
+            FieldShape::Circle { radius } => *radius,
+            FieldShape::Rectangle { width, height } => (0.5 * width).hypot(0.5 * height).atan(),
+            FieldShape::Polygon { vertices } => vertices.iter().map(|(x, y)| x.hypot(*y).atan()).fold(0.0, f64::max),
+        }
+    }
+
+    /// Project a position onto the tangent plane of the field, in the axes of the field.
+    ///
+    /// # Arguments
+    ///
+    /// * `ra` - The right ascension (radians)
+    /// * `dec` - The declination (radians)
+    ///
+    /// # Returns
+    ///
+    /// * The coordinates x and y (radians), or None if the position is more than 90 degrees from the centre
+    pub fn calc_tangent_plane(&self, ra: f64, dec: f64) -> Option<(f64, f64)> {
+        let d_ra = ra - self.ra;
+        let cos_c = self.dec.sin() * dec.sin() + self.dec.cos() * dec.cos() * d_ra.cos();
+        if cos_c <= 0.0 {
+            return None;
+        }
+        let xi = dec.cos() * d_ra.sin() / cos_c;
+        let eta = (self.dec.cos() * dec.sin() - self.dec.sin() * dec.cos() * d_ra.cos()) / cos_c;
+
+        let (sin_rot, cos_rot) = self.rotation.sin_cos();
+        Some((xi * cos_rot - eta * sin_rot, xi * sin_rot + eta * cos_rot))
+    }
+
+    /// Whether a position falls inside the field.
+    ///
+    /// # Arguments
+    ///
+    /// * `ra` - The right ascension (radians)
+    /// * `dec` - The declination (radians)
+    pub fn contains(&self, ra: f64, dec: f64) -> bool {
+        if let FieldShape::Circle { radius } = self.shape {
+            let direction = Vector3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+            return self.center().angle(&direction) <= radius;
+        }
+        let Some((x, y)) = self.calc_tangent_plane(ra, dec) else {
+            return false;
+        };
+        match &self.shape {
+            FieldShape::Rectangle { width, height } => x.abs() <= 0.5 * width && y.abs() <= 0.5 * height,
+            FieldShape::Polygon { vertices } => {
+                // count the crossings of a ray from the point towards +x
+                let mut inside = false;
+                for (i, &(x1, y1)) in vertices.iter().enumerate() {
+                    let (x2, y2) = vertices[(i + 1) % vertices.len()];
+                    if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+            FieldShape::Circle { .. } => unreachable!(),
+        }
+    }
+}
+
+/// Find the SpaceRocks which fall inside a field of view, seen from an observatory at an epoch.
+/// The rocks are first propagated on their two-body orbits and compared with the field without
+/// light travel time, and only those near the field are observed.
+///
+/// # Arguments
+///
+/// * `rocks` - The SpaceRocks, relative to the SSB or the Sun
+/// * `field` - The field of view, in J2000
+/// * `observatory` - The observatory
+/// * `epoch` - The epoch of the exposure
+///
+/// # Returns
+///
+/// * The index of each rock in the field, with its astrometric observation
+pub fn calc_rocks_in_field(rocks: &[SpaceRock], field: &Field, observatory: &Observatory, epoch: &Time) -> Result<Vec<(usize, Observation)>, Box<dyn std::error::Error>> {
+    // SPICE is not thread-safe, so the observers and the velocities of their origins are looked up here
+    let mut observers: HashMap<String, (Observer, Vector3<f64>)> = HashMap::new();
+    for rock in rocks {
+        let origin = rock.origin.as_str();
+        if !observers.contains_key(origin) {
+            let observer = observatory.at(epoch, "J2000", origin)?;
+            let origin_velocity = observer.spacerock.origin_velocity()?;
+            observers.insert(origin.to_string(), (observer, origin_velocity));
+        }
+    }
+
+    let center = field.center();
+    let limit = field.bounding_radius() + PREFILTER_MARGIN;
+
+    let found: Vec<Result<Option<(usize, Observation)>, String>> = rocks.par_iter().enumerate().map(|(index, rock)| {
+        let (observer, origin_velocity) = &observers[rock.origin.as_str()];
+
+        let mut target = rock.analytic_at(epoch).map_err(|e| e.to_string())?;
+        target.change_reference_plane("J2000").map_err(|e| e.to_string())?;
+        if (target.position - observer.position()).angle(&center) > limit {
+            return Ok(None);
+        }
+
+        let start = target.clone();
+        let observation = target.observe_with_propagator(observer, &CorrectionLevel::Astrometric, origin_velocity, |epoch| start.analytic_at(epoch)).map_err(|e| e.to_string())?;
+        if field.contains(observation.ra(), observation.dec()) {
+            Ok(Some((index, observation)))
+        } else {
+            Ok(None)
+        }
+    }).collect();
+
+    let mut in_field = Vec::new();
+    for result in found {
+        if let Some(found) = result? {
+            in_field.push(found);
+        }
+    }
+    Ok(in_field)
+}
@@ -0,0 +1,83 @@
+use nalgebra::Vector3;
+
+use crate::ReferencePlane;
+
+/// The geometry of an observation: the distances and angles between the target, the observer, the
+/// Sun and the Moon. Angles are in radians and distances in au.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometry {
+    /// The distance from the Sun to the target
+    pub heliocentric_distance: f64,
+    /// The distance from the observer to the target
+    pub observer_distance: f64,
+    /// The angle at the target between the Sun and the observer
+    pub phase_angle: f64,
+    /// The angle at the observer between the Sun and the target
+    pub solar_elongation: f64,
+    /// The angle at the observer between the Moon and the target, if the Moon is known
+    pub lunar_elongation: Option<f64>,
+    /// The galactic latitude of the target
+    pub galactic_latitude: f64,
+    /// The position angle, east of north, of the direction from the target to the Sun
+    pub sun_position_angle: f64,
+    /// The position angle, east of north, of the heliocentric velocity of the target
+    pub velocity_position_angle: f64,
+    /// The angle between the observer and the orbital plane of the target, seen from the target.
+    /// It is positive on the side of the orbital angular momentum.
+    pub out_of_plane_angle: f64,
+}
+
+/// Calculate the position angle, east of north, of a vector projected on the sky at a direction.
+fn position_angle(direction: &Vector3<f64>, vector: &Vector3<f64>) -> f64 {
+    let north_pole = Vector3::new(0.0, 0.0, 1.0);
+    let east = north_pole.cross(direction);
+    let east = if east.norm() > 0.0 { east.normalize() } else { Vector3::new(0.0, 1.0, 0.0) };
+    let north = direction.cross(&east);
+    vector.dot(&east).atan2(vector.dot(&north)).rem_euclid(std::f64::consts::TAU)
+}
+
+/// Calculate the geometry of an observation from the positions and velocities of the bodies. All
+/// vectors share an origin and the reference plane given, which is used to find the position
+/// angles and the galactic latitude.
+///
+/// # Arguments
+///
+/// * `target` - The position and velocity of the target (au, au/day)
+/// * `observer` - The position of the observer (au)
+/// * `sun` - The position and velocity of the Sun (au, au/day)
+/// * `moon` - The position of the Moon (au), if known
+/// * `reference_plane` - The reference plane of the vectors
+///
+/// # Returns
+///
+/// * The Geometry
+pub fn calc_geometry(target: (&Vector3<f64>, &Vector3<f64>), observer: &Vector3<f64>, sun: (&Vector3<f64>, &Vector3<f64>), moon: Option<&Vector3<f64>>, reference_plane: &ReferencePlane) -> Geometry {
+    // work in the J2000 equator, where position angles are measured
+    let to_j2000 = reference_plane.get_rotation_matrix().transpose();
+    let (position, velocity) = (to_j2000 * target.0, to_j2000 * target.1);
+    let observer = to_j2000 * observer;
+    let (sun_position, sun_velocity) = (to_j2000 * sun.0, to_j2000 * sun.1);
+
+    let target_to_sun = sun_position - position;
+    let target_to_observer = observer - position;
+    let line_of_sight = position - observer;
+    let direction = line_of_sight.normalize();
+
+    let heliocentric_velocity = velocity - sun_velocity;
+    let angular_momentum = (-target_to_sun).cross(&heliocentric_velocity);
+    let out_of_plane_angle = (angular_momentum.dot(&target_to_observer) / (angular_momentum.norm() * target_to_observer.norm())).clamp(-1.0, 1.0).asin();
+
+    let galactic = ReferencePlane::GALACTIC.get_rotation_matrix() * direction;
+
+    Geometry {
+        heliocentric_distance: target_to_sun.norm(),
+        observer_distance: line_of_sight.norm(),
+        phase_angle: target_to_sun.angle(&target_to_observer),
+        solar_elongation: (sun_position - observer).angle(&line_of_sight),
+        lunar_elongation: moon.map(|moon| (to_j2000 * moon - observer).angle(&line_of_sight)),
+        galactic_latitude: galactic.z.clamp(-1.0, 1.0).asin(),
+        sun_position_angle: position_angle(&direction, &target_to_sun),
+        velocity_position_angle: position_angle(&direction, &heliocentric_velocity),
+        out_of_plane_angle,
+    }
+}
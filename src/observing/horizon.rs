@@ -0,0 +1,119 @@
+use nalgebra::{Matrix3, Vector3};
+
+use crate::Observer;
+use crate::coordinates::calc_gcrs_to_itrs_matrix;
+use crate::observing::apparent::{Atmosphere, calc_refraction};
+
+/// Calculate the matrix which rotates vectors from the reference plane of an observer to the ITRS
+/// at the epoch of the observer.
+///
+/// # Arguments
+///
+/// * `observer` - The observer
+///
+/// # Returns
+///
+/// * The rotation matrix
+pub fn calc_terrestrial_matrix(observer: &Observer) -> Matrix3<f64> {
+    calc_gcrs_to_itrs_matrix(&observer.epoch()) * observer.spacerock.reference_plane.get_rotation_matrix().transpose()
+}
+
+/// Calculate the matrix which rotates vectors from the reference plane of a ground observer to its
+/// local horizon frame, with x to the east, y to the north and z to the zenith of the WGS84
+/// ellipsoid.
+///
+/// # Arguments
+///
+/// * `observer` - The observer, which must be on the ground
+///
+/// # Returns
+///
+/// * The rotation matrix
+pub fn calc_horizon_matrix(observer: &Observer) -> Result<Matrix3<f64>, Box<dyn std::error::Error>> {
+    let (lon, lat, _) = observer.observatory.geodetic().ok_or("Horizontal coordinates need an observatory on the ground")?;
+    let (slon, clon) = lon.sin_cos();
+    let (slat, clat) = lat.sin_cos();
+    let to_horizon = Matrix3::new(
+        -slon, clon, 0.0,
+        -slat * clon, -slat * slon, clat,
+        clat * clon, clat * slon, slat,
+    );
+    Ok(to_horizon * calc_terrestrial_matrix(observer))
+}
+
+/// Calculate the altitude and azimuth of a direction seen by a ground observer.
+///
+/// # Arguments
+///
+/// * `direction` - The direction, in the reference plane of the observer
+/// * `observer` - The observer, which must be on the ground
+/// * `refraction` - The weather at the observer, to refract the direction, or None for the geometric altitude
+///
+/// # Returns
+///
+/// * The altitude and the azimuth, measured from the north through the east (radians)
+pub fn calc_altaz(direction: &Vector3<f64>, observer: &Observer, refraction: Option<&Atmosphere>) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+    let mut local = calc_horizon_matrix(observer)? * direction.normalize();
+    if let Some(atmosphere) = refraction {
+        let (a, b) = atmosphere.refraction_constants();
+        local = calc_refraction(&local, a, b).normalize();
+    }
+    let alt = local.z.clamp(-1.0, 1.0).asin();
+    let az = local.x.atan2(local.y).rem_euclid(std::f64::consts::TAU);
+    Ok((alt, az))
+}
+
+/// Calculate the local hour angle and the declination of date of a direction seen by a ground
+/// observer. The hour angle is measured westwards from the meridian.
+///
+/// # Arguments
+///
+/// * `direction` - The direction, in the reference plane of the observer
+/// * `observer` - The observer, which must be on the ground
+///
+/// # Returns
+///
+/// * The hour angle, between -pi and pi, and the declination of date (radians)
+pub fn calc_hour_angle(direction: &Vector3<f64>, observer: &Observer) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+    let (lon, _, _) = observer.observatory.geodetic().ok_or("Hour angles need an observatory on the ground")?;
+    let terrestrial = calc_terrestrial_matrix(observer) * direction.normalize();
+    let hour_angle = lon - terrestrial.y.atan2(terrestrial.x);
+    let hour_angle = (hour_angle + std::f64::consts::PI).rem_euclid(std::f64::consts::TAU) - std::f64::consts::PI;
+    Ok((hour_angle, terrestrial.z.clamp(-1.0, 1.0).asin()))
+}
+
+/// Calculate the parallactic angle, the angle at the target between the directions to the zenith
+/// and to the north celestial pole. Follows the SOFA routine iauHd2pa.
+///
+/// # Arguments
+///
+/// * `hour_angle` - The hour angle (radians)
+/// * `dec` - The declination (radians)
+/// * `lat` - The latitude of the observer (radians)
+///
+/// # Returns
+///
+/// * The parallactic angle (radians)
+pub fn calc_parallactic_angle(hour_angle: f64, dec: f64, lat: f64) -> f64 {
+    let sqsz = lat.cos() * hour_angle.sin();
+    let cqsz = lat.sin() * dec.cos() - lat.cos() * dec.sin() * hour_angle.cos();
+    if sqsz != 0.0 || cqsz != 0.0 { sqsz.atan2(cqsz) } else { 0.0 }
+}
+
+/// Calculate the airmass at an altitude, from the formula of Kasten and Young (1989), which holds
+/// down to the horizon.
+///
+/// # Arguments
+///
+/// * `alt` - The altitude (radians)
+///
+/// # Returns
+///
+/// * The airmass, which is infinite below the horizon
+pub fn calc_airmass(alt: f64) -> f64 {
+    if alt <= 0.0 {
+        return f64::INFINITY;
+    }
+    let zenith_distance = 90.0 - alt.to_degrees();
+    1.0 / (alt.sin() + 0.50572 * (96.07995 - zenith_distance).powf(-1.6364))
+}
@@ -5,4 +5,21 @@ pub mod observer;
     pub use observer::Observer;
 
 pub mod observation;
-    pub use observation::{Observation};
\ No newline at end of file
+    pub use observation::{Observation};
+pub mod obscodes;
+    pub use obscodes::{ObsCode, ObsCodeTable, SiteKind, set_obscode_table, get_obscode_table, load_obscodes, get_obscode};
+
+pub mod apparent;
+    pub use apparent::{CorrectionLevel, Atmosphere, Deflector, DEFLECTORS, calc_light_deflection, calc_aberration, calc_refraction_constants, calc_refraction, calc_apparent_direction, calc_observed_direction};
+
+pub mod horizon;
+    pub use horizon::{calc_terrestrial_matrix, calc_horizon_matrix, calc_altaz, calc_hour_angle, calc_parallactic_angle, calc_airmass};
+
+pub mod geometry;
+    pub use geometry::{Geometry, calc_geometry};
+
+pub mod visibility;
+    pub use visibility::{VisibilityConstraints, RiseTransitSet, calc_rise_transit_set, calc_observable_windows};
+
+pub mod footprint;
+    pub use footprint::{Field, FieldShape, calc_rocks_in_field};
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use serde_json::Value;
+
+use crate::constants::DEG_TO_RAD;
+use crate::OBSERVATORIES;
+
+/// Where the observer of an observatory code is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiteKind {
+    /// A fixed site on the Earth, with parallax constants
+    Ground,
+    /// A spacecraft, whose position is given with each observation
+    Space,
+    /// A moving site on the Earth, whose position is given with each observation
+    Roving,
+}
+
+/// An entry of the MPC list of observatory codes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObsCode {
+    /// The three-character observatory code
+    pub code: String,
+    /// The name of the site
+    pub name: String,
+    /// The east longitude (radians), zero for space and roving observers
+    pub lon: f64,
+    /// The parallax constant rho cos(lat) (Earth radii)
+    pub rho_cos_lat: f64,
+    /// The parallax constant rho sin(lat) (Earth radii)
+    pub rho_sin_lat: f64,
+    /// Whether the site is on the ground, in space or roving
+    pub kind: SiteKind,
+}
+
+/// A table of observatory codes, e.g. from the MPC ObsCodes file. Codes missing from the table
+/// can fall back to the table built into the crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObsCodeTable {
+    entries: HashMap<String, ObsCode>,
+    builtin_fallback: bool,
+}
+
+impl Default for ObsCodeTable {
+    fn default() -> Self {
+        ObsCodeTable { entries: HashMap::new(), builtin_fallback: true }
+    }
+}
+
+impl ObsCodeTable {
+
+    /// Create a new ObsCodeTable, which falls back to the built-in codes.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The entries of the table. Later entries replace earlier ones with the same code.
+    ///
+    /// # Returns
+    ///
+    /// * `ObsCodeTable` - The table
+    pub fn new(entries: Vec<ObsCode>) -> Self {
+        let mut table = ObsCodeTable::default();
+        for entry in entries {
+            table.insert(entry);
+        }
+        table
+    }
+
+    /// The observatory codes built into the crate. They carry no names.
+    pub fn builtin() -> Self {
+        let entries = OBSERVATORIES.entries().map(|(code, (lon, rho_cos_lat, rho_sin_lat))| ObsCode {
+            code: code.to_string(),
+            name: String::new(),
+            lon: *lon,
+            rho_cos_lat: *rho_cos_lat,
+            rho_sin_lat: *rho_sin_lat,
+            kind: SiteKind::Ground,
+        }).collect();
+        let mut table = ObsCodeTable::new(entries);
+        table.builtin_fallback = false;
+        table
+    }
+
+    /// Read an MPC ObsCodes file, either the HTML/text list (`ObsCodes.html`) or the JSON form.
+    /// The form is told from the first character of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ObsCodeTable, Box<dyn std::error::Error>>` - The table
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read observatory codes {}: {}", path, e))?;
+        match content.trim_start().chars().next() {
+            Some('{') | Some('[') => ObsCodeTable::parse_json(&content),
+            _ => ObsCodeTable::parse_text(&content),
+        }
+    }
+
+    /// Parse the MPC list of observatory codes in its HTML/text form. Each entry is a line of fixed
+    /// columns: the code, the east longitude (degrees), rho cos(lat), rho sin(lat) and the name.
+    /// Space and roving observers leave the coordinates blank. Other lines (HTML tags, the header)
+    /// are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text of the file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ObsCodeTable, Box<dyn std::error::Error>>` - The table
+    pub fn parse_text(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut entries = Vec::new();
+        for line in content.lines() {
+            let line = line.trim_end();
+            let bytes = line.as_bytes();
+            if bytes.len() < 5 || !line.is_ascii() || !bytes[..3].iter().all(u8::is_ascii_alphanumeric) || bytes[3] != b' ' {
+                continue;
+            }
+
+            let column = |start: usize, end: usize| line.get(start..end.min(line.len())).unwrap_or("").trim();
+            let name = decode_html(column(30, line.len()));
+            let (lon, rho_cos_lat, rho_sin_lat) = (column(3, 13), column(13, 21), column(21, 30));
+
+            let entry = if lon.is_empty() && rho_cos_lat.is_empty() && rho_sin_lat.is_empty() {
+                unlocated_site(&line[..3], name)
+            } else {
+                let parse = |s: &str| s.parse::<f64>().map_err(|_| format!("Invalid observatory code line: {}", line));
+                ObsCode {
+                    code: line[..3].to_string(),
+                    name,
+                    lon: parse(lon)? * DEG_TO_RAD,
+                    rho_cos_lat: parse(rho_cos_lat)?,
+                    rho_sin_lat: parse(rho_sin_lat)?,
+                    kind: SiteKind::Ground,
+                }
+            };
+            entries.push(entry);
+        }
+
+        if entries.is_empty() {
+            return Err("No observatory codes found".into());
+        }
+        Ok(ObsCodeTable::new(entries))
+    }
+
+    /// Parse the MPC list of observatory codes in its JSON form. Both an object keyed by code and an
+    /// array of objects holding an `obscode` are read. The coordinates are taken from `Longitude`,
+    /// `cos` and `sin`, or `longitude`, `rhocosphi` and `rhosinphi`, as numbers or strings; sites
+    /// without them are space or roving observers, as also marked by `observations_type`.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text of the file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ObsCodeTable, Box<dyn std::error::Error>>` - The table
+    pub fn parse_json(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let value: Value = serde_json::from_str(content)?;
+        let sites: Vec<(String, &Value)> = match &value {
+            Value::Object(map) => map.iter().map(|(code, site)| (code.clone(), site)).collect(),
+            Value::Array(list) => list.iter().map(|site| {
+                let code = field(site, &["obscode", "code"]).and_then(Value::as_str).unwrap_or("").to_string();
+                (code, site)
+            }).collect(),
+            _ => return Err("Observatory codes must be a JSON object or array".into()),
+        };
+
+        let mut entries = Vec::with_capacity(sites.len());
+        for (code, site) in sites {
+            if code.len() != 3 {
+                return Err(format!("Invalid observatory code: {:?}", code).into());
+            }
+            let name = field(site, &["name", "Name"]).and_then(Value::as_str).unwrap_or("").trim().to_string();
+            let number = |keys: &[&str]| -> Result<Option<f64>, String> {
+                match field(site, keys) {
+                    None | Some(Value::Null) => Ok(None),
+                    Some(Value::Number(n)) => Ok(n.as_f64()),
+                    Some(Value::String(s)) if s.trim().is_empty() => Ok(None),
+                    Some(Value::String(s)) => s.trim().parse().map(Some).map_err(|_| format!("Invalid coordinate for observatory {}: {}", code, s)),
+                    Some(other) => Err(format!("Invalid coordinate for observatory {}: {}", code, other)),
+                }
+            };
+            let lon = number(&["Longitude", "longitude"])?;
+            let rho_cos_lat = number(&["cos", "rhocosphi"])?;
+            let rho_sin_lat = number(&["sin", "rhosinphi"])?;
+            let observations_type = field(site, &["observations_type"]).and_then(Value::as_str).unwrap_or("").to_lowercase();
+
+            let entry = match (lon, rho_cos_lat, rho_sin_lat) {
+                (Some(lon), Some(rho_cos_lat), Some(rho_sin_lat)) if observations_type != "satellite" && observations_type != "roving" => ObsCode {
+                    code: code.to_uppercase(),
+                    name,
+                    lon: lon * DEG_TO_RAD,
+                    rho_cos_lat,
+                    rho_sin_lat,
+                    kind: SiteKind::Ground,
+                },
+                _ => {
+                    let mut entry = unlocated_site(&code, name);
+                    match observations_type.as_str() {
+                        "satellite" => entry.kind = SiteKind::Space,
+                        "roving" => entry.kind = SiteKind::Roving,
+                        _ => {},
+                    }
+                    entry
+                },
+            };
+            entries.push(entry);
+        }
+        Ok(ObsCodeTable::new(entries))
+    }
+
+    /// Add an entry, replacing any entry with the same code.
+    pub fn insert(&mut self, mut entry: ObsCode) {
+        entry.code = entry.code.to_uppercase();
+        self.entries.insert(entry.code.clone(), entry);
+    }
+
+    /// Merge another table into this one. The entries of the other table replace those with the
+    /// same code.
+    pub fn merge(&mut self, other: ObsCodeTable) {
+        for (_, entry) in other.entries {
+            self.insert(entry);
+        }
+    }
+
+    /// Set whether codes missing from the table are looked up in the built-in table.
+    pub fn set_builtin_fallback(&mut self, builtin_fallback: bool) {
+        self.builtin_fallback = builtin_fallback;
+    }
+
+    /// Whether codes missing from the table are looked up in the built-in table.
+    pub fn builtin_fallback(&self) -> bool {
+        self.builtin_fallback
+    }
+
+    /// Look up an observatory code, in the table and then, if allowed, in the built-in table.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The observatory code
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ObsCode>` - The entry, or None if the code is unknown.
+    pub fn get(&self, code: &str) -> Option<ObsCode> {
+        let code = code.to_uppercase();
+        if let Some(entry) = self.entries.get(&code) {
+            return Some(entry.clone());
+        }
+        if !self.builtin_fallback {
+            return None;
+        }
+        OBSERVATORIES.get(code.as_str()).map(|(lon, rho_cos_lat, rho_sin_lat)| ObsCode {
+            code,
+            name: String::new(),
+            lon: *lon,
+            rho_cos_lat: *rho_cos_lat,
+            rho_sin_lat: *rho_sin_lat,
+            kind: SiteKind::Ground,
+        })
+    }
+
+    /// The entries of the table, in order of code, not counting the built-in table.
+    pub fn entries(&self) -> Vec<&ObsCode> {
+        let mut entries: Vec<&ObsCode> = self.entries.values().collect();
+        entries.sort_by(|a, b| a.code.cmp(&b.code));
+        entries
+    }
+
+    /// The number of entries in the table, not counting the built-in table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table has no entries of its own.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A site without coordinates, which is roving if the MPC names it so and in space otherwise.
+fn unlocated_site(code: &str, name: String) -> ObsCode {
+    let kind = if name.to_lowercase().contains("roving") { SiteKind::Roving } else { SiteKind::Space };
+    ObsCode { code: code.to_uppercase(), name, lon: 0.0, rho_cos_lat: 0.0, rho_sin_lat: 0.0, kind }
+}
+
+/// Look up the first of several keys in a JSON object.
+fn field<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().find_map(|key| value.get(key))
+}
+
+/// Decode the HTML entities that appear in the names of the MPC list.
+fn decode_html(s: &str) -> String {
+    s.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&#39;", "'").replace("&amp;", "&")
+}
+
+lazy_static! {
+    static ref OBSCODE_TABLE: RwLock<ObsCodeTable> = RwLock::new(ObsCodeTable::default());
+}
+
+/// Set the table of observatory codes used by `Observatory::from_obscode`.
+///
+/// # Arguments
+///
+/// * `table` - The table
+pub fn set_obscode_table(table: ObsCodeTable) {
+    *OBSCODE_TABLE.write().unwrap() = table;
+}
+
+/// Get a copy of the table of observatory codes used by `Observatory::from_obscode`.
+pub fn get_obscode_table() -> ObsCodeTable {
+    OBSCODE_TABLE.read().unwrap().clone()
+}
+
+/// Load an MPC ObsCodes file, merging it into the loaded table. Its entries replace any loaded or
+/// built-in entries with the same code.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file, in the HTML/text or JSON form
+pub fn load_obscodes(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let table = ObsCodeTable::from_file(path)?;
+    OBSCODE_TABLE.write().unwrap().merge(table);
+    Ok(())
+}
+
+/// Look up an observatory code in the loaded table, falling back to the built-in table.
+///
+/// # Arguments
+///
+/// * `code` - The observatory code
+///
+/// # Returns
+///
+/// * `Option<ObsCode>` - The entry, or None if the code is unknown.
+pub fn get_obscode(code: &str) -> Option<ObsCode> {
+    OBSCODE_TABLE.read().unwrap().get(code)
+}
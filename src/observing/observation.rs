@@ -1,4 +1,9 @@
 use crate::{Time, Observer};
+use crate::observing::apparent::Atmosphere;
+use crate::observing::horizon::{calc_altaz, calc_hour_angle, calc_parallactic_angle, calc_airmass};
+use crate::observing::geometry::{Geometry, calc_geometry};
+use crate::Origin;
+use crate::photometry::{Band, Colors, calc_band_conversion};
 
 use nalgebra::Vector3;
 
@@ -15,13 +20,13 @@ pub struct Observation {
     pub epoch: Time,
     pub observation_type: ObservationType,
     pub observer: Observer,
-    // pub filter: Option<String>,
+    pub band: Option<Band>,
     // pub obsid: Option<String>,
 }
 
 impl Observation {
     pub fn new(epoch: Time, observation_type: ObservationType, observer: Observer) -> Observation {
-        Observation { epoch, observation_type, observer }
+        Observation { epoch, observation_type, observer, band: None }
     }
 
     pub fn from_astrometry(epoch: Time, ra: f64, dec: f64, mag: Option<f64>, observer: Observer) -> Observation {
@@ -99,6 +104,39 @@ impl Observation {
         }
     }
 
+    /// Set the band of the magnitude of the observation.
+    pub fn set_band(&mut self, band: Band) {
+        self.band = Some(band);
+    }
+
+    /// Convert the observation to another band, changing its magnitude with the colours of the
+    /// target.
+    ///
+    /// # Arguments
+    ///
+    /// * `band` - The band to convert to
+    /// * `colors` - The colours of the target
+    ///
+    /// # Returns
+    ///
+    /// * The observation in the new band
+    pub fn to_band(&self, band: &Band, colors: &Colors) -> Result<Observation, Box<dyn std::error::Error>> {
+        let mut converted = self.clone();
+        converted.band = Some(*band);
+
+        let mag = match &mut converted.observation_type {
+            ObservationType::Astrometry { mag, .. } => mag,
+            ObservationType::Streak { mag, .. } => mag,
+            ObservationType::Complete { mag, .. } => mag,
+            ObservationType::Radar { .. } => return Ok(converted),
+        };
+        if let Some(value) = mag {
+            let from = self.band.ok_or("Cannot convert a magnitude without a band")?;
+            *value = calc_band_conversion(*value, &from, band, colors);
+        }
+        Ok(converted)
+    }
+
     pub fn proper_motion(&self) -> Option<f64> {
         let ra_rate = self.ra_rate()?;
         let dec_rate = self.dec_rate()?;
@@ -110,6 +148,99 @@ impl Observation {
         let dec = self.dec();
         Vector3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin())
     }
+
+    /// Calculate the geometric altitude and azimuth of the observation, seen from a ground
+    /// observatory. The azimuth is measured from the north through the east.
+    ///
+    /// # Returns
+    ///
+    /// * The altitude and azimuth (radians)
+    pub fn calc_altaz(&self) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+        calc_altaz(&self.pointing(), &self.observer, None)
+    }
+
+    /// Calculate the altitude and azimuth of the observation, raised by atmospheric refraction.
+    /// Use this with astrometric or apparent observations, not with observed ones, which are
+    /// already refracted.
+    ///
+    /// # Arguments
+    ///
+    /// * `atmosphere` - The weather at the observatory
+    ///
+    /// # Returns
+    ///
+    /// * The altitude and azimuth (radians)
+    pub fn calc_refracted_altaz(&self, atmosphere: &Atmosphere) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+        calc_altaz(&self.pointing(), &self.observer, Some(atmosphere))
+    }
+
+    /// Calculate the local hour angle of the observation, measured westwards from the meridian.
+    ///
+    /// # Returns
+    ///
+    /// * The hour angle, between -pi and pi (radians)
+    pub fn calc_hour_angle(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        Ok(calc_hour_angle(&self.pointing(), &self.observer)?.0)
+    }
+
+    /// Calculate the parallactic angle of the observation, the angle at the target from the
+    /// direction of the north celestial pole to the direction of the zenith.
+    ///
+    /// # Returns
+    ///
+    /// * The parallactic angle (radians)
+    pub fn calc_parallactic_angle(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        let (hour_angle, dec) = calc_hour_angle(&self.pointing(), &self.observer)?;
+        let (_, lat, _) = self.observer.observatory.geodetic().ok_or("Parallactic angles need an observatory on the ground")?;
+        Ok(calc_parallactic_angle(hour_angle, dec, lat))
+    }
+
+    /// Calculate the airmass of the observation.
+    ///
+    /// # Arguments
+    ///
+    /// * `refraction` - The weather at the observatory, to use the refracted altitude, or None for the geometric one
+    ///
+    /// # Returns
+    ///
+    /// * The airmass, which is infinite below the horizon
+    pub fn calc_airmass(&self, refraction: Option<&Atmosphere>) -> Result<f64, Box<dyn std::error::Error>> {
+        let (alt, _) = calc_altaz(&self.pointing(), &self.observer, refraction)?;
+        Ok(calc_airmass(alt))
+    }
+
+    /// Calculate the geometry of the observation: the distances to the target, its phase angle,
+    /// its elongations from the Sun and the Moon, its galactic latitude and its position angles.
+    /// The observation must be complete, so that the state of the target can be rebuilt. The Sun
+    /// and the Moon come from SPICE.
+    ///
+    /// # Returns
+    ///
+    /// * The Geometry
+    pub fn calc_geometry(&self) -> Result<Geometry, Box<dyn std::error::Error>> {
+        let (ra, dec, ra_rate, dec_rate, range, range_rate) = match self.observation_type {
+            ObservationType::Complete { ra, dec, ra_rate, dec_rate, range, range_rate, .. } => (ra, dec, ra_rate, dec_rate, range, range_rate),
+            _ => return Err("The geometry of an observation needs its range and range rate".into()),
+        };
+
+        // rebuild the state of the target relative to the observer from the spherical coordinates
+        let pointing = self.pointing();
+        let east = Vector3::new(-ra.sin(), ra.cos(), 0.0);
+        let north = Vector3::new(-dec.sin() * ra.cos(), -dec.sin() * ra.sin(), dec.cos());
+        let position = self.observer.position() + range * pointing;
+        let velocity = self.observer.velocity() + range_rate * pointing + range * (ra_rate * dec.cos() * east + dec_rate * north);
+
+        let (sun_position, sun_velocity) = match self.observer.spacerock.origin {
+            Origin::SUN => (Vector3::zeros(), Vector3::zeros()),
+            _ => {
+                let sun = self.observer.spacerock.spice_body("sun")?;
+                (sun.position, sun.velocity)
+            }
+        };
+        let moon = self.observer.spacerock.spice_body("moon")?;
+
+        Ok(calc_geometry((&position, &velocity), &self.observer.position(), (&sun_position, &sun_velocity), Some(&moon.position), &self.observer.spacerock.reference_plane))
+    }
 }
 
 // implement a display trait for Observation
@@ -1,23 +1,26 @@
-use crate::SpaceRock;
+use crate::{SpaceRock, Simulation, Origin};
 
 use crate::observing::observer::Observer;
-use crate::constants::{DEG_TO_RAD, M_TO_AU, EQUAT_RAD};
-use crate::OBSERVATORIES;
+use crate::constants::{M_TO_AU, KM_TO_AU, EQUAT_RAD, FLATTEN, SECONDS_PER_DAY, DEG_TO_RAD};
+use crate::coordinates::{ReferencePlane, calc_itrs_to_gcrs_state};
+use crate::observing::obscodes::{get_obscode, SiteKind};
 use crate::time::Time;
 
-use nalgebra::Vector3;
+use nalgebra::{Matrix3, Vector3};
 
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Observatory {
     GroundObservatory { obscode: String, lon: f64, lat: f64, rho: f64 },
     SpaceTelecope { name: String },
-    SpaceRockObservatory { rock: SpaceRock }
+    SpaceRockObservatory { rock: SpaceRock },
+    SatelliteObservatory { obscode: String, position: Vector3<f64> },
 }
 
 impl Observatory {
 
-    /// Create a new Observatory from an observatory code.
+    /// Create a new Observatory from an observatory code. The code is looked up in the loaded
+    /// table of observatory codes (see `load_obscodes`) and then in the built-in table.
     ///
     /// # Arguments
     ///
@@ -28,16 +31,113 @@ impl Observatory {
     /// * `Observatory` - The Observatory object.
     pub fn from_obscode(obscode: &str) -> Result<Self, &'static str> {
         let obscode = obscode.to_uppercase();
-        match OBSERVATORIES.get(&obscode) {
-            Some(obs) => {
-                let lon = obs.0;
-                let lat = obs.2.atan2(obs.1);
-                let rho = (obs.1 * obs.1 + obs.2 * obs.2).sqrt();
-                return Ok(Observatory::GroundObservatory { obscode: obscode, lon, lat, rho })
+        match get_obscode(&obscode) {
+            Some(site) => match site.kind {
+                SiteKind::Ground => {
+                    let lat = site.rho_sin_lat.atan2(site.rho_cos_lat);
+                    let rho = (site.rho_cos_lat * site.rho_cos_lat + site.rho_sin_lat * site.rho_sin_lat).sqrt();
+                    Ok(Observatory::GroundObservatory { obscode, lon: site.lon, lat, rho })
+                },
+                SiteKind::Space => Err("Observatory is in space and has no fixed position"),
+                SiteKind::Roving => Err("Observatory is roving and has no fixed position"),
             },
-            None => {
-                return Err("Observatory not found")
-            }
+            None => Err("Observatory not found"),
+        }
+    }
+
+    /// Create a new ground Observatory from geodetic coordinates on the WGS84 ellipsoid, for sites
+    /// without an observatory code.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the observatory.
+    /// * `lon` - The east longitude (radians).
+    /// * `lat` - The geodetic latitude (radians).
+    /// * `height` - The height above the ellipsoid (meters).
+    ///
+    /// # Returns
+    ///
+    /// * `Observatory` - The Observatory object.
+    pub fn from_geodetic(name: &str, lon: f64, lat: f64, height: f64) -> Self {
+        let (rho_cos_lat, rho_sin_lat) = calc_geodetic_to_parallax(lat, height);
+        Observatory::GroundObservatory {
+            obscode: name.to_string(),
+            lon,
+            lat: rho_sin_lat.atan2(rho_cos_lat),
+            rho: (rho_cos_lat * rho_cos_lat + rho_sin_lat * rho_sin_lat).sqrt(),
+        }
+    }
+
+    /// Create a new Observatory that follows a SpaceRock, e.g. a spacecraft with a known state.
+    /// The SpaceRock is propagated to the epoch of each observation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rock` - The SpaceRock carrying the observatory.
+    ///
+    /// # Returns
+    ///
+    /// * `Observatory` - The Observatory object.
+    pub fn from_spacerock(rock: SpaceRock) -> Self {
+        Observatory::SpaceRockObservatory { rock }
+    }
+
+    /// Create a new Observatory for a satellite at a known geocentric position, as reported
+    /// with a single observation.
+    ///
+    /// # Arguments
+    ///
+    /// * `obscode` - The observatory code of the satellite.
+    /// * `position` - The geocentric position in the J2000 equatorial frame (au).
+    ///
+    /// # Returns
+    ///
+    /// * `Observatory` - The Observatory object.
+    pub fn from_satellite_position(obscode: &str, position: Vector3<f64>) -> Self {
+        Observatory::SatelliteObservatory { obscode: obscode.to_uppercase(), position }
+    }
+
+    /// Create a new Observatory from the second line of an observation in the MPC 80-column format.
+    /// A satellite line (`s` in column 15) gives the geocentric J2000 equatorial position in km or au,
+    /// and a roving observer line (`v` in column 15) gives the east longitude, geodetic latitude
+    /// (degrees) and height (meters). Both end with the observatory code in columns 78-80, so the line
+    /// must have all 80 columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The second line of the observation.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Observatory, Box<dyn std::error::Error>>` - The Observatory object.
+    pub fn from_mpc_second_line(line: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if !line.is_ascii() || line.len() < 80 {
+            return Err(format!("Invalid MPC observer line: {}", line).into());
+        }
+        let column = |start: usize, end: usize| line[start..end].trim();
+        let number = |start: usize, end: usize| -> Result<f64, String> {
+            let field: String = column(start, end).chars().filter(|c| !c.is_whitespace()).collect();
+            field.parse().map_err(|_| format!("Invalid number {:?} in MPC observer line: {}", field, line))
+        };
+        let obscode = column(77, 80);
+
+        match &line[14..15] {
+            "s" => {
+                let scale = match column(32, 33) {
+                    "1" => KM_TO_AU,
+                    "2" => 1.0,
+                    units => return Err(format!("Invalid parallax units {:?} in MPC observer line", units).into()),
+                };
+                let position = Vector3::new(number(34, 45)?, number(46, 57)?, number(58, 69)?) * scale;
+                Ok(Observatory::from_satellite_position(obscode, position))
+            },
+            "v" => {
+                let lon = number(34, 44)? * DEG_TO_RAD;
+                let lat = number(45, 55)? * DEG_TO_RAD;
+                let height = number(56, 61)?;
+                Ok(Observatory::from_geodetic(obscode, lon, lat, height))
+            },
+            _ => Err(format!("Not a satellite or roving observer line: {}", line).into()),
         }
     }
 
@@ -67,47 +167,123 @@ impl Observatory {
     /// * `Result<Observer, Box<dyn std::error::Error>>` - The Observer object.
     pub fn at(&self, epoch: &Time, reference_plane: &str, origin: &str) -> Result<Observer, Box<dyn std::error::Error>> {
         match self {
-            Observatory::GroundObservatory { obscode: _, lon, lat, rho } => {
+            Observatory::GroundObservatory { .. } => {
                 let mut earth = SpaceRock::from_spice("earth", epoch, reference_plane, origin)?;
-                let rho_sin_lat = lat.sin() * rho;
-                let rho_cos_lat = lat.cos() * rho;
-                
-                let delta_et = 10.0;
-                let et = spice::str2et(&format!("JD{epoch} UTC", epoch=epoch.utc().jd()));     
-                let m: nalgebra::Matrix3<f64> = spice::pxform("ITRF93", reference_plane, et).into();
-                let mp: nalgebra::Matrix3<f64> = spice::pxform("ITRF93", reference_plane, et + delta_et).into();
-                let mm: nalgebra::Matrix3<f64> = spice::pxform("ITRF93", reference_plane, et - delta_et).into();
-                // transpose the matrix
-                let m = m.transpose();
-                let mp = mp.transpose();
-                let mm = mm.transpose();
-
-                let ox = rho_cos_lat * lon.cos();
-                let oy = rho_cos_lat * lon.sin();
-                let oz = rho_sin_lat;
-                let obsVec = Vector3::new(ox, oy, oz);
-
-                // println!("{:?}", obsVec);
-
-                let mVec = m * obsVec * EQUAT_RAD * M_TO_AU;
-                let mVecp = mp * obsVec * EQUAT_RAD * M_TO_AU;
-                let mVecm = mm * obsVec * EQUAT_RAD * M_TO_AU;
-                let d_vel = (mVecp - mVecm) / (2.0 * delta_et / 86400.0);
-                earth.position += mVec;
+                let (d_pos, d_vel) = self.geocentric_state(epoch, &earth.reference_plane)?;
+                earth.position += d_pos;
                 earth.velocity += d_vel;
-                
-                // let [d_pos, d_vel] = compute_topocentric_correction(*lon, rho_sin_lat, rho_cos_lat, epoch.jd());
-                // earth.position += d_pos;
-                // earth.velocity += d_vel;
                 Ok(Observer { spacerock: earth, observatory: self.clone() })
             },
             Observatory::SpaceTelecope { name } => {
                 let rock = SpaceRock::from_spice(&name, epoch, reference_plane, origin)?;
                 Ok(Observer { spacerock: rock, observatory: self.clone() })
-            }
-            _ => {
-                return Err("at not implemented for this observatory type".into())
-            }
+            },
+            Observatory::SpaceRockObservatory { rock } => {
+                let mut rock = rock.clone();
+                rock.analytic_propagate(epoch)?;
+                rock.change_reference_plane(reference_plane)?;
+                move_to_origin(&mut rock, origin)?;
+                Ok(Observer { spacerock: rock, observatory: self.clone() })
+            },
+            Observatory::SatelliteObservatory { position, .. } => {
+                // the MPC gives no velocity for the satellite, so it moves with the Earth
+                let mut earth = SpaceRock::from_spice("earth", epoch, reference_plane, origin)?;
+                let offset = earth.reference_plane.get_rotation_matrix() * position;
+                earth.position += offset;
+                earth.velocity -= earth.reference_plane.angular_velocity().cross(&offset);
+                Ok(Observer { spacerock: earth, observatory: self.clone() })
+            },
+        }
+    }
+
+    /// Get the Observer at a specific time, carrying a SpaceRockObservatory through an n-body
+    /// simulation instead of along a keplerian orbit. The simulation is cloned, integrated to the
+    /// epoch of the SpaceRock, given the SpaceRock as a particle and integrated to the epoch. Other
+    /// observatories are handled as in `at`.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The time to get the observer at.
+    /// * `simulation` - The simulation holding the perturbing bodies.
+    /// * `reference_plane` - The reference plane of the observer.
+    /// * `origin` - The origin of the observer.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Observer, Box<dyn std::error::Error>>` - The Observer object.
+    pub fn at_with_simulation(&self, epoch: &Time, simulation: &Simulation, reference_plane: &str, origin: &str) -> Result<Observer, Box<dyn std::error::Error>> {
+        match self {
+            Observatory::SpaceRockObservatory { rock } => {
+                let mut sim = simulation.clone();
+                sim.integrate(&rock.epoch);
+                let mut particle = rock.clone();
+                particle.epoch = sim.epoch.clone();
+                sim.add(particle)?;
+                sim.integrate(epoch);
+
+                let mut rock = sim.get_particle(&rock.name)?.clone();
+                rock.epoch = epoch.clone();
+                rock.change_reference_plane(reference_plane)?;
+                move_to_origin(&mut rock, origin)?;
+                Ok(Observer { spacerock: rock, observatory: self.clone() })
+            },
+            _ => self.at(epoch, reference_plane, origin),
+        }
+    }
+
+    /// Get the position of a ground observatory relative to the geocenter, from the native model of
+    /// the Earth's rotation (precession-nutation, the Earth rotation angle and polar motion). UT1
+    /// and the pole come from the loaded Earth orientation table, so no SPICE kernel is needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The time.
+    /// * `reference_plane` - The reference plane of the position.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vector3<f64>, Vector3<f64>), Box<dyn std::error::Error>>` - The position (au) and velocity (au/day).
+    pub fn geocentric_state(&self, epoch: &Time, reference_plane: &ReferencePlane) -> Result<(Vector3<f64>, Vector3<f64>), Box<dyn std::error::Error>> {
+        match self {
+            Observatory::GroundObservatory { lon, lat, rho, .. } => {
+                let itrs = itrs_position(*lon, *lat, *rho);
+                let (position, velocity) = calc_itrs_to_gcrs_state(epoch, &itrs);
+                let rotation = reference_plane.get_rotation_matrix();
+                let position = rotation * position;
+                Ok((position, rotation * velocity - reference_plane.angular_velocity().cross(&position)))
+            },
+            _ => Err("geocentric_state is only defined for ground observatories".into()),
+        }
+    }
+
+    /// Get the position of a ground observatory relative to the geocenter, rotating from the ITRF93
+    /// frame with SPICE. This needs a binary Earth orientation kernel (e.g. earth_latest_high_prec.bpc)
+    /// and serves to check the native model in `geocentric_state`.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The time.
+    /// * `reference_plane` - The reference plane of the position, which SPICE must know.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vector3<f64>, Vector3<f64>), Box<dyn std::error::Error>>` - The position (au) and velocity (au/day).
+    pub fn spice_geocentric_state(&self, epoch: &Time, reference_plane: &str) -> Result<(Vector3<f64>, Vector3<f64>), Box<dyn std::error::Error>> {
+        match self {
+            Observatory::GroundObservatory { lon, lat, rho, .. } => {
+                let delta_et = 10.0;
+                let et = spice::str2et(&format!("JD{epoch} UTC", epoch=epoch.utc().jd()));
+                // pxform returns rows, which nalgebra reads as columns
+                let m: Matrix3<f64> = spice::pxform("ITRF93", reference_plane, et).into();
+                let mp: Matrix3<f64> = spice::pxform("ITRF93", reference_plane, et + delta_et).into();
+                let mm: Matrix3<f64> = spice::pxform("ITRF93", reference_plane, et - delta_et).into();
+
+                let itrs = itrs_position(*lon, *lat, *rho);
+                let position = m.transpose() * itrs;
+                let velocity = (mp.transpose() * itrs - mm.transpose() * itrs) / (2.0 * delta_et / SECONDS_PER_DAY);
+                Ok((position, velocity))
+            },
+            _ => Err("spice_geocentric_state is only defined for ground observatories".into()),
         }
     }
 
@@ -120,7 +296,8 @@ impl Observatory {
         match self {
             Observatory::GroundObservatory { obscode, .. } => obscode.clone(),
             Observatory::SpaceTelecope { name } => name.clone(),
-            Observatory::SpaceRockObservatory { rock } => rock.name.clone()
+            Observatory::SpaceRockObservatory { rock } => rock.name.clone(),
+            Observatory::SatelliteObservatory { obscode, .. } => obscode.clone(),
         }
     }
 
@@ -134,6 +311,7 @@ impl Observatory {
             Observatory::GroundObservatory { lon, .. } => Some(*lon),
             Observatory::SpaceTelecope { .. } => None,
             Observatory::SpaceRockObservatory { .. } => None,
+            Observatory::SatelliteObservatory { .. } => None,
         }
     }
 
@@ -147,6 +325,22 @@ impl Observatory {
             Observatory::GroundObservatory { lat, .. } => Some(*lat),
             Observatory::SpaceTelecope { .. } => None,
             Observatory::SpaceRockObservatory { .. } => None,
+            Observatory::SatelliteObservatory { .. } => None,
+        }
+    }
+
+    /// Get the geodetic coordinates of the Observatory on the WGS84 ellipsoid.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(f64, f64, f64)>` - The east longitude (radians), geodetic latitude (radians) and height above the ellipsoid (meters).
+    pub fn geodetic(&self) -> Option<(f64, f64, f64)> {
+        match self {
+            Observatory::GroundObservatory { lon, lat, rho, .. } => {
+                let (lat, height) = calc_parallax_to_geodetic(rho * lat.cos(), rho * lat.sin());
+                Some((*lon, lat, height))
+            },
+            _ => None,
         }
     }
 
@@ -160,51 +354,86 @@ impl Observatory {
             Observatory::GroundObservatory { rho, .. } => Some(*rho),
             Observatory::SpaceTelecope { .. } => None,
             Observatory::SpaceRockObservatory { .. } => None,
+            Observatory::SatelliteObservatory { .. } => None,
         }
     }
 
-}
+    /// Convert a time to TDB as kept by a clock at the Observatory. For a ground observatory this
+    /// includes the topocentric terms of TDB - TT, which reach about 2 µs. Other observatories get
+    /// the geocentric TDB.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The time to convert.
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - The time in TDB.
+    pub fn tdb(&self, epoch: &Time) -> Time {
+        match self {
+            Observatory::GroundObservatory { lon, lat, rho, .. } => {
+                let radius = rho * EQUAT_RAD / 1000.0;
+                epoch.tdb_topocentric(*lon, radius * lat.cos(), radius * lat.sin())
+            },
+            _ => epoch.tdb(),
+        }
+    }
 
-fn compute_local_sidereal_time(epoch: f64, lon: f64) -> f64 {
-    let t = (epoch - 2451545.0) / 36525.0;
-    let mut theta = 280.46061837 + 360.98564736629 * (epoch - 2451545.0) + (0.000387933 * t * t) - (t * t * t / 38710000.0);
-    theta *= DEG_TO_RAD;
-    return theta + lon
 }
 
-fn sidereal_rate(epoch: f64) -> f64 {
-    let t = (epoch - 2451545.0) / 36525.0;
-    let tprime = 1.0 / 36525.0;
-    let theta_dot = 360.98564736629 + 2.0 * 0.000387933 * t * tprime + 3.0 * t * t * tprime / 38710000.0;
-    return theta_dot * DEG_TO_RAD
+/// Move a SpaceRock to the SSB or the Sun, loading the new origin from SPICE if it differs.
+fn move_to_origin(rock: &mut SpaceRock, origin: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let origin = Origin::from_str(origin)?;
+    if rock.origin == origin {
+        return Ok(());
+    }
+    match origin {
+        Origin::SSB => rock.to_ssb()?,
+        Origin::SUN => rock.to_helio()?,
+        _ => return Err(format!("Cannot move an observer to origin {}", origin).into()),
+    }
+    rock.origin = origin;
+    Ok(())
 }
 
-fn compute_topocentric_correction(lon: f64, rho_sin_lat: f64, rho_cos_lat: f64, epoch: f64) -> [Vector3<f64>; 2] {
-
-    let phi = compute_local_sidereal_time(epoch, lon);
-    let phi_rate = sidereal_rate(epoch);
-    
-    let sin_lon = phi.sin();
-    let cos_lon = phi.cos();
-
-    let sin_lon_prime = cos_lon * phi_rate;
-    let cos_lon_prime = -sin_lon * phi_rate;
-        
-    let dx = rho_cos_lat * cos_lon * EQUAT_RAD;
-    let dy = rho_cos_lat * sin_lon * EQUAT_RAD;
-    let dz = rho_sin_lat * EQUAT_RAD;
-
-    let dvx = cos_lon_prime * rho_cos_lat * EQUAT_RAD;
-    let dvy = sin_lon_prime * rho_cos_lat * EQUAT_RAD;
-    let dvz = 0.0;
+/// Convert a geodetic latitude and height on the WGS84 ellipsoid into the parallax constants
+/// rho cos(lat) and rho sin(lat), in Earth radii.
+fn calc_geodetic_to_parallax(lat: f64, height: f64) -> (f64, f64) {
+    let e2 = FLATTEN * (2.0 - FLATTEN);
+    let (sin_lat, cos_lat) = lat.sin_cos();
+    let n = EQUAT_RAD / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+    ((n + height) * cos_lat / EQUAT_RAD, (n * (1.0 - e2) + height) * sin_lat / EQUAT_RAD)
+}
 
-    let d_pos = Vector3::new(dx, dy, dz) * M_TO_AU; // AU
-    let d_vel = Vector3::new(dvx, dvy, dvz) * M_TO_AU; // AU/day
+/// Convert the parallax constants rho cos(lat) and rho sin(lat), in Earth radii, into a geodetic
+/// latitude and height on the WGS84 ellipsoid, by fixed-point iteration on the latitude.
+fn calc_parallax_to_geodetic(rho_cos_lat: f64, rho_sin_lat: f64) -> (f64, f64) {
+    let e2 = FLATTEN * (2.0 - FLATTEN);
+    let p = rho_cos_lat * EQUAT_RAD;
+    let z = rho_sin_lat * EQUAT_RAD;
 
-    return [d_pos, d_vel];
+    // at the poles the latitude is fixed and the height is measured along the axis
+    if p < 1e-3 {
+        let lat = std::f64::consts::FRAC_PI_2.copysign(z);
+        return (lat, z.abs() - EQUAT_RAD * (1.0 - FLATTEN));
+    }
 
+    let mut lat = z.atan2(p * (1.0 - e2));
+    let mut height = 0.0;
+    for _ in 0..10 {
+        let sin_lat = lat.sin();
+        let n = EQUAT_RAD / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        height = p / lat.cos() - n;
+        lat = z.atan2(p * (1.0 - e2 * n / (n + height)));
+    }
+    (lat, height)
 }
 
+/// The position of a ground observatory in the ITRS (au), from its longitude, geocentric latitude
+/// and distance from the geocenter (Earth radii).
+fn itrs_position(lon: f64, lat: f64, rho: f64) -> Vector3<f64> {
+    Vector3::new(lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()) * rho * EQUAT_RAD * M_TO_AU
+}
 
 // earth_latest_high_prec.bpc
 // pxform
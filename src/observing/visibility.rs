@@ -0,0 +1,188 @@
+use crate::{Observatory, SpaceRock, Time};
+use crate::constants::DEG_TO_RAD;
+use crate::observing::apparent::CorrectionLevel;
+use crate::observing::horizon::{calc_altaz, calc_hour_angle};
+
+/// The step between samples when searching for events (days).
+const SEARCH_STEP: f64 = 5.0 / 1440.0;
+
+/// The precision to which events are located (days).
+const EVENT_PRECISION: f64 = 1.0 / 86400.0;
+
+/// Limits on when a target can be observed from the ground.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibilityConstraints {
+    /// The lowest geometric altitude of the target (radians)
+    pub min_altitude: f64,
+    /// The highest geometric altitude of the Sun (radians), e.g. -18 degrees for astronomical twilight
+    pub max_sun_altitude: f64,
+    /// The smallest angle between the target and the Moon (radians)
+    pub min_moon_separation: f64,
+}
+
+impl Default for VisibilityConstraints {
+    fn default() -> Self {
+        VisibilityConstraints {
+            min_altitude: 20.0 * DEG_TO_RAD,
+            max_sun_altitude: -18.0 * DEG_TO_RAD,
+            min_moon_separation: 30.0 * DEG_TO_RAD,
+        }
+    }
+}
+
+/// When a target crosses an altitude and the meridian. Each event is None if it does not happen
+/// in the day searched, e.g. for a circumpolar target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiseTransitSet {
+    pub rise: Option<Time>,
+    pub transit: Option<Time>,
+    pub set: Option<Time>,
+}
+
+/// Where a target, the Sun and the Moon are in the sky of an observatory.
+struct Sky {
+    alt: f64,
+    hour_angle: f64,
+    sun_alt: f64,
+    moon_separation: f64,
+}
+
+/// Observe a body from SPICE, with the light travel time.
+fn observe_spice_body(name: &str, observer: &crate::Observer, origin: &str) -> Result<crate::Observation, Box<dyn std::error::Error>> {
+    let mut body = SpaceRock::from_spice(name, &observer.epoch(), "J2000", origin)?;
+    let origin_velocity = body.origin_velocity()?;
+    body.observe_with_propagator(observer, &CorrectionLevel::Astrometric, &origin_velocity, |epoch| SpaceRock::from_spice(name, epoch, "J2000", origin))
+}
+
+/// Calculate where a target is in the sky of a ground observatory, propagating it on its two-body
+/// orbit. The Sun and the Moon come from SPICE, and are only looked up when asked for.
+fn calc_sky(rock: &SpaceRock, observatory: &Observatory, epoch: &Time, sun_and_moon: bool) -> Result<Sky, Box<dyn std::error::Error>> {
+    let origin = rock.origin.as_str();
+    let observer = observatory.at(epoch, "J2000", origin)?;
+
+    let mut target = rock.analytic_at(epoch)?;
+    target.change_reference_plane("J2000")?;
+    let observation = target.observe(&observer)?;
+    let pointing = observation.pointing();
+
+    let (alt, _) = calc_altaz(&pointing, &observer, None)?;
+    let (hour_angle, _) = calc_hour_angle(&pointing, &observer)?;
+
+    let (sun_alt, moon_separation) = if sun_and_moon {
+        let sun = observe_spice_body("sun", &observer, origin)?;
+        let (sun_alt, _) = calc_altaz(&sun.pointing(), &observer, None)?;
+        let moon = observe_spice_body("moon", &observer, origin)?;
+        (sun_alt, moon.pointing().angle(&pointing))
+    } else {
+        (0.0, 0.0)
+    };
+
+    Ok(Sky { alt, hour_angle, sun_alt, moon_separation })
+}
+
+/// Bisect for the time at which a condition changes, given that it differs at the two ends.
+fn bisect<F>(start: &Time, mut a: f64, mut b: f64, mut condition: F) -> Result<f64, Box<dyn std::error::Error>>
+where
+    F: FnMut(&Time) -> Result<bool, Box<dyn std::error::Error>>,
+{
+    let at_a = condition(&(start.clone() + a))?;
+    while b - a > EVENT_PRECISION {
+        let mid = 0.5 * (a + b);
+        if condition(&(start.clone() + mid))? == at_a {
+            a = mid;
+        } else {
+            b = mid;
+        }
+    }
+    Ok(0.5 * (a + b))
+}
+
+/// Calculate when a SpaceRock rises above an altitude, crosses the meridian and sets below the
+/// altitude, in the day after a time. The altitude is geometric; use about -0.57 degrees to
+/// include the refraction at the horizon.
+///
+/// # Arguments
+///
+/// * `rock` - The SpaceRock, relative to the SSB or the Sun
+/// * `observatory` - The ground observatory
+/// * `start` - The start of the day searched
+/// * `altitude` - The altitude of rising and setting (radians)
+///
+/// # Returns
+///
+/// * The RiseTransitSet
+pub fn calc_rise_transit_set(rock: &SpaceRock, observatory: &Observatory, start: &Time, altitude: f64) -> Result<RiseTransitSet, Box<dyn std::error::Error>> {
+    let steps = (1.0 / SEARCH_STEP).ceil() as usize;
+    let mut events = RiseTransitSet { rise: None, transit: None, set: None };
+
+    let mut previous = calc_sky(rock, observatory, start, false)?;
+    for i in 1..=steps {
+        let (a, b) = ((i - 1) as f64 * SEARCH_STEP, i as f64 * SEARCH_STEP);
+        let sky = calc_sky(rock, observatory, &(start.clone() + b), false)?;
+
+        if events.rise.is_none() && previous.alt < altitude && sky.alt >= altitude {
+            let t = bisect(start, a, b, |epoch| Ok(calc_sky(rock, observatory, epoch, false)?.alt >= altitude))?;
+            events.rise = Some(start.clone() + t);
+        }
+        if events.set.is_none() && previous.alt >= altitude && sky.alt < altitude {
+            let t = bisect(start, a, b, |epoch| Ok(calc_sky(rock, observatory, epoch, false)?.alt >= altitude))?;
+            events.set = Some(start.clone() + t);
+        }
+        // the hour angle passes through zero at transit, and jumps from pi to -pi half a day later
+        if events.transit.is_none() && previous.hour_angle < 0.0 && sky.hour_angle >= 0.0 && sky.hour_angle - previous.hour_angle < std::f64::consts::PI {
+            let t = bisect(start, a, b, |epoch| Ok(calc_sky(rock, observatory, epoch, false)?.hour_angle >= 0.0))?;
+            events.transit = Some(start.clone() + t);
+        }
+        previous = sky;
+    }
+
+    Ok(events)
+}
+
+/// Calculate the windows in which a SpaceRock can be observed from a ground observatory, between
+/// two times.
+///
+/// # Arguments
+///
+/// * `rock` - The SpaceRock, relative to the SSB or the Sun
+/// * `observatory` - The ground observatory
+/// * `start` - The start of the search
+/// * `end` - The end of the search
+/// * `constraints` - The limits on the altitude, the Sun and the Moon
+///
+/// # Returns
+///
+/// * The start and end of each window, in order
+pub fn calc_observable_windows(rock: &SpaceRock, observatory: &Observatory, start: &Time, end: &Time, constraints: &VisibilityConstraints) -> Result<Vec<(Time, Time)>, Box<dyn std::error::Error>> {
+    let span = end.tdb().jd() - start.tdb().jd();
+    if span <= 0.0 {
+        return Err("The end of the search must be after its start".into());
+    }
+
+    let mut observable = |epoch: &Time| -> Result<bool, Box<dyn std::error::Error>> {
+        let sky = calc_sky(rock, observatory, epoch, true)?;
+        Ok(sky.alt >= constraints.min_altitude && sky.sun_alt <= constraints.max_sun_altitude && sky.moon_separation >= constraints.min_moon_separation)
+    };
+
+    let steps = (span / SEARCH_STEP).ceil() as usize;
+    let mut windows = Vec::new();
+    let mut opened = if observable(start)? { Some(start.clone()) } else { None };
+
+    for i in 1..=steps {
+        let (a, b) = ((i - 1) as f64 * SEARCH_STEP, (i as f64 * SEARCH_STEP).min(span));
+        let now = observable(&(start.clone() + b))?;
+        if now == opened.is_some() {
+            continue;
+        }
+        let t = start.clone() + bisect(start, a, b, &mut observable)?;
+        match opened.take() {
+            Some(open) => windows.push((open, t)),
+            None => opened = Some(t),
+        }
+    }
+    if let Some(open) = opened {
+        windows.push((open, end.clone()));
+    }
+
+    Ok(windows)
+}
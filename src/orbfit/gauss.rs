@@ -10,7 +10,7 @@ pub fn gauss(o1: &Observation, o2: &Observation, o3: &Observation, min_distance:
 
     // get the order of the epochs
     let mut triplet = [o1, o2, o3];
-    triplet.sort_by(|a, b| a.epoch.epoch.partial_cmp(&b.epoch.epoch).unwrap());
+    triplet.sort_by(|a, b| a.epoch.jd().partial_cmp(&b.epoch.jd()).unwrap());
 
     let r1 = triplet[0].observer.position();
     let r2 = triplet[1].observer.position();
@@ -20,9 +20,9 @@ pub fn gauss(o1: &Observation, o2: &Observation, o3: &Observation, min_distance:
     let rho2 = triplet[1].pointing();
     let rho3 = triplet[2].pointing();
     
-    let t1 = triplet[0].epoch.epoch;
-    let t2 = triplet[1].epoch.epoch;
-    let t3 = triplet[2].epoch.epoch;
+    let t1 = triplet[0].epoch.jd();
+    let t2 = triplet[1].epoch.jd();
+    let t3 = triplet[2].epoch.jd();
 
     let tau1 = t1 - t2;
     let tau3 = t3 - t2;
@@ -100,7 +100,7 @@ pub fn gauss(o1: &Observation, o2: &Observation, o3: &Observation, min_distance:
 
         let ltt = r2.norm() / SPEED_OF_LIGHT;
         let mut corrected_t = triplet[1].epoch.clone();
-        corrected_t.epoch -= ltt;
+        corrected_t -= ltt;
         let rock = SpaceRock::from_xyz("rock", x, y, z, vx, vy, vz, corrected_t, "J2000", "SSb").expect("Failed to create SpaceRock from XYZ");
         res.push(rock);
     }
@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+
+/// A photometric band. Magnitudes from phase functions are in V.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Band {
+    /// Johnson V
+    V,
+    /// Gaia G
+    Gaia,
+    /// g, in the SDSS-like systems of SDSS, Pan-STARRS and Rubin
+    G,
+    /// r
+    R,
+    /// i
+    I,
+    /// z
+    Z,
+    /// y, of Pan-STARRS and Rubin
+    Y,
+    /// The wide Pan-STARRS w, which spans g, r and i
+    W,
+}
+
+impl Band {
+
+    /// Create a Band from a string. The case matters: "G" is Gaia G and "g" is g.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - One of "V", "G", "g", "r", "i", "z", "y" or "w"
+    ///
+    /// # Returns
+    ///
+    /// * The Band
+    pub fn from_str(s: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match s {
+            "V" | "v" => Ok(Band::V),
+            "G" | "Gaia" => Ok(Band::Gaia),
+            "g" => Ok(Band::G),
+            "r" | "R" => Ok(Band::R),
+            "i" | "I" => Ok(Band::I),
+            "z" | "Z" => Ok(Band::Z),
+            "y" | "Y" => Ok(Band::Y),
+            "w" | "W" => Ok(Band::W),
+            _ => Err(format!("Invalid band: {}", s).into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Band::V => "V",
+            Band::Gaia => "G",
+            Band::G => "g",
+            Band::R => "r",
+            Band::I => "i",
+            Band::Z => "z",
+            Band::Y => "y",
+            Band::W => "w",
+        }
+    }
+}
+
+impl std::fmt::Display for Band {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The colours of a body, as differences of magnitudes between neighbouring bands.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Colors {
+    pub g_r: f64,
+    pub r_i: f64,
+    pub i_z: f64,
+    pub z_y: f64,
+}
+
+impl Default for Colors {
+    /// The colours of the Sun, for a body with a flat reflectance spectrum.
+    fn default() -> Self {
+        Colors { g_r: 0.44, r_i: 0.11, i_z: 0.03, z_y: 0.02 }
+    }
+}
+
+/// Calculate the magnitude in a band minus the magnitude in V, for a body with some colours. V and
+/// r are related by Jester et al. (2005), Gaia G by Evans et al. (2018), and w is taken as the mean
+/// of g, r and i.
+///
+/// # Arguments
+///
+/// * `band` - The band
+/// * `colors` - The colours of the body
+///
+/// # Returns
+///
+/// * The magnitude in the band minus the magnitude in V
+pub fn calc_band_offset(band: &Band, colors: &Colors) -> f64 {
+    let r = -0.41 * colors.g_r + 0.01;
+    let g = r + colors.g_r;
+    let i = r - colors.r_i;
+    match band {
+        Band::V => 0.0,
+        Band::Gaia => {
+            let g_i = colors.g_r + colors.r_i;
+            g - 0.13518 - 0.46245 * g_i - 0.25171 * g_i.powi(2) + 0.021349 * g_i.powi(3)
+        }
+        Band::G => g,
+        Band::R => r,
+        Band::I => i,
+        Band::Z => i - colors.i_z,
+        Band::Y => i - colors.i_z - colors.z_y,
+        Band::W => (g + r + i) / 3.0,
+    }
+}
+
+/// Convert a magnitude from one band to another.
+///
+/// # Arguments
+///
+/// * `mag` - The magnitude in the first band
+/// * `from` - The band of the magnitude
+/// * `to` - The band to convert to
+/// * `colors` - The colours of the body
+///
+/// # Returns
+///
+/// * The magnitude in the second band
+pub fn calc_band_conversion(mag: f64, from: &Band, to: &Band, colors: &Colors) -> f64 {
+    mag - calc_band_offset(from, colors) + calc_band_offset(to, colors)
+}
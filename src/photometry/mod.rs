@@ -0,0 +1,5 @@
+pub mod phase_function;
+    pub use phase_function::{PhaseFunction, calc_hg_phase, calc_hg1g2_phase, calc_hg12_phase, calc_phase_angle, calc_magnitude};
+
+pub mod band;
+    pub use band::{Band, Colors, calc_band_offset, calc_band_conversion};
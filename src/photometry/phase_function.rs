@@ -0,0 +1,281 @@
+use serde::{Serialize, Deserialize};
+
+use crate::Properties;
+
+/// The phase angles (degrees), values and end derivatives (per radian) of the clamped cubic
+/// splines of the H,G1,G2 basis functions of Muinonen et al. (2010).
+const PHI1_NODES: [f64; 6] = [7.5, 30.0, 60.0, 90.0, 120.0, 150.0];
+const PHI1_VALUES: [f64; 6] = [7.5e-1, 3.3486016e-1, 1.3410560e-1, 5.1104756e-2, 2.1465687e-2, 3.6396989e-3];
+const PHI1_DERIVATIVES: (f64, f64) = (-1.9098593, -9.1328612e-2);
+
+const PHI2_NODES: [f64; 6] = [7.5, 30.0, 60.0, 90.0, 120.0, 150.0];
+const PHI2_VALUES: [f64; 6] = [9.25e-1, 6.2884169e-1, 3.1755495e-1, 1.2716367e-1, 2.2373903e-2, 1.6505689e-4];
+const PHI2_DERIVATIVES: (f64, f64) = (-5.7295780e-1, 8.6573138e-8);
+
+const PHI3_NODES: [f64; 9] = [0.0, 0.3, 1.0, 2.0, 4.0, 8.0, 12.0, 20.0, 30.0];
+const PHI3_VALUES: [f64; 9] = [1.0, 8.3381185e-1, 5.7735424e-1, 4.2144772e-1, 2.3174230e-1, 1.0348178e-1, 6.1733473e-2, 1.6107006e-2, 0.0];
+const PHI3_DERIVATIVES: (f64, f64) = (-1.0630097, 0.0);
+
+/// How the apparent magnitude of a body depends on its distances and phase angle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PhaseFunction {
+    /// The IAU H,G system of Bowell et al. (1989)
+    HG,
+    /// The H,G1,G2 system of Muinonen et al. (2010)
+    HG1G2,
+    /// The H,G12* system of Penttilä et al. (2016)
+    HG12,
+    /// H and a linear phase coefficient
+    Linear,
+    /// The total magnitude of a comet, from M1 and K1
+    CometTotal,
+    /// The nuclear magnitude of a comet, from M2, K2 and the phase coefficient
+    CometNuclear,
+}
+
+impl PhaseFunction {
+
+    /// Create a PhaseFunction from a string
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - One of "HG", "HG1G2", "HG12", "linear", "comet_total" or "comet_nuclear"
+    ///
+    /// # Returns
+    ///
+    /// * The PhaseFunction
+    pub fn from_str(s: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match s.to_uppercase().as_str() {
+            "HG" => Ok(PhaseFunction::HG),
+            "HG1G2" => Ok(PhaseFunction::HG1G2),
+            "HG12" | "HG12*" => Ok(PhaseFunction::HG12),
+            "LINEAR" => Ok(PhaseFunction::Linear),
+            "COMET_TOTAL" | "TOTAL" => Ok(PhaseFunction::CometTotal),
+            "COMET_NUCLEAR" | "NUCLEAR" => Ok(PhaseFunction::CometNuclear),
+            _ => Err(format!("Invalid phase function: {}", s).into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            PhaseFunction::HG => "HG",
+            PhaseFunction::HG1G2 => "HG1G2",
+            PhaseFunction::HG12 => "HG12",
+            PhaseFunction::Linear => "linear",
+            PhaseFunction::CometTotal => "comet_total",
+            PhaseFunction::CometNuclear => "comet_nuclear",
+        }
+    }
+
+    /// Choose the phase function for a set of properties: the one set explicitly, or else the
+    /// most detailed one whose parameters are known. An absolute magnitude with no slope uses
+    /// H,G with G = 0.15.
+    ///
+    /// # Arguments
+    ///
+    /// * `properties` - The properties of the body
+    ///
+    /// # Returns
+    ///
+    /// * The PhaseFunction, or None if the properties give no magnitude
+    pub fn infer(properties: &Properties) -> Option<PhaseFunction> {
+        if properties.phase_function.is_some() {
+            return properties.phase_function;
+        }
+        if properties.absolute_magnitude.is_some() {
+            return if properties.g1.is_some() && properties.g2.is_some() {
+                Some(PhaseFunction::HG1G2)
+            } else if properties.g12.is_some() {
+                Some(PhaseFunction::HG12)
+            } else if properties.phase_coefficient.is_some() {
+                Some(PhaseFunction::Linear)
+            } else {
+                Some(PhaseFunction::HG)
+            };
+        }
+        if properties.m1.is_some() && properties.k1.is_some() {
+            return Some(PhaseFunction::CometTotal);
+        }
+        if properties.m2.is_some() && properties.k2.is_some() {
+            return Some(PhaseFunction::CometNuclear);
+        }
+        None
+    }
+}
+
+impl std::fmt::Display for PhaseFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Calculate the second derivatives of a cubic spline through nodes, with its first derivatives
+/// clamped at the two ends.
+fn spline_second_derivatives(x: &[f64], y: &[f64], derivatives: (f64, f64)) -> Vec<f64> {
+    let n = x.len();
+    let mut y2 = vec![0.0; n];
+    let mut u = vec![0.0; n];
+
+    y2[0] = -0.5;
+    u[0] = (3.0 / (x[1] - x[0])) * ((y[1] - y[0]) / (x[1] - x[0]) - derivatives.0);
+    for i in 1..n - 1 {
+        let sig = (x[i] - x[i - 1]) / (x[i + 1] - x[i - 1]);
+        let p = sig * y2[i - 1] + 2.0;
+        y2[i] = (sig - 1.0) / p;
+        u[i] = (y[i + 1] - y[i]) / (x[i + 1] - x[i]) - (y[i] - y[i - 1]) / (x[i] - x[i - 1]);
+        u[i] = (6.0 * u[i] / (x[i + 1] - x[i - 1]) - sig * u[i - 1]) / p;
+    }
+    let qn = 0.5;
+    let un = (3.0 / (x[n - 1] - x[n - 2])) * (derivatives.1 - (y[n - 1] - y[n - 2]) / (x[n - 1] - x[n - 2]));
+    y2[n - 1] = (un - qn * u[n - 2]) / (qn * y2[n - 2] + 1.0);
+    for k in (0..n - 1).rev() {
+        y2[k] = y2[k] * y2[k + 1] + u[k];
+    }
+    y2
+}
+
+/// Evaluate a clamped cubic spline, through nodes in degrees, at a phase angle in radians. Beyond
+/// the last node the last piece is extended.
+fn evaluate_spline(nodes: &[f64], values: &[f64], derivatives: (f64, f64), phase_angle: f64) -> f64 {
+    let x: Vec<f64> = nodes.iter().map(|node| node.to_radians()).collect();
+    let y2 = spline_second_derivatives(&x, values, derivatives);
+
+    let hi = x.iter().position(|&node| node > phase_angle).unwrap_or(x.len() - 1).max(1);
+    let lo = hi - 1;
+    let h = x[hi] - x[lo];
+    let a = (x[hi] - phase_angle) / h;
+    let b = (phase_angle - x[lo]) / h;
+    a * values[lo] + b * values[hi] + ((a.powi(3) - a) * y2[lo] + (b.powi(3) - b) * y2[hi]) * h.powi(2) / 6.0
+}
+
+/// Calculate the phase angle, at a body, between the Sun and an observer, from the sides of their
+/// triangle.
+///
+/// # Arguments
+///
+/// * `sun_distance` - The distance from the Sun to the body (au)
+/// * `observer_distance` - The distance from the observer to the body (au)
+/// * `observer_sun_distance` - The distance from the Sun to the observer (au)
+///
+/// # Returns
+///
+/// * The phase angle (radians)
+pub fn calc_phase_angle(sun_distance: f64, observer_distance: f64, observer_sun_distance: f64) -> f64 {
+    let q = (sun_distance.powi(2) + observer_distance.powi(2) - observer_sun_distance.powi(2)) / (2.0 * sun_distance * observer_distance);
+    q.clamp(-1.0, 1.0).acos()
+}
+
+/// Calculate the reduction of the H,G system at a phase angle, from the approximate basis functions
+/// of Bowell et al. (1989).
+///
+/// # Arguments
+///
+/// * `phase_angle` - The phase angle (radians)
+/// * `g` - The slope parameter G
+///
+/// # Returns
+///
+/// * The magnitude to add to the reduced magnitude, infinite near 180° where no light is reflected
+pub fn calc_hg_phase(phase_angle: f64, g: f64) -> f64 {
+    let tan_half = (phase_angle / 2.0).tan();
+    let psi_1 = (-3.332 * tan_half.powf(0.631)).exp();
+    let psi_2 = (-1.862 * tan_half.powf(1.218)).exp();
+    if psi_1 == 0.0 && psi_2 == 0.0 {
+        return f64::INFINITY;
+    }
+    -2.5 * ((1.0 - g) * psi_1 + g * psi_2).log10()
+}
+
+/// Calculate the reduction of the H,G1,G2 system at a phase angle, from the basis functions of
+/// Muinonen et al. (2010).
+///
+/// # Arguments
+///
+/// * `phase_angle` - The phase angle (radians)
+/// * `g1` - The parameter G1
+/// * `g2` - The parameter G2
+///
+/// # Returns
+///
+/// * The magnitude to add to the reduced magnitude
+pub fn calc_hg1g2_phase(phase_angle: f64, g1: f64, g2: f64) -> f64 {
+    let (phi1, phi2) = if phase_angle < PHI1_NODES[0].to_radians() {
+        (1.0 - 6.0 * phase_angle / std::f64::consts::PI, 1.0 - 9.0 * phase_angle / (5.0 * std::f64::consts::PI))
+    } else {
+        (evaluate_spline(&PHI1_NODES, &PHI1_VALUES, PHI1_DERIVATIVES, phase_angle),
+         evaluate_spline(&PHI2_NODES, &PHI2_VALUES, PHI2_DERIVATIVES, phase_angle))
+    };
+    let phi3 = if phase_angle < PHI3_NODES[PHI3_NODES.len() - 1].to_radians() {
+        evaluate_spline(&PHI3_NODES, &PHI3_VALUES, PHI3_DERIVATIVES, phase_angle)
+    } else {
+        0.0
+    };
+    -2.5 * (g1 * phi1 + g2 * phi2 + (1.0 - g1 - g2) * phi3).log10()
+}
+
+/// Calculate the reduction of the H,G12* system of Penttilä et al. (2016) at a phase angle, which
+/// is the H,G1,G2 system with G1 and G2 fixed by G12*.
+///
+/// # Arguments
+///
+/// * `phase_angle` - The phase angle (radians)
+/// * `g12` - The parameter G12*
+///
+/// # Returns
+///
+/// * The magnitude to add to the reduced magnitude
+pub fn calc_hg12_phase(phase_angle: f64, g12: f64) -> f64 {
+    calc_hg1g2_phase(phase_angle, 0.84293649 * g12, 0.53513350 * (1.0 - g12))
+}
+
+/// Calculate the apparent magnitude of a body from its properties.
+///
+/// # Arguments
+///
+/// * `properties` - The properties of the body, with the parameters of the phase function
+/// * `phase_function` - The phase function to use
+/// * `sun_distance` - The distance from the Sun to the body (au)
+/// * `observer_distance` - The distance from the observer to the body (au)
+/// * `phase_angle` - The phase angle (radians)
+///
+/// # Returns
+///
+/// * The apparent magnitude
+pub fn calc_magnitude(properties: &Properties, phase_function: &PhaseFunction, sun_distance: f64, observer_distance: f64, phase_angle: f64) -> Result<f64, Box<dyn std::error::Error>> {
+    let missing = |parameter: &str| format!("The {} phase function needs {}", phase_function, parameter);
+    let reduced = |h: f64| h + 5.0 * (sun_distance * observer_distance).log10();
+
+    match phase_function {
+        PhaseFunction::HG => {
+            let h = properties.absolute_magnitude.ok_or_else(|| missing("an absolute magnitude"))?;
+            Ok(reduced(h) + calc_hg_phase(phase_angle, properties.gslope.unwrap_or(0.15)))
+        }
+        PhaseFunction::HG1G2 => {
+            let h = properties.absolute_magnitude.ok_or_else(|| missing("an absolute magnitude"))?;
+            let g1 = properties.g1.ok_or_else(|| missing("G1"))?;
+            let g2 = properties.g2.ok_or_else(|| missing("G2"))?;
+            Ok(reduced(h) + calc_hg1g2_phase(phase_angle, g1, g2))
+        }
+        PhaseFunction::HG12 => {
+            let h = properties.absolute_magnitude.ok_or_else(|| missing("an absolute magnitude"))?;
+            let g12 = properties.g12.ok_or_else(|| missing("G12"))?;
+            Ok(reduced(h) + calc_hg12_phase(phase_angle, g12))
+        }
+        PhaseFunction::Linear => {
+            let h = properties.absolute_magnitude.ok_or_else(|| missing("an absolute magnitude"))?;
+            let coefficient = properties.phase_coefficient.ok_or_else(|| missing("a phase coefficient"))?;
+            Ok(reduced(h) + coefficient * phase_angle.to_degrees())
+        }
+        PhaseFunction::CometTotal => {
+            let m1 = properties.m1.ok_or_else(|| missing("M1"))?;
+            let k1 = properties.k1.ok_or_else(|| missing("K1"))?;
+            Ok(m1 + 5.0 * observer_distance.log10() + k1 * sun_distance.log10())
+        }
+        PhaseFunction::CometNuclear => {
+            let m2 = properties.m2.ok_or_else(|| missing("M2"))?;
+            let k2 = properties.k2.ok_or_else(|| missing("K2"))?;
+            let coefficient = properties.phase_coefficient.unwrap_or(0.0);
+            Ok(m2 + 5.0 * observer_distance.log10() + k2 * sun_distance.log10() + coefficient * phase_angle.to_degrees())
+        }
+    }
+}
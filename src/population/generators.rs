@@ -0,0 +1,400 @@
+use std::f64::consts::{PI, TAU};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::{SpaceRock, Time};
+use crate::constants::PLANET_SEMIMAJOR_AXES;
+use crate::population::{HDistribution, Histogram};
+use crate::transforms::calc_true_anomaly_from_mean_anomaly;
+use crate::utils::standard_normal;
+
+/// The perihelion distance inside which an orbit is near-Earth (au).
+const NEO_PERIHELION: f64 = 1.3;
+
+/// The number of draws allowed for one orbit, before a population is taken to be empty.
+const MAX_DRAWS: usize = 10000;
+
+/// A planet that holds small bodies in mean-motion resonances.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Perturber {
+    Jupiter,
+    Neptune,
+}
+
+impl Perturber {
+
+    pub fn from_str(s: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match s.to_lowercase().as_str() {
+            "jupiter" => Ok(Perturber::Jupiter),
+            "neptune" => Ok(Perturber::Neptune),
+            _ => Err(format!("Unknown perturber: {}", s).into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Perturber::Jupiter => "jupiter",
+            Perturber::Neptune => "neptune",
+        }
+    }
+
+    /// The mean semi-major axis (au).
+    pub fn semimajor_axis(&self) -> f64 {
+        PLANET_SEMIMAJOR_AXES[self.as_str()]
+    }
+
+    /// The mean longitude at an epoch, in the ecliptic of J2000, from the mean elements of
+    /// Standish (1992), good to a fraction of a degree between 1800 and 2050.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch
+    ///
+    /// # Returns
+    ///
+    /// * The mean longitude (radians)
+    pub fn mean_longitude(&self, epoch: &Time) -> f64 {
+        let t = (epoch.tdb().jd() - 2451545.0) / 36525.0;
+        let (longitude, rate) = match self {
+            Perturber::Jupiter => (34.39644051, 3034.74612775),
+            Perturber::Neptune => (-55.12002969, 218.45945325),
+        };
+        (longitude + rate * t).to_radians().rem_euclid(TAU)
+    }
+}
+
+/// Near-Earth objects drawn from a histogram in (a, e, i) or (a, e, i, H), e.g. the model of
+/// Granvik et al. (2018). Orbits outside q < 1.3 au are redrawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearEarthObjects {
+    /// The histogram of semi-major axis (au), eccentricity, inclination (radians) and optionally H
+    pub histogram: Histogram,
+}
+
+impl Default for NearEarthObjects {
+    /// A coarse stand-in for the NEO model of Granvik et al. (2018), the product of rough fits to its
+    /// marginal distributions. Supply the histogram of the model itself for debiasing.
+    fn default() -> Self {
+        let a_edges = vec![0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 2.25, 2.5, 2.75, 3.0, 3.5, 4.2];
+        let a_weights = [0.5, 1.5, 3.0, 5.0, 7.0, 9.0, 10.0, 9.0, 7.5, 5.5, 6.0, 3.0];
+        let e_edges: Vec<f64> = (0..=10).map(|k| 0.1 * k as f64).collect();
+        let e_weights = [0.5, 2.0, 4.5, 7.0, 8.5, 8.0, 6.5, 4.5, 2.5, 1.0];
+        let inc_edges: Vec<f64> = [0.0, 5.0, 10.0, 15.0, 20.0, 30.0, 40.0, 60.0, 90.0].iter().map(|i: &f64| i.to_radians()).collect();
+        let inc_weights = [11.0, 14.0, 13.0, 11.0, 16.0, 10.0, 8.0, 2.0];
+
+        let mut weights = Vec::with_capacity(a_weights.len() * e_weights.len() * inc_weights.len());
+        for wa in a_weights.iter() {
+            for we in e_weights.iter() {
+                for wi in inc_weights.iter() {
+                    weights.push(wa * we * wi);
+                }
+            }
+        }
+        let histogram = Histogram::new(vec![a_edges, e_edges, inc_edges], weights).expect("The default NEO histogram is valid");
+        NearEarthObjects { histogram }
+    }
+}
+
+/// Main-belt asteroids, uniform in semi-major axis, with normally distributed eccentricities.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MainBelt {
+    /// The inner edge of the belt (au)
+    pub a_min: f64,
+    /// The outer edge of the belt (au)
+    pub a_max: f64,
+    /// The mean eccentricity
+    pub e_mean: f64,
+    /// The standard deviation of the eccentricity
+    pub e_width: f64,
+    /// The width of the inclination distribution (radians)
+    pub inc_width: f64,
+}
+
+impl Default for MainBelt {
+    fn default() -> Self {
+        MainBelt { a_min: 2.1, a_max: 3.3, e_mean: 0.14, e_width: 0.07, inc_width: 7.0_f64.to_radians() }
+    }
+}
+
+/// Jupiter trojans, librating about the L4 and L5 points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JupiterTrojans {
+    /// The fraction of trojans in the leading (L4) swarm
+    pub l4_fraction: f64,
+    /// The largest libration amplitude about the Lagrange point (radians)
+    pub max_amplitude: f64,
+    /// The largest eccentricity
+    pub e_max: f64,
+    /// The width of the inclination distribution (radians)
+    pub inc_width: f64,
+}
+
+impl Default for JupiterTrojans {
+    fn default() -> Self {
+        JupiterTrojans { l4_fraction: 0.6, max_amplitude: 30.0_f64.to_radians(), e_max: 0.15, inc_width: 12.0_f64.to_radians() }
+    }
+}
+
+/// Classical trans-Neptunian objects, a mix of a dynamically cold and a hot component.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassicalTnos {
+    /// The inner edge of the belt (au)
+    pub a_min: f64,
+    /// The outer edge of the belt (au)
+    pub a_max: f64,
+    /// The largest eccentricity
+    pub e_max: f64,
+    /// The fraction of objects in the cold component
+    pub cold_fraction: f64,
+    /// The width of the inclination distribution of the cold component (radians)
+    pub cold_inc_width: f64,
+    /// The width of the inclination distribution of the hot component (radians)
+    pub hot_inc_width: f64,
+}
+
+impl Default for ClassicalTnos {
+    fn default() -> Self {
+        ClassicalTnos {
+            a_min: 42.4,
+            a_max: 47.7,
+            e_max: 0.1,
+            cold_fraction: 0.6,
+            cold_inc_width: 2.6_f64.to_radians(),
+            hot_inc_width: 15.0_f64.to_radians(),
+        }
+    }
+}
+
+/// Objects in a p:q mean-motion resonance with a planet, with resonant angle
+/// φ = pλ - qλ' - (p - q)ϖ librating about a centre with an amplitude drawn uniformly up to a
+/// largest amplitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResonantTnos {
+    pub perturber: Perturber,
+    /// The ratio of the period of the object to that of the planet is p/q
+    pub p: u32,
+    pub q: u32,
+    /// The centre of libration of the resonant angle (radians)
+    pub center: f64,
+    /// The largest libration amplitude (radians)
+    pub max_amplitude: f64,
+    /// The smallest eccentricity
+    pub e_min: f64,
+    /// The largest eccentricity
+    pub e_max: f64,
+    /// The width of the inclination distribution (radians)
+    pub inc_width: f64,
+}
+
+impl Default for ResonantTnos {
+    /// The plutinos, in the 3:2 resonance with Neptune.
+    fn default() -> Self {
+        ResonantTnos {
+            perturber: Perturber::Neptune,
+            p: 3,
+            q: 2,
+            center: PI,
+            max_amplitude: 120.0_f64.to_radians(),
+            e_min: 0.05,
+            e_max: 0.3,
+            inc_width: 12.0_f64.to_radians(),
+        }
+    }
+}
+
+/// Scattered trans-Neptunian objects, uniform in perihelion distance, with dN/da ∝ a^slope.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScatteredTnos {
+    /// The smallest perihelion distance (au)
+    pub q_min: f64,
+    /// The largest perihelion distance (au)
+    pub q_max: f64,
+    /// The smallest semi-major axis (au)
+    pub a_min: f64,
+    /// The largest semi-major axis (au)
+    pub a_max: f64,
+    /// The slope of the distribution of semi-major axes
+    pub a_slope: f64,
+    /// The width of the inclination distribution (radians)
+    pub inc_width: f64,
+}
+
+impl Default for ScatteredTnos {
+    fn default() -> Self {
+        ScatteredTnos { q_min: 30.0, q_max: 38.0, a_min: 50.0, a_max: 1000.0, a_slope: -1.5, inc_width: 20.0_f64.to_radians() }
+    }
+}
+
+/// A synthetic population of small bodies, to draw orbits and absolute magnitudes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Population {
+    NearEarthObjects(NearEarthObjects),
+    MainBelt(MainBelt),
+    JupiterTrojans(JupiterTrojans),
+    ClassicalTnos(ClassicalTnos),
+    ResonantTnos(ResonantTnos),
+    ScatteredTnos(ScatteredTnos),
+}
+
+/// The elements of one draw from a population, with an absolute magnitude if the population gives one.
+struct Draw {
+    a: f64,
+    e: f64,
+    inc: f64,
+    arg: f64,
+    node: f64,
+    mean_anomaly: f64,
+    h: Option<f64>,
+}
+
+impl Population {
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Population::NearEarthObjects(_) => "neo",
+            Population::MainBelt(_) => "main_belt",
+            Population::JupiterTrojans(_) => "trojan",
+            Population::ClassicalTnos(_) => "classical",
+            Population::ResonantTnos(_) => "resonant",
+            Population::ScatteredTnos(_) => "scattered",
+        }
+    }
+
+    /// Draw a population of SpaceRocks. The draws are made in order from a single generator, so a
+    /// seed gives the same population every time.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of rocks
+    /// * `h_distribution` - The distribution of absolute magnitudes, unless the population gives them
+    /// * `epoch` - The epoch of the orbits
+    /// * `seed` - An optional seed for the random number generator, for reproducible draws
+    ///
+    /// # Returns
+    ///
+    /// * The rocks, heliocentric in the ecliptic of J2000, named `{population}_{idx}`
+    pub fn sample(&self, n: usize, h_distribution: &HDistribution, epoch: &Time, seed: Option<u64>) -> Result<Vec<SpaceRock>, Box<dyn std::error::Error>> {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut rocks = Vec::with_capacity(n);
+        for idx in 0..n {
+            let draw = self.draw(&mut rng, epoch)?;
+            let h = match draw.h {
+                Some(h) => h,
+                None => h_distribution.sample(&mut rng),
+            };
+            let true_anomaly = calc_true_anomaly_from_mean_anomaly(draw.e, draw.mean_anomaly)?;
+            let name = format!("{}_{}", self.as_str(), idx);
+            let mut rock = SpaceRock::from_kepler(&name, draw.a * (1.0 - draw.e), draw.e, draw.inc, draw.arg, draw.node, true_anomaly, epoch.clone(), "ECLIPJ2000", "SUN")?;
+            rock.set_absolute_magnitude(h);
+            rocks.push(rock);
+        }
+        Ok(rocks)
+    }
+
+    fn draw(&self, rng: &mut StdRng, epoch: &Time) -> Result<Draw, Box<dyn std::error::Error>> {
+        for _ in 0..MAX_DRAWS {
+            let node = TAU * rng.gen::<f64>();
+            let arg = TAU * rng.gen::<f64>();
+
+            let draw = match self {
+                Population::NearEarthObjects(neos) => {
+                    let point = neos.histogram.sample(rng);
+                    if point.len() < 3 {
+                        return Err("A histogram of NEOs needs axes of a, e and i".into());
+                    }
+                    let (a, e, inc) = (point[0], point[1], point[2]);
+                    if e >= 1.0 || a * (1.0 - e) >= NEO_PERIHELION {
+                        continue;
+                    }
+                    Draw { a, e, inc, arg, node, mean_anomaly: TAU * rng.gen::<f64>(), h: point.get(3).copied() }
+                },
+                Population::MainBelt(belt) => {
+                    let a = belt.a_min + (belt.a_max - belt.a_min) * rng.gen::<f64>();
+                    let e = belt.e_mean + belt.e_width * standard_normal(rng);
+                    if !(0.0..1.0).contains(&e) || a * (1.0 - e) < NEO_PERIHELION {
+                        continue;
+                    }
+                    let inc = sample_inclination(rng, belt.inc_width);
+                    Draw { a, e, inc, arg, node, mean_anomaly: TAU * rng.gen::<f64>(), h: None }
+                },
+                Population::JupiterTrojans(trojans) => {
+                    let e = trojans.e_max * rng.gen::<f64>();
+                    let inc = sample_inclination(rng, trojans.inc_width);
+                    let center = if rng.gen::<f64>() < trojans.l4_fraction { PI / 3.0 } else { -PI / 3.0 };
+                    let resonance = ResonantTnos { perturber: Perturber::Jupiter, p: 1, q: 1, center, max_amplitude: trojans.max_amplitude, e_min: 0.0, e_max: trojans.e_max, inc_width: trojans.inc_width };
+                    let mean_anomaly = calc_resonant_mean_anomaly(rng, &resonance, node + arg, epoch);
+                    Draw { a: Perturber::Jupiter.semimajor_axis(), e, inc, arg, node, mean_anomaly, h: None }
+                },
+                Population::ClassicalTnos(classicals) => {
+                    let a = classicals.a_min + (classicals.a_max - classicals.a_min) * rng.gen::<f64>();
+                    let e = classicals.e_max * rng.gen::<f64>();
+                    let width = if rng.gen::<f64>() < classicals.cold_fraction { classicals.cold_inc_width } else { classicals.hot_inc_width };
+                    let inc = sample_inclination(rng, width);
+                    Draw { a, e, inc, arg, node, mean_anomaly: TAU * rng.gen::<f64>(), h: None }
+                },
+                Population::ResonantTnos(resonants) => {
+                    if resonants.p == 0 || resonants.q == 0 {
+                        return Err("The orders of a resonance must be positive".into());
+                    }
+                    let a = resonants.perturber.semimajor_axis() * (resonants.p as f64 / resonants.q as f64).powf(2.0 / 3.0);
+                    let e = resonants.e_min + (resonants.e_max - resonants.e_min) * rng.gen::<f64>();
+                    let inc = sample_inclination(rng, resonants.inc_width);
+                    let mean_anomaly = calc_resonant_mean_anomaly(rng, resonants, node + arg, epoch);
+                    Draw { a, e, inc, arg, node, mean_anomaly, h: None }
+                },
+                Population::ScatteredTnos(scattered) => {
+                    let q = scattered.q_min + (scattered.q_max - scattered.q_min) * rng.gen::<f64>();
+                    let a = sample_power_law(rng, scattered.a_slope, scattered.a_min, scattered.a_max);
+                    if a <= q {
+                        continue;
+                    }
+                    let inc = sample_inclination(rng, scattered.inc_width);
+                    Draw { a, e: 1.0 - q / a, inc, arg, node, mean_anomaly: TAU * rng.gen::<f64>(), h: None }
+                },
+            };
+            return Ok(draw);
+        }
+        Err(format!("Could not draw an orbit from the {} population", self.as_str()).into())
+    }
+}
+
+/// Place a resonant object on its orbit, by drawing its resonant angle from a libration about a
+/// centre. Of the p longitudes that give the angle, one is picked at random.
+fn calc_resonant_mean_anomaly(rng: &mut StdRng, resonance: &ResonantTnos, varpi: f64, epoch: &Time) -> f64 {
+    let amplitude = resonance.max_amplitude * rng.gen::<f64>();
+    let phi = resonance.center + amplitude * (TAU * rng.gen::<f64>()).sin();
+    let branch = rng.gen_range(0..resonance.p) as f64;
+    let (p, q) = (resonance.p as f64, resonance.q as f64);
+    let longitude = (phi + q * resonance.perturber.mean_longitude(epoch) + (p - q) * varpi + TAU * branch) / p;
+    (longitude - varpi).rem_euclid(TAU)
+}
+
+/// Draw an inclination from sin(i) exp(-i²/2σ²) (Brown 2001), by rejection from a Rayleigh
+/// distribution.
+fn sample_inclination(rng: &mut StdRng, width: f64) -> f64 {
+    if width <= 0.0 {
+        return 0.0;
+    }
+    loop {
+        let inc = width * (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt();
+        if inc > 0.0 && inc < PI && rng.gen::<f64>() < inc.sin() / inc {
+            return inc;
+        }
+    }
+}
+
+/// Draw from dN/dx ∝ x^slope between two bounds.
+fn sample_power_law(rng: &mut StdRng, slope: f64, min: f64, max: f64) -> f64 {
+    let u = rng.gen::<f64>();
+    if (slope + 1.0).abs() < 1e-12 {
+        min * (max / min).powf(u)
+    } else {
+        let k = slope + 1.0;
+        (min.powf(k) + u * (max.powf(k) - min.powf(k))).powf(1.0 / k)
+    }
+}
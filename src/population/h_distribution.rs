@@ -0,0 +1,147 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+
+/// A distribution of absolute magnitudes, as a broken power law. In each piece the differential
+/// distribution is dN/dH ∝ 10^(αH), and the pieces join continuously at the breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HDistribution {
+    /// The brightest absolute magnitude
+    pub h_min: f64,
+    /// The faintest absolute magnitude
+    pub h_max: f64,
+    /// The logarithmic slope α of each piece, from bright to faint
+    pub slopes: Vec<f64>,
+    /// The absolute magnitudes where the slope changes, from bright to faint
+    pub breaks: Vec<f64>,
+}
+
+impl HDistribution {
+
+    /// A single power law.
+    ///
+    /// # Arguments
+    ///
+    /// * `slope` - The logarithmic slope α
+    /// * `h_min` - The brightest absolute magnitude
+    /// * `h_max` - The faintest absolute magnitude
+    ///
+    /// # Returns
+    ///
+    /// * The HDistribution
+    pub fn power_law(slope: f64, h_min: f64, h_max: f64) -> Result<Self, Box<dyn std::error::Error>> {
+        HDistribution::broken_power_law(vec![slope], vec![], h_min, h_max)
+    }
+
+    /// A power law whose slope changes at one or more breaks.
+    ///
+    /// # Arguments
+    ///
+    /// * `slopes` - The logarithmic slope of each piece, from bright to faint
+    /// * `breaks` - The absolute magnitudes of the breaks, one fewer than the slopes
+    /// * `h_min` - The brightest absolute magnitude
+    /// * `h_max` - The faintest absolute magnitude
+    ///
+    /// # Returns
+    ///
+    /// * The HDistribution
+    pub fn broken_power_law(slopes: Vec<f64>, breaks: Vec<f64>, h_min: f64, h_max: f64) -> Result<Self, Box<dyn std::error::Error>> {
+        if h_max <= h_min {
+            return Err("The faintest absolute magnitude must be fainter than the brightest".into());
+        }
+        if slopes.len() != breaks.len() + 1 {
+            return Err("A broken power law needs one more slope than breaks".into());
+        }
+        let mut last = h_min;
+        for h in breaks.iter() {
+            if *h <= last || *h >= h_max {
+                return Err("The breaks must increase, between the brightest and faintest absolute magnitudes".into());
+            }
+            last = *h;
+        }
+        Ok(HDistribution { h_min, h_max, slopes, breaks })
+    }
+
+    /// The range of each piece, with the share of the distribution in it.
+    fn pieces(&self) -> Vec<(f64, f64, f64, f64)> {
+        let mut edges = vec![self.h_min];
+        edges.extend(self.breaks.iter());
+        edges.push(self.h_max);
+
+        // scale each piece to meet the last at its break, relative to the brightest
+        let mut scale = 0.0;
+        let mut pieces = Vec::with_capacity(self.slopes.len());
+        for (k, slope) in self.slopes.iter().enumerate() {
+            let (a, b) = (edges[k], edges[k + 1]);
+            if k > 0 {
+                scale += (self.slopes[k - 1] - slope) * a;
+            }
+            let mass = if *slope == 0.0 {
+                b - a
+            } else {
+                (10.0_f64.powf(slope * (b - a)) - 1.0) / (slope * std::f64::consts::LN_10)
+            } * 10.0_f64.powf(scale + slope * a - self.slopes[0] * self.h_min);
+            pieces.push((a, b, *slope, mass));
+        }
+        pieces
+    }
+
+    /// The fraction of the distribution brighter than an absolute magnitude.
+    ///
+    /// # Arguments
+    ///
+    /// * `h` - The absolute magnitude
+    ///
+    /// # Returns
+    ///
+    /// * The fraction, between 0 and 1
+    pub fn fraction_brighter(&self, h: f64) -> f64 {
+        let pieces = self.pieces();
+        let total: f64 = pieces.iter().map(|piece| piece.3).sum();
+        let mut below = 0.0;
+        for (a, b, slope, mass) in pieces {
+            if h >= b {
+                below += mass;
+            } else if h > a {
+                below += mass * calc_piece_fraction(a, b, slope, h);
+            }
+        }
+        below / total
+    }
+
+    /// Draw an absolute magnitude.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    ///
+    /// * The absolute magnitude
+    pub fn sample(&self, rng: &mut StdRng) -> f64 {
+        let pieces = self.pieces();
+        let total: f64 = pieces.iter().map(|piece| piece.3).sum();
+        let mut u = rng.gen::<f64>() * total;
+        for (a, b, slope, mass) in pieces.iter() {
+            if u < *mass {
+                // invert the cumulative distribution of the piece
+                let v = u / mass;
+                return if *slope == 0.0 {
+                    a + v * (b - a)
+                } else {
+                    a + (1.0 + v * (10.0_f64.powf(slope * (b - a)) - 1.0)).log10() / slope
+                };
+            }
+            u -= mass;
+        }
+        self.h_max
+    }
+}
+
+/// The fraction of a piece of the power law brighter than an absolute magnitude inside it.
+fn calc_piece_fraction(a: f64, b: f64, slope: f64, h: f64) -> f64 {
+    if slope == 0.0 {
+        (h - a) / (b - a)
+    } else {
+        (10.0_f64.powf(slope * (h - a)) - 1.0) / (10.0_f64.powf(slope * (b - a)) - 1.0)
+    }
+}
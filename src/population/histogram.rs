@@ -0,0 +1,80 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+
+/// A binned distribution over several quantities, e.g. the (a, e, i, H) of a model of the NEOs.
+/// Draws pick a bin by its weight, and fall uniformly inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// The edges of the bins along each axis, increasing
+    pub edges: Vec<Vec<f64>>,
+    /// The weight of each bin, with the last axis varying fastest
+    pub weights: Vec<f64>,
+    cumulative: Vec<f64>,
+}
+
+impl Histogram {
+
+    /// Create a histogram from the edges of its bins and their weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - The edges of the bins along each axis, increasing
+    /// * `weights` - The weight of each bin, with the last axis varying fastest
+    ///
+    /// # Returns
+    ///
+    /// * The Histogram
+    pub fn new(edges: Vec<Vec<f64>>, weights: Vec<f64>) -> Result<Self, Box<dyn std::error::Error>> {
+        if edges.is_empty() {
+            return Err("A histogram needs at least one axis".into());
+        }
+        for axis in edges.iter() {
+            if axis.len() < 2 || axis.windows(2).any(|pair| pair[1] <= pair[0]) {
+                return Err("The edges of a histogram must increase, with at least one bin per axis".into());
+            }
+        }
+        let bins: usize = edges.iter().map(|axis| axis.len() - 1).product();
+        if weights.len() != bins {
+            return Err(format!("A histogram with {} bins needs {} weights, not {}", bins, bins, weights.len()).into());
+        }
+        if weights.iter().any(|w| *w < 0.0 || !w.is_finite()) {
+            return Err("The weights of a histogram must be finite and not negative".into());
+        }
+
+        let mut total = 0.0;
+        let cumulative: Vec<f64> = weights.iter().map(|w| { total += w; total }).collect();
+        if total <= 0.0 {
+            return Err("A histogram needs a bin with weight".into());
+        }
+        Ok(Histogram { edges, weights, cumulative })
+    }
+
+    /// The number of axes.
+    pub fn dimension(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Draw a point.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    ///
+    /// * The value along each axis
+    pub fn sample(&self, rng: &mut StdRng) -> Vec<f64> {
+        let total = self.cumulative[self.cumulative.len() - 1];
+        let u = rng.gen::<f64>() * total;
+        let mut index = self.cumulative.partition_point(|c| *c <= u).min(self.cumulative.len() - 1);
+
+        let mut point = vec![0.0; self.edges.len()];
+        for (axis, edges) in self.edges.iter().enumerate().rev() {
+            let bins = edges.len() - 1;
+            let bin = index % bins;
+            index /= bins;
+            point[axis] = edges[bin] + rng.gen::<f64>() * (edges[bin + 1] - edges[bin]);
+        }
+        point
+    }
+}
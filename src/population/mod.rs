@@ -0,0 +1,8 @@
+pub mod h_distribution;
+    pub use h_distribution::HDistribution;
+
+pub mod histogram;
+    pub use histogram::Histogram;
+
+pub mod generators;
+    pub use generators::{Population, Perturber, NearEarthObjects, MainBelt, JupiterTrojans, ClassicalTnos, ResonantTnos, ScatteredTnos};
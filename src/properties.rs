@@ -1,5 +1,7 @@
 use serde::{Serialize, Deserialize};
 
+use crate::photometry::{Colors, PhaseFunction};
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Properties {
     pub mass: Option<f64>,
@@ -7,4 +9,21 @@ pub struct Properties {
     pub gslope: Option<f64>,
     pub radius: Option<f64>,
     pub albedo: Option<f64>,
-}
\ No newline at end of file
+    /// The parameters of the H,G1,G2 system
+    pub g1: Option<f64>,
+    pub g2: Option<f64>,
+    /// The parameter of the H,G12* system
+    pub g12: Option<f64>,
+    /// The linear phase coefficient (magnitudes per degree)
+    pub phase_coefficient: Option<f64>,
+    /// The total magnitude parameters of a comet
+    pub m1: Option<f64>,
+    pub k1: Option<f64>,
+    /// The nuclear magnitude parameters of a comet
+    pub m2: Option<f64>,
+    pub k2: Option<f64>,
+    /// The phase function used for magnitudes, or None to choose it from the parameters known
+    pub phase_function: Option<PhaseFunction>,
+    /// The colours used to convert magnitudes between bands, or None for solar colours
+    pub colors: Option<Colors>,
+}
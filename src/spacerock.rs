@@ -1,9 +1,12 @@
 use crate::{Origin, ReferencePlane, Time, Properties, Observer, Observation};
+use crate::observing::{CorrectionLevel, calc_apparent_direction, calc_observed_direction};
+use crate::observing::{Observatory, VisibilityConstraints, RiseTransitSet, calc_rise_transit_set, calc_observable_windows};
+use crate::photometry::{Band, Colors, PhaseFunction, calc_magnitude, calc_phase_angle};
 use crate::constants::*;
-use crate::correct_for_ltt;
 use crate::OrbitType;
+use crate::dynamics::{calc_moid, Moid, calc_collision_probability, CollisionProbability, calc_dynamical_class, DynamicalClass};
 
-use crate::transforms::{calc_conic_anomaly_from_true_anomaly, calc_mean_anomaly_from_conic_anomaly, solve_for_universal_anomaly, stumpff_c, stumpff_s};
+use crate::transforms::{calc_conic_anomaly_from_true_anomaly, calc_mean_anomaly_from_conic_anomaly, solve_for_universal_anomaly, solve_for_light_time, stumpff_c, stumpff_s};
 
 use serde::{Serialize, Deserialize};
 use nalgebra::Vector3;
@@ -56,12 +59,18 @@ impl SpaceRock {
 
         // check a priori if the name is in the list of loaded kernels
 
-        let reference_plane = ReferencePlane::from_str(reference_plane)?;
+        let reference_plane = ReferencePlane::from_str_at(reference_plane, epoch)?;
         let origin = Origin::from_str(origin)?;
 
+        // SPICE does not know the frames of date, so query in J2000 and rotate afterwards
+        let spice_plane = match reference_plane.epoch() {
+            Some(_) => ReferencePlane::J2000,
+            None => reference_plane.clone(),
+        };
+
         // let mut ep = epoch.clone();
         let et = spice::str2et(&format!("JD{epoch} UTC", epoch=epoch.utc().jd()));
-        let (state, _) = spice::spkezr(name, et, reference_plane.as_str(), "NONE", &origin.to_string());
+        let (state, _) = spice::spkezr(name, et, spice_plane.as_str(), "NONE", &origin.to_string());
         let position = Vector3::new(state[0], state[1], state[2]) * KM_TO_AU;
         let velocity = Vector3::new(state[3], state[4], state[5]) * KM_TO_AU * SECONDS_PER_DAY;
 
@@ -70,10 +79,11 @@ impl SpaceRock {
             position,
             velocity,
             epoch: epoch.clone(),
-            reference_plane,
+            reference_plane: spice_plane,
             origin,
             properties: None,
         };
+        rock.change_reference_plane_to(&reference_plane)?;
 
         if let Some(m) = MASSES.get(name.to_lowercase().as_str()) { rock.set_mass(*m) };
 
@@ -151,7 +161,7 @@ impl SpaceRock {
     /// ```
     pub fn from_xyz(name: &str, x: f64, y: f64, z: f64, vx: f64, vy: f64, vz: f64, epoch: Time, reference_plane: &str, origin: &str) -> Result<Self, Box<dyn std::error::Error>> {
 
-        let reference_plane = ReferencePlane::from_str(reference_plane)?;
+        let reference_plane = ReferencePlane::from_str_at(reference_plane, &epoch)?;
         let origin = Origin::from_str(origin)?;
 
         let position = Vector3::new(x, y, z);
@@ -200,7 +210,6 @@ impl SpaceRock {
         let mut ep = epoch.clone();
 
         let timescale = &ep.timescale.to_str().to_uppercase();
-        let timeformat = &ep.format.to_str().to_uppercase();
 
         match reference_plane.to_uppercase().as_str() {
             "J2000" => {
@@ -226,11 +235,10 @@ impl SpaceRock {
         ep.to_tdb();
         params.insert("TIME_TYPE", "'TDB'");
 
-        let time_list = format!("'{}'", ep.epoch);
+        // Horizons only reads some formats, so always send a Julian Date
+        let time_list = format!("'{}'", ep.jd());
         params.insert("TLIST", time_list.as_str());
-
-        let tf = format!("'{}'", timeformat);
-        params.insert("TLIST_TYPE", tf.as_str());
+        params.insert("TLIST_TYPE", "'JD'");
 
         let center = format!("'@{}'", origin);
         params.insert("center", center.as_str());
@@ -375,10 +383,12 @@ impl SpaceRock {
         let gauss_f = 1.0 - chi.powi(2) / r * stumpff_c(z);
         let gauss_g = dt - chi.powi(3) / mu.sqrt() * stumpff_s(z);
 
-        let gauss_fdot = (chi * mu.sqrt() / (r * vr)) * (z * stumpff_s(z) - 1.0);
-        let gauss_gdot = 1.0 - (chi.powi(2) / r) * stumpff_c(z);
-
         let position = self.position * gauss_f + self.velocity * gauss_g;
+        let r_new = position.norm();
+
+        let gauss_fdot = (mu.sqrt() / (r_new * r)) * (z * stumpff_s(z) - 1.0) * chi;
+        let gauss_gdot = 1.0 - (chi.powi(2) / r_new) * stumpff_c(z);
+
         let velocity = self.position * gauss_fdot + self.velocity * gauss_gdot;
 
         self.position = position;
@@ -402,27 +412,72 @@ impl SpaceRock {
     }
         
 
-    /// Change the reference plane of the SpaceRock
+    /// Change the reference plane of the SpaceRock. A frame of date (MOD, TOD, CIRS, TIRS or ECLIPDATE)
+    /// is taken at the epoch of the SpaceRock.
     ///
     /// # Arguments
     /// * `reference_plane` - The new reference plane
     pub fn change_reference_plane(&mut self, reference_plane: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let reference_plane = ReferencePlane::from_str_at(reference_plane, &self.epoch)?;
+        self.change_reference_plane_to(&reference_plane)
+    }
+
+    /// Change the reference plane of the SpaceRock. Velocities in TIRS and the ITRS are relative to
+    /// the turning frame, about an axis through the origin.
+    ///
+    /// # Arguments
+    /// * `reference_plane` - The new reference plane, which may be a frame of any date
+    pub fn change_reference_plane_to(&mut self, reference_plane: &ReferencePlane) -> Result<(), Box<dyn std::error::Error>> {
 
-        let reference_plane = ReferencePlane::from_str(reference_plane)?;
-        if reference_plane == self.reference_plane {
+        if *reference_plane == self.reference_plane {
             return Ok(());
         }
 
         let inv = self.reference_plane.get_rotation_matrix().try_inverse().ok_or("Could not invert rotation matrix")?;
         let rot = reference_plane.get_rotation_matrix() * inv;
 
+        // remove the turning of the old frame from the velocity, and add that of the new one
+        let velocity = self.velocity + self.reference_plane.angular_velocity().cross(&self.position);
         self.position = rot * self.position;
-        self.velocity = rot * self.velocity;
-        self.reference_plane = reference_plane;
+        self.velocity = rot * velocity - reference_plane.angular_velocity().cross(&self.position);
+        self.reference_plane = reference_plane.clone();
 
         Ok(())
     }
 
+    /// Load a body from spice at the epoch, reference plane and origin of the SpaceRock. A frame of date
+    /// keeps its own epoch, which may differ from that of the SpaceRock after propagation.
+    pub(crate) fn spice_body(&self, name: &str) -> Result<SpaceRock, Box<dyn std::error::Error>> {
+        self.spice_body_from(name, self.origin.as_str())
+    }
+
+    fn spice_body_from(&self, name: &str, origin: &str) -> Result<SpaceRock, Box<dyn std::error::Error>> {
+        let plane = match self.reference_plane.epoch() {
+            Some(_) => "J2000",
+            None => self.reference_plane.as_str(),
+        };
+        let mut body = SpaceRock::from_spice(name, &self.epoch, plane, origin)?;
+        body.change_reference_plane_to(&self.reference_plane)?;
+        Ok(body)
+    }
+
+    /// The barycentric velocity of the origin of the SpaceRock. The Sun and custom origins come from
+    /// SPICE, which is not thread-safe, so look this up once per epoch before observing rocks in
+    /// parallel. A custom origin is looked up by its name.
+    pub fn origin_velocity(&self) -> Result<Vector3<f64>, Box<dyn std::error::Error>> {
+        match &self.origin {
+            Origin::SSB => Ok(Vector3::zeros()),
+            Origin::SUN => Ok(-self.spice_body("ssb")?.velocity),
+            Origin::Custom { name, .. } => {
+                let (_, found) = spice::bodn2c(name);
+                if !found {
+                    return Err(format!("Cannot find the custom origin {} in SPICE, so its barycentric velocity is unknown", name).into());
+                }
+                Ok(self.spice_body_from(name, "SSB")?.velocity)
+            }
+        }
+    }
+
     /// Change the origin of the SpaceRock
     ///
     /// # Arguments
@@ -451,7 +506,7 @@ impl SpaceRock {
     /// ```
     pub fn to_ssb(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // get the ssb from spice
-        let mut ssb = SpaceRock::from_spice("ssb", &self.epoch, self.reference_plane.as_str(), self.origin.as_str())?;
+        let mut ssb = self.spice_body("ssb")?;
         ssb.set_mass(MU_BARY / GRAVITATIONAL_CONSTANT);
         self.change_origin(&ssb);
         Ok(())
@@ -470,7 +525,7 @@ impl SpaceRock {
     /// ```
     pub fn to_helio(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // get the sun from spice
-        let sun = SpaceRock::from_spice("sun", &self.epoch, self.reference_plane.as_str(), self.origin.as_str())?;
+        let sun = self.spice_body("sun")?;
         self.change_origin(&sun);
         Ok(())
     }
@@ -544,6 +599,75 @@ impl SpaceRock {
         self.properties.as_mut().unwrap().gslope = Some(gslope);
     }
 
+    /// Set the parameters of the H,G1,G2 phase function.
+    pub fn set_g1g2(&mut self, g1: f64, g2: f64) {
+        if self.properties.is_none() {
+            self.properties = Some(Properties::default());
+        }
+        self.properties.as_mut().unwrap().g1 = Some(g1);
+        self.properties.as_mut().unwrap().g2 = Some(g2);
+    }
+
+    /// Set the parameter of the H,G12* phase function.
+    pub fn set_g12(&mut self, g12: f64) {
+        if self.properties.is_none() {
+            self.properties = Some(Properties::default());
+        }
+        self.properties.as_mut().unwrap().g12 = Some(g12);
+    }
+
+    /// Set the linear phase coefficient (magnitudes per degree).
+    pub fn set_phase_coefficient(&mut self, phase_coefficient: f64) {
+        if self.properties.is_none() {
+            self.properties = Some(Properties::default());
+        }
+        self.properties.as_mut().unwrap().phase_coefficient = Some(phase_coefficient);
+    }
+
+    /// Set the total magnitude parameters of a comet.
+    pub fn set_total_magnitude(&mut self, m1: f64, k1: f64) {
+        if self.properties.is_none() {
+            self.properties = Some(Properties::default());
+        }
+        self.properties.as_mut().unwrap().m1 = Some(m1);
+        self.properties.as_mut().unwrap().k1 = Some(k1);
+    }
+
+    /// Set the nuclear magnitude parameters of a comet.
+    pub fn set_nuclear_magnitude(&mut self, m2: f64, k2: f64) {
+        if self.properties.is_none() {
+            self.properties = Some(Properties::default());
+        }
+        self.properties.as_mut().unwrap().m2 = Some(m2);
+        self.properties.as_mut().unwrap().k2 = Some(k2);
+    }
+
+    /// Set the phase function used for magnitudes, overriding the one chosen from the parameters.
+    pub fn set_phase_function(&mut self, phase_function: PhaseFunction) {
+        if self.properties.is_none() {
+            self.properties = Some(Properties::default());
+        }
+        self.properties.as_mut().unwrap().phase_function = Some(phase_function);
+    }
+
+    /// Set the colours used to convert magnitudes between bands.
+    pub fn set_colors(&mut self, colors: Colors) {
+        if self.properties.is_none() {
+            self.properties = Some(Properties::default());
+        }
+        self.properties.as_mut().unwrap().colors = Some(colors);
+    }
+
+    /// The colours of the SpaceRock, or the solar colours if none are set.
+    pub fn colors(&self) -> Colors {
+        self.properties.as_ref().and_then(|p| p.colors).unwrap_or_default()
+    }
+
+    /// The phase function used for magnitudes, if the SpaceRock has one.
+    pub fn phase_function(&self) -> Option<PhaseFunction> {
+        self.properties.as_ref().and_then(PhaseFunction::infer)
+    }
+
     pub fn set_radius(&mut self, radius: f64) {
         if self.properties.is_none() {
             self.properties = Some(Properties::default());
@@ -662,6 +786,144 @@ impl SpaceRock {
         calc_conic_anomaly_from_true_anomaly(self.e(), self.true_anomaly()).expect("Invalid eccentricity")
     }
 
+    /// Calculate the minimum orbit intersection distance (MOID) between the osculating orbits of this SpaceRock and another.
+    ///
+    /// # Arguments
+    /// * `other` - The SpaceRock to compare against. It must share an origin and reference plane with this one.
+    ///
+    /// # Returns
+    /// * A Moid object holding the distance (au) and the true anomalies on both orbits where it occurs
+    ///
+    /// # Example
+    /// ```
+    /// use spacerocks::SpaceRock;
+    /// use spacerocks::Time;
+    ///
+    /// let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+    /// let apophis = SpaceRock::from_kepler("Apophis", 0.746, 0.191, 0.058, 2.206, 3.561, 1.0, epoch.clone(), "ECLIPJ2000", "SUN").unwrap();
+    /// let earth = SpaceRock::from_kepler("Earth", 0.983, 0.017, 0.0, 1.993, 3.052, 0.5, epoch, "ECLIPJ2000", "SUN").unwrap();
+    /// let moid = apophis.moid(&earth).unwrap();
+    /// ```
+    pub fn moid(&self, other: &SpaceRock) -> Result<Moid, Box<dyn std::error::Error>> {
+        calc_moid(self, other)
+    }
+
+    /// Calculate the MOID between this SpaceRock and the orbit of the Earth. The Earth is loaded from spice
+    /// at the epoch, reference plane and origin of the SpaceRock, so a kernel must be loaded before calling this method.
+    ///
+    /// # Returns
+    /// * A Moid object. `true_anomaly_1` refers to this SpaceRock, `true_anomaly_2` to the Earth
+    pub fn earth_moid(&self) -> Result<Moid, Box<dyn std::error::Error>> {
+        let earth = self.spice_body("earth")?;
+        calc_moid(self, &earth)
+    }
+
+    /// Calculate the intrinsic collision probability and mean impact velocity between the orbits of this SpaceRock
+    /// and another, assuming uniformly precessing nodes and arguments of pericenter.
+    ///
+    /// # Arguments
+    /// * `other` - The other SpaceRock. It must share an origin and reference plane with this one.
+    ///
+    /// # Returns
+    /// * A CollisionProbability object, with the intrinsic probability in km^-2 yr^-1 and the impact velocity in km/s
+    pub fn collision_probability(&self, other: &SpaceRock) -> Result<CollisionProbability, Box<dyn std::error::Error>> {
+        calc_collision_probability(self, other)
+    }
+
+    /// Calculate the Tisserand parameter with respect to a planet, using the planet's mean semimajor axis
+    /// and the inclination of the SpaceRock to the ecliptic.
+    ///
+    /// # Arguments
+    /// * `planet` - The name of the planet, e.g. "jupiter" or "jupiter barycenter"
+    ///
+    /// # Returns
+    /// * The Tisserand parameter
+    ///
+    /// # Example
+    /// ```
+    /// use spacerocks::SpaceRock;
+    /// use spacerocks::Time;
+    ///
+    /// let epoch = Time::now();
+    /// let rock = SpaceRock::from_kepler("rock", 2.5, 0.1, 0.2, 0.0, 0.0, 0.0, epoch, "ECLIPJ2000", "SUN").unwrap();
+    /// let tj = rock.tisserand("jupiter").unwrap();
+    /// ```
+    pub fn tisserand(&self, planet: &str) -> Result<f64, Box<dyn std::error::Error>> {
+        let name = planet.to_lowercase();
+        let name = name.trim_end_matches(" barycenter");
+        let a_planet = PLANET_SEMIMAJOR_AXES.get(name).ok_or(format!("Unknown planet {}", planet))?;
+
+        let mut rock = self.clone();
+        rock.change_reference_plane("ECLIPJ2000")?;
+        Ok(rock.tisserand_from_semimajor_axis(*a_planet))
+    }
+
+    /// Calculate the Tisserand parameter with respect to a body on a circular orbit in the reference plane
+    /// of the SpaceRock. This is valid for bound and unbound orbits alike.
+    ///
+    /// # Arguments
+    /// * `a_planet` - The semimajor axis of the perturbing body (au)
+    ///
+    /// # Returns
+    /// * The Tisserand parameter
+    pub fn tisserand_from_semimajor_axis(&self, a_planet: f64) -> f64 {
+        // written in terms of 1/a and the semi-latus rectum, so that parabolic orbits are handled
+        let inverse_a = -2.0 * self.specific_energy() / self.origin.mu();
+        let p = self.h().powi(2) / self.origin.mu();
+        a_planet * inverse_a + 2.0 * self.inc().cos() * (p / a_planet).sqrt()
+    }
+
+    /// Assign the SpaceRock a dynamical class (near-Earth, main belt, Jupiter-family comet, etc.)
+    /// from its ecliptic elements and its Tisserand parameter with respect to Jupiter.
+    ///
+    /// # Returns
+    /// * The DynamicalClass
+    pub fn dynamical_class(&self) -> Result<DynamicalClass, Box<dyn std::error::Error>> {
+        calc_dynamical_class(self)
+    }
+
+    /// Calculate when the SpaceRock rises above an altitude, transits and sets, seen from a ground
+    /// observatory in the day after a time.
+    ///
+    /// # Arguments
+    /// * `observatory` - The ground Observatory
+    /// * `start` - The start of the day searched
+    /// * `altitude` - The geometric altitude of rising and setting (radians)
+    ///
+    /// # Returns
+    /// * The RiseTransitSet
+    pub fn rise_transit_set(&self, observatory: &Observatory, start: &Time, altitude: f64) -> Result<RiseTransitSet, Box<dyn std::error::Error>> {
+        calc_rise_transit_set(self, observatory, start, altitude)
+    }
+
+    /// Calculate the windows in which the SpaceRock can be observed from a ground observatory.
+    ///
+    /// # Arguments
+    /// * `observatory` - The ground Observatory
+    /// * `start` - The start of the search
+    /// * `end` - The end of the search
+    /// * `constraints` - The limits on the altitude, the Sun and the Moon
+    ///
+    /// # Returns
+    /// * The start and end of each window
+    pub fn observable_windows(&self, observatory: &Observatory, start: &Time, end: &Time, constraints: &VisibilityConstraints) -> Result<Vec<(Time, Time)>, Box<dyn std::error::Error>> {
+        calc_observable_windows(self, observatory, start, end, constraints)
+    }
+
+    /// Calculate the windows in which the SpaceRock can be observed in the night after a time,
+    /// which is usually around local noon. The search covers the following 24 hours.
+    ///
+    /// # Arguments
+    /// * `observatory` - The ground Observatory
+    /// * `epoch` - The start of the search
+    /// * `constraints` - The limits on the altitude, the Sun and the Moon
+    ///
+    /// # Returns
+    /// * The start and end of each window
+    pub fn observable_tonight(&self, observatory: &Observatory, epoch: &Time, constraints: &VisibilityConstraints) -> Result<Vec<(Time, Time)>, Box<dyn std::error::Error>> {
+        calc_observable_windows(self, observatory, epoch, &(epoch.clone() + 1.0), constraints)
+    }
+
     // calculate the osculating elements and return a KeplerOrbit object. This is more expensive than the other 
     // individual methods, but cheaper if you need multiple elements
     // pub fn calculate_orbit(&self) -> KeplerOrbit {
@@ -669,6 +931,59 @@ impl SpaceRock {
     // }
 
     pub fn observe(&mut self, observer: &Observer) -> Result<Observation, Box<dyn std::error::Error>> {
+        self.observe_with(observer, &CorrectionLevel::Astrometric)
+    }
+
+    /// Observe the SpaceRock, with the corrections of a given level. Astrometric positions are
+    /// corrected for light travel time only. Apparent positions are also corrected for light
+    /// deflection and aberration, and need the Sun and planets from SPICE. Observed positions are
+    /// also refracted, and need an observatory on the ground. The rates, range and range rate
+    /// are the light-time corrected ones at every level.
+    ///
+    /// # Arguments
+    /// * `observer` - The Observer, at the epoch and in the reference plane of the SpaceRock
+    /// * `correction` - The CorrectionLevel
+    ///
+    /// # Returns
+    /// * The Observation
+    pub fn observe_with(&mut self, observer: &Observer, correction: &CorrectionLevel) -> Result<Observation, Box<dyn std::error::Error>> {
+        let rock = self.clone();
+        let origin_velocity = self.origin_velocity()?;
+        self.observe_with_propagator(observer, correction, &origin_velocity, |epoch| rock.analytic_at(epoch))
+    }
+
+    /// Observe the SpaceRock, with its magnitude in a given band. Magnitudes from the phase
+    /// functions are in V, and are converted with the colours of the SpaceRock.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer
+    /// * `correction` - The level of corrections to apply
+    /// * `band` - The band of the magnitude
+    ///
+    /// # Returns
+    ///
+    /// * The observation
+    pub fn observe_in_band(&mut self, observer: &Observer, correction: &CorrectionLevel, band: &Band) -> Result<Observation, Box<dyn std::error::Error>> {
+        let observation = self.observe_with(observer, correction)?;
+        observation.to_band(band, &self.colors())
+    }
+
+    /// Observe the SpaceRock, moving it back to the epoch at which the light was emitted with a
+    /// given propagator. Use this for perturbed targets, e.g. with a Simulation or an ephemeris.
+    ///
+    /// # Arguments
+    /// * `observer` - The Observer, at the epoch and in the reference plane of the SpaceRock
+    /// * `correction` - The CorrectionLevel
+    /// * `origin_velocity` - The barycentric velocity of the origin at the epoch (au/day), from `origin_velocity`
+    /// * `propagate` - Gives the state of the SpaceRock at an epoch, in its origin and reference plane
+    ///
+    /// # Returns
+    /// * The Observation
+    pub fn observe_with_propagator<F>(&mut self, observer: &Observer, correction: &CorrectionLevel, origin_velocity: &Vector3<f64>, propagate: F) -> Result<Observation, Box<dyn std::error::Error>>
+    where
+        F: FnMut(&Time) -> Result<SpaceRock, Box<dyn std::error::Error>>,
+    {
 
         // self.change_reference_plane("J2000")?;
 
@@ -678,18 +993,32 @@ impl SpaceRock {
             return Err("Observer and SpaceRock have different epochs".into());
         }
 
-        if self.reference_plane.as_str() != observer.reference_plane() {
+        if self.reference_plane != observer.spacerock.reference_plane {
             return Err("Observer and SpaceRock have different reference planes".into());
         }
+
+        if self.origin != observer.spacerock.origin {
+            return Err("Observer and SpaceRock have different origins".into());
+        }
+
         // Calculate the topocentric state, correct for light travel time
-        let cr = correct_for_ltt(&self, observer);
+        let (cr, _) = solve_for_light_time(self, observer, origin_velocity, propagate, 1e-12, 20)?;
+
+        let direction = match correction {
+            CorrectionLevel::Astrometric => cr.position,
+            CorrectionLevel::Apparent => calc_apparent_direction(&cr.position, observer)?,
+            CorrectionLevel::Observed(atmosphere) => {
+                let apparent = calc_apparent_direction(&cr.position, observer)?;
+                calc_observed_direction(&apparent, observer, atmosphere)?
+            }
+        };
 
         // Calaculate the ra, and dec
-        let mut ra = cr.position.y.atan2(cr.position.x);
+        let mut ra = direction.y.atan2(direction.x);
         if ra < 0.0 {
             ra += 2.0 * std::f64::consts::PI;
         }
-        let dec = (cr.position.z / cr.position.norm()).asin();
+        let dec = (direction.z / direction.norm()).asin();
 
         // Calculate the ra and dec rates
         let xi = cr.position.x.powi(2) + cr.position.y.powi(2);
@@ -705,42 +1034,20 @@ impl SpaceRock {
 
         // if self has properties, calculate the magnitude
         let mut mag = None;
-        
         if let Some(properties) = &self.properties {
-
-            if let Some(absolute_magnitude) = properties.absolute_magnitude {
-                
-                let gslope = properties.gslope.unwrap();
-
+            if let Some(phase_function) = PhaseFunction::infer(properties) {
                 let delta = cr.position.norm();
                 let sun_dist = (cr.position + observer.position()).norm();
                 let earth_dist = observer.position().norm();
-                let q = (sun_dist.powi(2) + delta.powi(2) - earth_dist) / (2.0 * sun_dist * delta);
-                // let mut beta = 0.0;
-                // match q {
-                //     q if q <= -1.0 => beta = std::f64::consts::PI,
-                //     q if q >= 1.0 => beta = 0.0,
-                //     _ => beta = q.acos(),
-                // };
-                let beta = match q {
-                    q if q <= -1.0 => std::f64::consts::PI,
-                    q if q >= 1.0 => 0.0,
-                    _ => q.acos(),
-                };
-
-                let psi_1 = (-3.332 * ((beta / 2.0).tan()).powf(0.631)).exp();
-                let psi_2 = (-1.862 * ((beta / 2.0).tan()).powf(1.218)).exp();
-                mag = Some(absolute_magnitude + 5.0 * (sun_dist * delta).log10());
-                if psi_1 == 0.0 && psi_2 == 0.0 {
-                    mag = mag;
-                } else {
-                    let mm = mag.unwrap() - 2.5 * ((1.0 - gslope) * psi_1 + gslope * psi_2).log10();
-                    mag = Some(mm);
-                }
+                let beta = calc_phase_angle(sun_dist, delta, earth_dist);
+                mag = Some(calc_magnitude(properties, &phase_function, sun_dist, delta, beta)?);
             }
         }
 
-        let observation = Observation::from_complete(self.epoch.clone(), ra, dec, ra_rate, dec_rate, rho, rho_rate, mag, observer.clone());
+        let mut observation = Observation::from_complete(self.epoch.clone(), ra, dec, ra_rate, dec_rate, rho, rho_rate, mag, observer.clone());
+        if mag.is_some() {
+            observation.set_band(Band::V);
+        }
         Ok(observation)
     }
 
@@ -208,8 +208,19 @@ impl SpiceKernel {
             return Ok(());
         }
         
+        // keep the time system's leap seconds in step with SPICE, parsing them before SPICE
+        // loads the file so that a bad file leaves neither loaded
+        let leap_seconds = if path.ends_with(".tls") {
+            Some(crate::time::LeapSecondTable::from_lsk(path).map_err(|e| e.to_string())?)
+        } else {
+            None
+        };
+
         println!("Loading kernel: {}", path);
         spice::furnsh(path);
+        if let Some(table) = leap_seconds {
+            crate::time::set_leap_second_table(table);
+        }
         self.loaded_files.push(path.to_string());
         Ok(())
     }
@@ -0,0 +1,106 @@
+use crate::survey::Exposure;
+
+/// How a survey turns the sources in its exposures into detections.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectionModel {
+    /// The fraction of the footprint covered by live pixels
+    pub fill_factor: f64,
+    /// The efficiency for bright sources
+    pub peak_efficiency: f64,
+    /// The width of the fall in efficiency about the limiting magnitude (magnitudes)
+    pub width: f64,
+    /// The slowest rate of motion that is detected (radians/day)
+    pub min_rate: f64,
+    /// The fastest rate of motion that is detected (radians/day)
+    pub max_rate: f64,
+    /// The systematic astrometric error, added in quadrature to the centroiding error (radians)
+    pub astrometric_floor: f64,
+    /// Whether trailed sources lose signal to noise and measured flux
+    pub trailing_losses: bool,
+}
+
+impl Default for DetectionModel {
+    fn default() -> Self {
+        DetectionModel {
+            fill_factor: 0.9,
+            peak_efficiency: 1.0,
+            width: 0.1,
+            min_rate: 0.0,
+            max_rate: f64::INFINITY,
+            astrometric_floor: (0.01_f64 / 3600.0).to_radians(),
+            trailing_losses: true,
+        }
+    }
+}
+
+/// Calculate the losses of a source trailed across an exposure, from the fits of Veres & Chesley
+/// (2017). The first is the loss in the measured magnitude of the source, and the second the loss
+/// in its detectability by a point-source filter.
+///
+/// # Arguments
+///
+/// * `rate` - The rate of motion (radians/day)
+/// * `exposure_time` - The length of the exposure (seconds)
+/// * `seeing` - The FWHM of the point spread function (radians)
+///
+/// # Returns
+///
+/// * The trailing loss and the detection loss (magnitudes)
+pub fn calc_trailing_losses(rate: f64, exposure_time: f64, seeing: f64) -> (f64, f64) {
+    let x = rate * exposure_time / 86400.0 / seeing;
+    let trailing = 1.25 * (1.0 + 0.761 * x.powi(2) / (1.0 + 1.162 * x)).log10();
+    let detection = 1.25 * (1.0 + 0.420 * x.powi(2) / (1.0 + 0.003 * x)).log10();
+    (trailing, detection)
+}
+
+impl DetectionModel {
+
+    /// Calculate the signal to noise ratio of a source in an exposure.
+    ///
+    /// # Arguments
+    ///
+    /// * `mag` - The magnitude of the source, in the band of the exposure
+    /// * `rate` - The rate of motion of the source (radians/day)
+    /// * `exposure` - The exposure
+    ///
+    /// # Returns
+    ///
+    /// * The signal to noise ratio, which is 5 at the limiting magnitude
+    pub fn signal_to_noise(&self, mag: f64, rate: f64, exposure: &Exposure) -> f64 {
+        let loss = if self.trailing_losses { calc_trailing_losses(rate, exposure.exposure_time, exposure.seeing).1 } else { 0.0 };
+        5.0 * 10.0_f64.powf(-0.4 * (mag + loss - exposure.limiting_magnitude))
+    }
+
+    /// Calculate the probability that a source in the footprint of an exposure is detected.
+    ///
+    /// # Arguments
+    ///
+    /// * `mag` - The magnitude of the source, in the band of the exposure
+    /// * `rate` - The rate of motion of the source (radians/day)
+    /// * `exposure` - The exposure
+    ///
+    /// # Returns
+    ///
+    /// * The probability of detection
+    pub fn efficiency(&self, mag: f64, rate: f64, exposure: &Exposure) -> f64 {
+        if rate < self.min_rate || rate > self.max_rate {
+            return 0.0;
+        }
+        let loss = if self.trailing_losses { calc_trailing_losses(rate, exposure.exposure_time, exposure.seeing).1 } else { 0.0 };
+        self.fill_factor * self.peak_efficiency / (1.0 + ((mag + loss - exposure.limiting_magnitude) / self.width).exp())
+    }
+
+    /// Calculate the astrometric error of a source, per coordinate.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_to_noise` - The signal to noise ratio of the source
+    /// * `exposure` - The exposure
+    ///
+    /// # Returns
+    ///
+    /// * The standard deviation of each coordinate (radians)
+    pub fn astrometric_error(&self, signal_to_noise: f64, exposure: &Exposure) -> f64 {
+        self.astrometric_floor.hypot(exposure.seeing / signal_to_noise)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{Band, Observatory, Time};
+use crate::observing::Field;
+
+/// A single exposure of a survey.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exposure {
+    /// The middle of the exposure
+    pub epoch: Time,
+    /// The footprint of the exposure on the sky, in J2000
+    pub field: Field,
+    pub band: Band,
+    /// The magnitude of a point source detected at 5 sigma
+    pub limiting_magnitude: f64,
+    /// The FWHM of the point spread function (radians)
+    pub seeing: f64,
+    /// The length of the exposure (seconds)
+    pub exposure_time: f64,
+    pub observatory: Observatory,
+}
+
+impl Exposure {
+
+    /// Create an exposure from an MPC observatory code.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The middle of the exposure
+    /// * `field` - The footprint of the exposure, in J2000
+    /// * `band` - The band of the exposure
+    /// * `limiting_magnitude` - The 5 sigma limiting magnitude for a point source
+    /// * `seeing` - The FWHM of the point spread function (radians)
+    /// * `exposure_time` - The length of the exposure (seconds)
+    /// * `obscode` - The MPC code of the observatory
+    ///
+    /// # Returns
+    ///
+    /// * The Exposure
+    pub fn new(epoch: Time, field: Field, band: Band, limiting_magnitude: f64, seeing: f64, exposure_time: f64, obscode: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let observatory = Observatory::from_obscode(obscode)?;
+        Ok(Exposure { epoch, field, band, limiting_magnitude, seeing, exposure_time, observatory })
+    }
+}
@@ -0,0 +1,8 @@
+pub mod exposure;
+    pub use exposure::Exposure;
+
+pub mod detection_model;
+    pub use detection_model::{DetectionModel, calc_trailing_losses};
+
+pub mod simulate_survey;
+    pub use simulate_survey::{Detection, simulate_survey, simulate_survey_from_ephemerides};
@@ -0,0 +1,156 @@
+use std::f64::consts::PI;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+
+use crate::{Ephemeris, Observation, Observer, ReferencePlane, SpaceRock};
+use crate::observing::{CorrectionLevel, calc_rocks_in_field};
+use crate::observing::footprint::PREFILTER_MARGIN;
+use crate::photometry::{Band, Colors, calc_band_conversion};
+use crate::survey::{DetectionModel, Exposure, calc_trailing_losses};
+use crate::utils::standard_normal;
+
+/// A simulated detection of a SpaceRock in an exposure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    /// The index of the detected rock
+    pub rock: usize,
+    /// The index of the exposure
+    pub exposure: usize,
+    /// The measured astrometry and photometry, with noise
+    pub observation: Observation,
+}
+
+/// Simulate the detections a survey would record of a population of SpaceRocks. Each rock in the
+/// footprint of an exposure is detected with the efficiency of the detection model at its magnitude
+/// and rate of motion, and its measured position and magnitude are perturbed by their errors.
+///
+/// Every rock is propagated, and the observatory looked up in SPICE, at every exposure. For large
+/// populations or long surveys use `simulate_survey_from_ephemerides`.
+///
+/// # Arguments
+///
+/// * `rocks` - The SpaceRocks, relative to the SSB or the Sun, with absolute magnitudes
+/// * `exposures` - The exposures of the survey
+/// * `model` - The detection model
+/// * `seed` - The seed of the random number generator
+///
+/// # Returns
+///
+/// * The detections, in the order of the exposures
+pub fn simulate_survey(rocks: &[SpaceRock], exposures: &[Exposure], model: &DetectionModel, seed: Option<u64>) -> Result<Vec<Detection>, Box<dyn std::error::Error>> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut detections = Vec::new();
+    for (exposure_index, exposure) in exposures.iter().enumerate() {
+        let in_field = calc_rocks_in_field(rocks, &exposure.field, &exposure.observatory, &exposure.epoch)?;
+
+        for (rock_index, observation) in in_field {
+            let rock = &rocks[rock_index];
+            if let Some(observation) = detect(&mut rng, model, exposure, &rock.name, &rock.colors(), &observation)? {
+                detections.push(Detection { rock: rock_index, exposure: exposure_index, observation });
+            }
+        }
+    }
+    Ok(detections)
+}
+
+/// Simulate the detections a survey would record of a population, from precomputed ephemerides of
+/// the rocks and of the observatory. This gives the same detections as `simulate_survey`, but each
+/// rock costs one evaluation of its ephemeris per exposure, and the observatory is not looked up
+/// in SPICE. With ephemerides relative to the SSB no kernels are needed at all; relative to the
+/// Sun, the velocity of the Sun is looked up once per exposure. The cost still grows as the number
+/// of rocks times the number of exposures.
+///
+/// # Arguments
+///
+/// * `rocks` - The ephemerides of the rocks, with absolute magnitudes, in J2000
+/// * `observer` - The ephemeris of the observatory of every exposure, named as the observatory, in the reference plane and origin of the rocks
+/// * `exposures` - The exposures of the survey, within the spans of the ephemerides
+/// * `model` - The detection model
+/// * `seed` - The seed of the random number generator
+///
+/// # Returns
+///
+/// * The detections, in the order of the exposures
+pub fn simulate_survey_from_ephemerides(rocks: &[Ephemeris], observer: &Ephemeris, exposures: &[Exposure], model: &DetectionModel, seed: Option<u64>) -> Result<Vec<Detection>, Box<dyn std::error::Error>> {
+    if observer.reference_plane != ReferencePlane::J2000 {
+        return Err("The ephemeris of the observer must be in J2000".into());
+    }
+    if rocks.iter().any(|rock| rock.reference_plane != observer.reference_plane || rock.origin != observer.origin) {
+        return Err("The ephemerides of the rocks and the observer have different reference planes or origins".into());
+    }
+    if let Some(exposure) = exposures.iter().find(|exposure| exposure.observatory.name() != observer.name) {
+        return Err(format!("An exposure was taken from {}, but the ephemeris of the observer is of {}", exposure.observatory.name(), observer.name).into());
+    }
+
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut detections = Vec::new();
+    for (exposure_index, exposure) in exposures.iter().enumerate() {
+        let epoch = exposure.epoch.tdb().jd();
+        let observer = Observer { spacerock: observer.at(&exposure.epoch)?, observatory: exposure.observatory.clone() };
+        let origin_velocity = observer.spacerock.origin_velocity()?;
+        let center = exposure.field.center();
+        let limit = exposure.field.bounding_radius() + PREFILTER_MARGIN;
+
+        let found: Vec<Result<Option<(usize, Observation)>, String>> = rocks.par_iter().enumerate().map(|(index, rock)| {
+            let position = rock.position(epoch).map_err(|e| e.to_string())?;
+            if (position - observer.position()).angle(&center) > limit {
+                return Ok(None);
+            }
+            let mut target = rock.at(&exposure.epoch).map_err(|e| e.to_string())?;
+            let observation = target.observe_with_propagator(&observer, &CorrectionLevel::Astrometric, &origin_velocity, |epoch| rock.at(epoch)).map_err(|e| e.to_string())?;
+            if exposure.field.contains(observation.ra(), observation.dec()) {
+                Ok(Some((index, observation)))
+            } else {
+                Ok(None)
+            }
+        }).collect();
+
+        for result in found {
+            let Some((rock_index, observation)) = result? else {
+                continue;
+            };
+            let rock = &rocks[rock_index];
+            let colors = rock.properties.as_ref().and_then(|p| p.colors).unwrap_or_default();
+            if let Some(observation) = detect(&mut rng, model, exposure, &rock.name, &colors, &observation)? {
+                detections.push(Detection { rock: rock_index, exposure: exposure_index, observation });
+            }
+        }
+    }
+    Ok(detections)
+}
+
+/// Decide whether a rock in the field of an exposure is detected, and if so measure it with noise.
+fn detect(rng: &mut StdRng, model: &DetectionModel, exposure: &Exposure, name: &str, colors: &Colors, observation: &Observation) -> Result<Option<Observation>, Box<dyn std::error::Error>> {
+    let mag = match observation.mag() {
+        Some(mag) => calc_band_conversion(mag, &Band::V, &exposure.band, colors),
+        None => return Err(format!("Cannot simulate detections of {}, which has no magnitude", name).into()),
+    };
+    let rate = observation.proper_motion().unwrap_or(0.0);
+
+    if rng.gen::<f64>() >= model.efficiency(mag, rate, exposure) {
+        return Ok(None);
+    }
+
+    // trailed sources are measured fainter, and all are measured with noise
+    let snr = model.signal_to_noise(mag, rate, exposure);
+    let trailing = if model.trailing_losses { calc_trailing_losses(rate, exposure.exposure_time, exposure.seeing).0 } else { 0.0 };
+    let measured_mag = mag + trailing + 1.0857 / snr * standard_normal(rng);
+
+    let sigma = model.astrometric_error(snr, exposure);
+    let dec = observation.dec() + sigma * standard_normal(rng);
+    let ra = (observation.ra() + sigma * standard_normal(rng) / observation.dec().cos()).rem_euclid(2.0 * PI);
+
+    let mut detected = Observation::from_astrometry(observation.epoch.clone(), ra, dec, Some(measured_mag), observation.observer.clone());
+    detected.set_band(exposure.band);
+    Ok(Some(detected))
+}
@@ -1,35 +1,100 @@
 use std::collections::HashMap;
 use lazy_static::lazy_static;
-use crate::time::leapseconds::LEAP_SECONDS;
+use crate::time::leapseconds::leap_seconds_at;
+use crate::time::eop::get_delta_ut1;
+use crate::time::fairhead_bretagnon::calc_tdb_minus_tt;
 use chrono::{Utc, DateTime};
-use chrono::TimeZone;
+use crate::errors::TimeError;
+
+/// Normalize a two-part Julian Date, so that the first part is a whole number of days
+/// and the second part is the fraction of a day, in [0, 1).
+///
+/// # Arguments
+///
+/// * `day` - The first part of the Julian Date
+/// * `fraction` - The second part of the Julian Date
+///
+/// # Returns
+///
+/// * The normalized `(day, fraction)`
+pub fn normalize_two_part(day: f64, fraction: f64) -> (f64, f64) {
+    let whole = day.floor();
+    let fraction = (day - whole) + fraction;
+    let carry = fraction.floor();
+    let fraction = fraction - carry;
+    // rounding can leave the fraction at exactly 1.0 when it was a hair below zero
+    if fraction >= 1.0 {
+        return (whole + carry + 1.0, 0.0);
+    }
+    (whole + carry, fraction)
+}
+
+/// Add an offset in seconds to a two-part Julian Date. The offset is applied to the fraction,
+/// so no precision is lost to the size of the day number.
+fn add_seconds(day: f64, fraction: f64, seconds: f64) -> (f64, f64) {
+    normalize_two_part(day, fraction + seconds / 86400.0)
+}
+
+/// The difference TT - TAI, in seconds.
+pub const TT_MINUS_TAI: f64 = 32.184;
+
+/// The difference GPS - TAI, in seconds.
+pub const GPS_MINUS_TAI: f64 = -19.0;
+
+/// The epoch 1977 January 1.0 TAI, where TT, TCG and TCB coincide (TDB is offset by TDB0), as a two-part Julian Date.
+const T77: (f64, f64) = (2443144.5, 0.0003725);
+
+/// The rate of TT with respect to TCG (IAU 2000 Resolution B1.9).
+pub const L_G: f64 = 6.969290134e-10;
+
+/// The rate of TDB with respect to TCB (IAU 2006 Resolution B3).
+pub const L_B: f64 = 1.550519768e-8;
+
+/// TDB - TCB at T77, in seconds (IAU 2006 Resolution B3).
+pub const TDB0: f64 = -6.55e-5;
+
+/// The difference TDB - TT at the geocenter, in seconds, from the Fairhead & Bretagnon series.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the TT (or TDB) Julian Date
+/// * `fraction` - The second part of the TT (or TDB) Julian Date
+///
+/// # Returns
+///
+/// * TDB - TT in seconds
+pub fn tdb_minus_tt(day: f64, fraction: f64) -> f64 {
+    calc_tdb_minus_tt(day, fraction, 0.0, 0.0, 0.0, 0.0)
+}
 
 /// Converts UTC (Universal Time Coordinated) to TAI (International Atomic Time)
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in UTC Julian Date
+/// * `day` - The first part of the epoch in UTC Julian Date
+/// * `fraction` - The second part of the epoch in UTC Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in TAI Julian Date
-pub fn utc_to_tai(epoch: f64) -> f64 {
-    let leapseconds = get_leap_seconds_at_epoch(epoch);
-    epoch + leapseconds / 86400.0
+/// * The epoch in TAI Julian Date, as `(day, fraction)`
+pub fn utc_to_tai(day: f64, fraction: f64) -> (f64, f64) {
+    let leapseconds = get_leap_seconds_at_epoch(day + fraction);
+    add_seconds(day, fraction, leapseconds)
 }
 
 /// Converts TAI (International Atomic Time) to UTC (Universal Time Coordinated)
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in TAI Julian Date
+/// * `day` - The first part of the epoch in TAI Julian Date
+/// * `fraction` - The second part of the epoch in TAI Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in UTC Julian Date
-pub fn tai_to_utc(epoch: f64) -> f64 {
-    let leapseconds = get_leap_seconds_at_epoch(epoch);
-    epoch - leapseconds / 86400.0
+/// * The epoch in UTC Julian Date, as `(day, fraction)`
+pub fn tai_to_utc(day: f64, fraction: f64) -> (f64, f64) {
+    let leapseconds = get_leap_seconds_at_epoch(day + fraction);
+    add_seconds(day, fraction, -leapseconds)
 }
 
 /// Converts TAI (International Atomic Time) to TT (Terrestrial Time)
@@ -37,13 +102,14 @@ pub fn tai_to_utc(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in TAI Julian Date
+/// * `day` - The first part of the epoch in TAI Julian Date
+/// * `fraction` - The second part of the epoch in TAI Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in TT Julian Date
-pub fn tai_to_tt(epoch: f64) -> f64 {
-    epoch + 32.184 / 86400.0
+/// * The epoch in TT Julian Date, as `(day, fraction)`
+pub fn tai_to_tt(day: f64, fraction: f64) -> (f64, f64) {
+    add_seconds(day, fraction, TT_MINUS_TAI)
 }
 
 /// Converts TT (Terrestrial Time) to TAI (International Atomic Time)
@@ -51,13 +117,14 @@ pub fn tai_to_tt(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in TT Julian Date
+/// * `day` - The first part of the epoch in TT Julian Date
+/// * `fraction` - The second part of the epoch in TT Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in TAI Julian Date
-pub fn tt_to_tai(epoch: f64) -> f64 {
-    epoch - 32.184 / 86400.0
+/// * The epoch in TAI Julian Date, as `(day, fraction)`
+pub fn tt_to_tai(day: f64, fraction: f64) -> (f64, f64) {
+    add_seconds(day, fraction, -TT_MINUS_TAI)
 }
 
 /// Converts TT (Terrestrial Time) to TDB (Barycentric Dynamical Time)
@@ -65,14 +132,14 @@ pub fn tt_to_tai(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in TT Julian Date
+/// * `day` - The first part of the epoch in TT Julian Date
+/// * `fraction` - The second part of the epoch in TT Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in TDB Julian Date
-pub fn tt_to_tdb(epoch: f64) -> f64 {
-    let g = (357.53 + 0.9856003 * (epoch - 2451545.0)).to_radians();
-    epoch + (0.001658 * g.sin() + 0.000014 * (2.0 * g).sin()) / 86400.0
+/// * The epoch in TDB Julian Date, as `(day, fraction)`
+pub fn tt_to_tdb(day: f64, fraction: f64) -> (f64, f64) {
+    add_seconds(day, fraction, tdb_minus_tt(day, fraction))
 }
 
 /// Converts TDB (Barycentric Dynamical Time) to TT (Terrestrial Time)
@@ -80,14 +147,14 @@ pub fn tt_to_tdb(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in TDB Julian Date
+/// * `day` - The first part of the epoch in TDB Julian Date
+/// * `fraction` - The second part of the epoch in TDB Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in TT Julian Date
-pub fn tdb_to_tt(epoch: f64) -> f64 {
-    let g = (357.53 + 0.9856003 * (epoch - 2451545.0)).to_radians();
-    epoch - (0.001658 * g.sin() + 0.000014 * (2.0 * g).sin()) / 86400.0
+/// * The epoch in TT Julian Date, as `(day, fraction)`
+pub fn tdb_to_tt(day: f64, fraction: f64) -> (f64, f64) {
+    add_seconds(day, fraction, -tdb_minus_tt(day, fraction))
 }
 
 /// Converts UTC (Universal Time Coordinated) to TDB (Barycentric Dynamical Time)
@@ -95,15 +162,16 @@ pub fn tdb_to_tt(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in UTC Julian Date
+/// * `day` - The first part of the epoch in UTC Julian Date
+/// * `fraction` - The second part of the epoch in UTC Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in TDB Julian Date
-pub fn utc_to_tdb(epoch: f64) -> f64 {
-    let tai = utc_to_tai(epoch);
-    let tt = tai_to_tt(tai);
-    tt_to_tdb(tt)
+/// * The epoch in TDB Julian Date, as `(day, fraction)`
+pub fn utc_to_tdb(day: f64, fraction: f64) -> (f64, f64) {
+    let (day, fraction) = utc_to_tai(day, fraction);
+    let (day, fraction) = tai_to_tt(day, fraction);
+    tt_to_tdb(day, fraction)
 }
 
 /// Converts TDB (Barycentric Dynamical Time) to UTC (Universal Time Coordinated)
@@ -111,15 +179,16 @@ pub fn utc_to_tdb(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in TDB Julian Date
+/// * `day` - The first part of the epoch in TDB Julian Date
+/// * `fraction` - The second part of the epoch in TDB Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in UTC Julian Date
-pub fn tdb_to_utc(epoch: f64) -> f64 {
-    let tt = tdb_to_tt(epoch);
-    let tai = tt_to_tai(tt);
-    tai_to_utc(tai)
+/// * The epoch in UTC Julian Date, as `(day, fraction)`
+pub fn tdb_to_utc(day: f64, fraction: f64) -> (f64, f64) {
+    let (day, fraction) = tdb_to_tt(day, fraction);
+    let (day, fraction) = tt_to_tai(day, fraction);
+    tai_to_utc(day, fraction)
 }
 
 /// Converts UTC (Universal Time Coordinated) to TT (Terrestrial Time)
@@ -127,14 +196,15 @@ pub fn tdb_to_utc(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in UTC Julian Date
+/// * `day` - The first part of the epoch in UTC Julian Date
+/// * `fraction` - The second part of the epoch in UTC Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in TT Julian Date
-pub fn utc_to_tt(epoch: f64) -> f64 {
-    let tai = utc_to_tai(epoch);
-    tai_to_tt(tai)
+/// * The epoch in TT Julian Date, as `(day, fraction)`
+pub fn utc_to_tt(day: f64, fraction: f64) -> (f64, f64) {
+    let (day, fraction) = utc_to_tai(day, fraction);
+    tai_to_tt(day, fraction)
 }
 
 /// Converts TT (Terrestrial Time) to UTC (Universal Time Coordinated)
@@ -142,14 +212,140 @@ pub fn utc_to_tt(epoch: f64) -> f64 {
 /// 
 /// # Arguments
 /// 
-/// * `epoch` - The epoch in TT Julian Date
+/// * `day` - The first part of the epoch in TT Julian Date
+/// * `fraction` - The second part of the epoch in TT Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in UTC Julian Date, as `(day, fraction)`
+pub fn tt_to_utc(day: f64, fraction: f64) -> (f64, f64) {
+    let (day, fraction) = tt_to_tai(day, fraction);
+    tai_to_utc(day, fraction)
+}
+
+/// Converts TAI (International Atomic Time) to GPS time
+/// GPS time differs from TAI by a constant offset of -19 seconds
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in TAI Julian Date
+/// * `fraction` - The second part of the epoch in TAI Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in GPS Julian Date, as `(day, fraction)`
+pub fn tai_to_gps(day: f64, fraction: f64) -> (f64, f64) {
+    add_seconds(day, fraction, GPS_MINUS_TAI)
+}
+
+/// Converts GPS time to TAI (International Atomic Time)
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in GPS Julian Date
+/// * `fraction` - The second part of the epoch in GPS Julian Date
 /// 
 /// # Returns
 /// 
-/// * The epoch in UTC Julian Date
-pub fn tt_to_utc(epoch: f64) -> f64 {
-    let tai = tt_to_tai(epoch);
-    tai_to_utc(tai)
+/// * The epoch in TAI Julian Date, as `(day, fraction)`
+pub fn gps_to_tai(day: f64, fraction: f64) -> (f64, f64) {
+    add_seconds(day, fraction, -GPS_MINUS_TAI)
+}
+
+/// Converts TT (Terrestrial Time) to TCG (Geocentric Coordinate Time)
+/// TCG runs faster than TT by the rate L_G, and the two agreed at 1977 January 1.0 TAI
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in TT Julian Date
+/// * `fraction` - The second part of the epoch in TT Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in TCG Julian Date, as `(day, fraction)`
+pub fn tt_to_tcg(day: f64, fraction: f64) -> (f64, f64) {
+    let elapsed = (day - T77.0) + (fraction - T77.1);
+    normalize_two_part(day, fraction + elapsed * L_G / (1.0 - L_G))
+}
+
+/// Converts TCG (Geocentric Coordinate Time) to TT (Terrestrial Time)
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in TCG Julian Date
+/// * `fraction` - The second part of the epoch in TCG Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in TT Julian Date, as `(day, fraction)`
+pub fn tcg_to_tt(day: f64, fraction: f64) -> (f64, f64) {
+    let elapsed = (day - T77.0) + (fraction - T77.1);
+    normalize_two_part(day, fraction - elapsed * L_G)
+}
+
+/// Converts TDB (Barycentric Dynamical Time) to TCB (Barycentric Coordinate Time)
+/// TCB runs faster than TDB by the rate L_B, with TDB offset by TDB0 at 1977 January 1.0 TAI
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in TDB Julian Date
+/// * `fraction` - The second part of the epoch in TDB Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in TCB Julian Date, as `(day, fraction)`
+pub fn tdb_to_tcb(day: f64, fraction: f64) -> (f64, f64) {
+    let elapsed = (day - T77.0) + (fraction - T77.1);
+    normalize_two_part(day, fraction + (elapsed * L_B - TDB0 / 86400.0) / (1.0 - L_B))
+}
+
+/// Converts TCB (Barycentric Coordinate Time) to TDB (Barycentric Dynamical Time)
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in TCB Julian Date
+/// * `fraction` - The second part of the epoch in TCB Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in TDB Julian Date, as `(day, fraction)`
+pub fn tcb_to_tdb(day: f64, fraction: f64) -> (f64, f64) {
+    let elapsed = (day - T77.0) + (fraction - T77.1);
+    normalize_two_part(day, fraction - elapsed * L_B + TDB0 / 86400.0)
+}
+
+/// Converts UTC (Universal Time Coordinated) to UT1, with UT1 - UTC from the loaded Earth orientation table
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in UTC Julian Date
+/// * `fraction` - The second part of the epoch in UTC Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in UT1 Julian Date, as `(day, fraction)`
+pub fn utc_to_ut1(day: f64, fraction: f64) -> (f64, f64) {
+    add_seconds(day, fraction, get_delta_ut1(day + fraction))
+}
+
+/// Converts UT1 to UTC (Universal Time Coordinated), with UT1 - UTC from the loaded Earth orientation table
+/// 
+/// # Arguments
+/// 
+/// * `day` - The first part of the epoch in UT1 Julian Date
+/// * `fraction` - The second part of the epoch in UT1 Julian Date
+/// 
+/// # Returns
+/// 
+/// * The epoch in UTC Julian Date, as `(day, fraction)`
+pub fn ut1_to_utc(day: f64, fraction: f64) -> (f64, f64) {
+    // UT1 - UTC changes by a few ms per day, so evaluating it at the UTC guess converges immediately
+    let mut dut1 = get_delta_ut1(day + fraction);
+    for _ in 0..2 {
+        let (utc_day, utc_fraction) = add_seconds(day, fraction, -dut1);
+        dut1 = get_delta_ut1(utc_day + utc_fraction);
+    }
+    add_seconds(day, fraction, -dut1)
 }
 
 // Calendar related conversions
@@ -220,7 +416,8 @@ pub fn jd_to_calendar(jd: &f64) -> String {
     format!("{} {} {}", day, MONTHS.get(&month).unwrap(), year)
 }
 
-/// Get number of leap seconds at a given epoch
+/// Get number of leap seconds at a given epoch, from the loaded leap second table
+/// (the built-in table unless a leapseconds kernel has been loaded)
 ///
 /// # Arguments
 ///
@@ -230,44 +427,437 @@ pub fn jd_to_calendar(jd: &f64) -> String {
 ///
 /// * The number of leap seconds at the given epoch
 pub fn get_leap_seconds_at_epoch(jd: f64) -> f64 {
-   
-    let mut num_leap_seconds = 0.0;
-    for &(time, leap_seconds) in &LEAP_SECONDS {
-        if jd >= time {
-            num_leap_seconds = leap_seconds;
-            break;
-        }
+    leap_seconds_at(jd)
+}
+
+/// Converts a UTC `DateTime` to a two-part Julian Date, keeping the sub-second part of the timestamp.
+///
+/// # Arguments
+///
+/// * `datetime` - The UTC `DateTime`
+///
+/// # Returns
+///
+/// * The Julian Date, as `(day, fraction)`
+pub fn datetime_to_julian(datetime: &DateTime<Utc>) -> (f64, f64) {
+    let timestamp = datetime.timestamp();
+    let days = timestamp.div_euclid(86400) as f64;
+    let seconds = timestamp.rem_euclid(86400) as f64 + datetime.timestamp_subsec_nanos() as f64 * 1e-9;
+    // the Unix epoch is JD 2440587.5
+    normalize_two_part(2440587.0 + days, 0.5 + seconds / 86400.0)
+}
+
+/// The Julian Date of the Unix epoch, 1970-01-01T00:00:00, as `(day, fraction)`.
+const UNIX_EPOCH_JD: (f64, f64) = (2440587.0, 0.5);
+
+/// The number of days from the Unix epoch to the GPS epoch, 1980-01-06T00:00:00.
+const GPS_EPOCH_DAYS: i64 = 3657;
+
+/// The largest number of decimal places written when formatting a time. Two-part Julian Dates
+/// resolve about 10 picoseconds, so further digits would be noise.
+const MAX_PRECISION: usize = 12;
+
+/// The characters used for months and days in MPC packed dates.
+const PACKED_DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+/// Count the days from 1970-01-01 to a date in the proleptic Gregorian calendar.
+///
+/// # Arguments
+///
+/// * `year` - The year
+/// * `month` - The month (1-12)
+/// * `day` - The day of the month (1-31)
+///
+/// # Returns
+///
+/// * The number of days since 1970-01-01
+pub fn civil_to_days(year: i64, month: u32, day: u32) -> i64 {
+    let (month, day) = (month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Find the date in the proleptic Gregorian calendar a number of days after 1970-01-01.
+///
+/// # Arguments
+///
+/// * `days` - The number of days since 1970-01-01
+///
+/// # Returns
+///
+/// * The `(year, month, day)`
+pub fn days_to_civil(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month as u32, day as u32)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    (civil_to_days(next_year, next_month, 1) - civil_to_days(year, month, 1)) as u32
+}
+
+/// Build a two-part Julian Date from a day count since 1970-01-01 and a fraction of a day,
+/// which may run past the end of the day.
+fn days_to_julian(days: i64, fraction_of_day: f64) -> (f64, f64) {
+    normalize_two_part(UNIX_EPOCH_JD.0 + days as f64, UNIX_EPOCH_JD.1 + fraction_of_day)
+}
+
+/// Split a two-part Julian Date into the days since 1970-01-01 and the fraction of the day.
+fn julian_to_days(day: f64, fraction: f64) -> (i64, f64) {
+    let (days, fraction_of_day) = normalize_two_part(day - UNIX_EPOCH_JD.0, fraction - UNIX_EPOCH_JD.1);
+    (days as i64, fraction_of_day)
+}
+
+/// Split a two-part Julian Date into the days since 1970-01-01 and the time of day, rounded to a
+/// whole number of `units_per_day`. Rounding up to midnight carries into the next day.
+fn julian_to_rounded_days(day: f64, fraction: f64, units_per_day: i128) -> (i64, i128) {
+    let (mut days, fraction_of_day) = julian_to_days(day, fraction);
+    let mut units = (fraction_of_day * units_per_day as f64).round() as i128;
+    if units >= units_per_day {
+        days += 1;
+        units -= units_per_day;
+    }
+    (days, units)
+}
+
+fn parse_error(text: &str, reason: &str) -> TimeError {
+    TimeError::ParseError(format!("'{}': {}", text, reason))
+}
+
+/// Parse a field that must be made up of digits, so that signs, exponents and `inf` are refused.
+fn parse_digits<T: std::str::FromStr>(field: &str, original: &str, what: &str) -> Result<T, TimeError> {
+    if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(parse_error(original, &format!("invalid {} '{}'", what, field)));
+    }
+    field.parse().map_err(|_| parse_error(original, &format!("invalid {} '{}'", what, field)))
+}
+
+/// Parse a field of the form `ddd.ddd`, returning the whole part and the decimal fraction separately.
+fn parse_decimal(field: &str, original: &str, what: &str) -> Result<(u32, f64), TimeError> {
+    let (whole, decimals) = field.split_once('.').unwrap_or((field, ""));
+    let whole = parse_digits(whole, original, what)?;
+    if decimals.is_empty() {
+        return Ok((whole, 0.0));
+    }
+    if !decimals.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(parse_error(original, &format!("invalid {} '{}'", what, field)));
+    }
+    let decimals = format!("0.{}", decimals).parse().map_err(|_| parse_error(original, &format!("invalid {} '{}'", what, field)))?;
+    Ok((whole, decimals))
+}
+
+fn check_date(year: i64, month: u32, day: u32, original: &str) -> Result<(), TimeError> {
+    if !(1..=12).contains(&month) {
+        return Err(parse_error(original, &format!("month {} is out of range", month)));
+    }
+    if day < 1 || day > days_in_month(year, month) {
+        return Err(parse_error(original, &format!("day {} is out of range for {:04}-{:02}", day, year, month)));
     }
-    num_leap_seconds
+    Ok(())
 }
 
-/// Converts an ISO 8601 formatted timestamp to a Julian Date.
+/// Converts an ISO 8601 date or date and time to a two-part Julian Date, keeping every digit
+/// of the seconds. The time may be separated from the date by `T` or a space, may leave out
+/// the seconds, and may end in `Z`. The result is in whatever timescale the string is in.
 ///
 /// # Arguments
 ///
-/// * `isot` - A string representing the ISO 8601 formatted timestamp 
-///            (e.g., "2024-12-11T12:34:56.789Z") in UTC.
+/// * `iso` - The string, e.g. "2024-12-11", "2024-12-11T12:34" or "2024-12-11T12:34:56.123456789Z"
 ///
 /// # Returns
 ///
-/// * The Julian Date corresponding to the given timestamp.
+/// * `Result<(f64, f64), TimeError>` - The Julian Date, as `(day, fraction)`
+pub fn iso_to_julian(iso: &str) -> Result<(f64, f64), TimeError> {
+    let text = iso.trim();
+    let text = text.strip_suffix(['Z', 'z']).unwrap_or(text);
+    let (date, clock) = match text.find(['T', 't', ' ']) {
+        Some(idx) => (&text[..idx], Some(text[idx + 1..].trim())),
+        None => (text, None),
+    };
+
+    let fields: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = fields[..] else {
+        return Err(parse_error(iso, "expected a date of the form YYYY-MM-DD"));
+    };
+    if year.len() != 4 {
+        return Err(parse_error(iso, "the year must have four digits"));
+    }
+    let year = parse_digits(year, iso, "year")?;
+    let month = parse_digits(month, iso, "month")?;
+    let day = parse_digits(day, iso, "day")?;
+    check_date(year, month, day, iso)?;
+
+    let seconds = match clock {
+        Some(clock) => {
+            let fields: Vec<&str> = clock.split(':').collect();
+            let (hour, minute, second) = match fields[..] {
+                [hour, minute] => (hour, minute, "0"),
+                [hour, minute, second] => (hour, minute, second),
+                _ => return Err(parse_error(iso, "expected a time of the form hh:mm or hh:mm:ss.sss")),
+            };
+            let hour: u32 = parse_digits(hour, iso, "hour")?;
+            let minute: u32 = parse_digits(minute, iso, "minute")?;
+            let (second, decimals) = parse_decimal(second, iso, "second")?;
+            // a second of 60 is allowed for leap seconds
+            if hour > 23 || minute > 59 || second > 60 {
+                return Err(parse_error(iso, "the time of day is out of range"));
+            }
+            (hour * 3600 + minute * 60 + second) as f64 + decimals
+        },
+        None => 0.0,
+    };
+
+    Ok(days_to_julian(civil_to_days(year, month, day), seconds / 86400.0))
+}
+
+/// Converts a two-part Julian Date to an ISO 8601 string, with the seconds rounded to a number
+/// of decimal places (at most 12). No timescale suffix is written.
 ///
-/// # Example
+/// # Arguments
 ///
-/// ```
-/// let julian_date = isot_to_julian("2024-12-11T12:34:56.789Z");
-/// println!("Julian Date: {}", julian_date);
-/// ```
-pub fn isot_to_julian(isot: &str) -> f64 {
-    let datetime: DateTime<Utc> = Utc.datetime_from_str(isot, "%Y-%m-%dT%H:%M:%S%.fZ").unwrap();
-    // let datetime: DateTime<Utc> = DateTime::parse_from_str(isot, "%Y-%m-%dT%H:%M:%S%.fZ").unwrap().into();
-    // let datetime: DateTime<Utc> = DateTime::parse_from_str(isot, "%Y-%m-%dT%H:%M:%S%.fZ").unwrap().into();
-    let unix_time = datetime.timestamp() as f64;
-    let julian_day = unix_time / 86400.0 + 2440587.5;
-    julian_day
+/// * `day` - The first part of the Julian Date
+/// * `fraction` - The second part of the Julian Date
+/// * `precision` - The number of decimal places of the seconds
+///
+/// # Returns
+///
+/// * A string of the form "2024-12-11T12:34:56.789"
+pub fn julian_to_iso(day: f64, fraction: f64, precision: usize) -> String {
+    let precision = precision.min(MAX_PRECISION);
+    let scale = 10_i128.pow(precision as u32);
+    let (days, units) = julian_to_rounded_days(day, fraction, 86400 * scale);
+    let (year, month, day_of_month) = days_to_civil(days);
+    let seconds = units / scale;
+    let mut iso = format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day_of_month, seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if precision > 0 {
+        iso.push_str(&format!(".{:0width$}", units % scale, width = precision));
+    }
+    iso
+}
+
+/// Converts a calendar date with a fractional day, as used in the MPC 80-column observation
+/// format, to a two-part Julian Date. The fields may be separated by spaces or dashes.
+///
+/// # Arguments
+///
+/// * `date` - The date, e.g. "2024 12 11.52414" or "2024-12-11.52414"
+///
+/// # Returns
+///
+/// * `Result<(f64, f64), TimeError>` - The Julian Date, as `(day, fraction)`
+pub fn calendar_date_to_julian(date: &str) -> Result<(f64, f64), TimeError> {
+    let fields: Vec<&str> = date.split(|c: char| c.is_whitespace() || c == '-').filter(|field| !field.is_empty()).collect();
+    let [year, month, day] = fields[..] else {
+        return Err(parse_error(date, "expected a date of the form YYYY MM DD.ddddd"));
+    };
+    if year.len() != 4 {
+        return Err(parse_error(date, "the year must have four digits"));
+    }
+    let year = parse_digits(year, date, "year")?;
+    let month = parse_digits(month, date, "month")?;
+    let (day, fraction_of_day) = parse_decimal(day, date, "day")?;
+    check_date(year, month, day, date)?;
+    Ok(days_to_julian(civil_to_days(year, month, day), fraction_of_day))
 }
 
+/// Converts a two-part Julian Date to a calendar date with a fractional day, as used in the
+/// MPC 80-column observation format.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the Julian Date
+/// * `fraction` - The second part of the Julian Date
+/// * `precision` - The number of decimal places of the day (at most 12)
+///
+/// # Returns
+///
+/// * A string of the form "2024 12 11.52414"
+pub fn julian_to_calendar_date(day: f64, fraction: f64, precision: usize) -> String {
+    let precision = precision.min(MAX_PRECISION);
+    let scale = 10_i128.pow(precision as u32);
+    let (days, units) = julian_to_rounded_days(day, fraction, scale);
+    let (year, month, day_of_month) = days_to_civil(days);
+    if precision == 0 {
+        return format!("{:04} {:02} {:02}", year, month, day_of_month);
+    }
+    format!("{:04} {:02} {:02}.{:0width$}", year, month, day_of_month, units, width = precision)
+}
+
+/// Converts an MPC packed date to a two-part Julian Date. The first character gives the century
+/// (I = 18, J = 19, K = 20), then come two digits of the year and one character each for the
+/// month and the day (1-9, then A = 10 onwards). Any further digits are a decimal fraction of the day.
+///
+/// # Arguments
+///
+/// * `packed` - The packed date, e.g. "K24CB" (2024 December 11) or "K24CB52414"
+///
+/// # Returns
+///
+/// * `Result<(f64, f64), TimeError>` - The Julian Date, as `(day, fraction)`
+pub fn mpc_packed_to_julian(packed: &str) -> Result<(f64, f64), TimeError> {
+    let text = packed.trim();
+    if text.len() < 5 || !text.is_ascii() {
+        return Err(parse_error(packed, "packed dates have at least five characters"));
+    }
+    let bytes = text.to_ascii_uppercase().into_bytes();
+    let unpack = |c: u8| PACKED_DIGITS.iter().position(|&digit| digit == c).map(|p| p as u32);
+
+    let century = match bytes[0] {
+        c @ b'A'..=b'Z' => (c - b'A') as i64 + 10,
+        _ => return Err(parse_error(packed, "the century must be a letter")),
+    };
+    let year = century * 100 + parse_digits::<i64>(&text[1..3], packed, "year")?;
+    let month = unpack(bytes[3]).ok_or_else(|| parse_error(packed, "invalid month"))?;
+    let day = unpack(bytes[4]).ok_or_else(|| parse_error(packed, "invalid day"))?;
+    check_date(year, month, day, packed)?;
+    let (_, fraction_of_day) = parse_decimal(&format!("0.{}", &text[5..]), packed, "fraction of a day")?;
+    Ok(days_to_julian(civil_to_days(year, month, day), fraction_of_day))
+}
 
+/// Converts a two-part Julian Date to an MPC packed date. Years from 1000 to 3599 can be packed.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the Julian Date
+/// * `fraction` - The second part of the Julian Date
+/// * `precision` - The number of digits of the fraction of the day to append (at most 12)
+///
+/// # Returns
+///
+/// * `Result<String, TimeError>` - The packed date, e.g. "K24CB52414"
+pub fn julian_to_mpc_packed(day: f64, fraction: f64, precision: usize) -> Result<String, TimeError> {
+    let precision = precision.min(MAX_PRECISION);
+    let scale = 10_i128.pow(precision as u32);
+    let (days, units) = julian_to_rounded_days(day, fraction, scale);
+    let (year, month, day_of_month) = days_to_civil(days);
+    if !(1000..3600).contains(&year) {
+        return Err(TimeError::ParseError(format!("for year {}: only years from 1000 to 3599 can be packed", year)));
+    }
+    let mut packed = format!(
+        "{}{:02}{}{}",
+        (b'A' + (year / 100 - 10) as u8) as char,
+        year % 100,
+        PACKED_DIGITS[month as usize] as char,
+        PACKED_DIGITS[day_of_month as usize] as char
+    );
+    if precision > 0 {
+        packed.push_str(&format!("{:0width$}", units, width = precision));
+    }
+    Ok(packed)
+}
+
+/// Converts a two-part decimal year, the year and the elapsed fraction of it, to a two-part Julian Date.
+///
+/// # Arguments
+///
+/// * `year` - The first part of the decimal year
+/// * `fraction` - The second part of the decimal year
+///
+/// # Returns
+///
+/// * The Julian Date, as `(day, fraction)`
+pub fn decimal_year_to_julian(year: f64, fraction: f64) -> (f64, f64) {
+    let (year, fraction) = normalize_two_part(year, fraction);
+    let start = civil_to_days(year as i64, 1, 1);
+    let length = civil_to_days(year as i64 + 1, 1, 1) - start;
+    days_to_julian(start, fraction * length as f64)
+}
+
+/// Converts a two-part Julian Date to a decimal year.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the Julian Date
+/// * `fraction` - The second part of the Julian Date
+///
+/// # Returns
+///
+/// * The decimal year
+pub fn julian_to_decimal_year(day: f64, fraction: f64) -> f64 {
+    let (days, fraction_of_day) = julian_to_days(day, fraction);
+    let (year, _, _) = days_to_civil(days);
+    let start = civil_to_days(year, 1, 1);
+    let length = civil_to_days(year + 1, 1, 1) - start;
+    year as f64 + ((days - start) as f64 + fraction_of_day) / length as f64
+}
+
+fn seconds_to_julian(seconds: f64, fraction: f64, epoch_days: i64) -> (f64, f64) {
+    let days = seconds.div_euclid(86400.0);
+    days_to_julian(epoch_days + days as i64, (seconds.rem_euclid(86400.0) + fraction) / 86400.0)
+}
+
+fn julian_to_seconds(day: f64, fraction: f64, epoch_days: i64) -> f64 {
+    let (days, fraction_of_day) = julian_to_days(day, fraction);
+    (days - epoch_days) as f64 * 86400.0 + fraction_of_day * 86400.0
+}
+
+/// Converts a two-part count of Unix seconds, which has 86400 seconds to every day, to a two-part Julian Date.
+///
+/// # Arguments
+///
+/// * `seconds` - The first part of the seconds since 1970-01-01T00:00:00
+/// * `fraction` - The second part of the seconds since 1970-01-01T00:00:00
+///
+/// # Returns
+///
+/// * The Julian Date, as `(day, fraction)`
+pub fn unix_to_julian(seconds: f64, fraction: f64) -> (f64, f64) {
+    seconds_to_julian(seconds, fraction, 0)
+}
+
+/// Converts a two-part Julian Date to Unix seconds.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the Julian Date
+/// * `fraction` - The second part of the Julian Date
+///
+/// # Returns
+///
+/// * The seconds since 1970-01-01T00:00:00
+pub fn julian_to_unix(day: f64, fraction: f64) -> f64 {
+    julian_to_seconds(day, fraction, 0)
+}
+
+/// Converts a two-part count of GPS seconds to a two-part Julian Date.
+///
+/// # Arguments
+///
+/// * `seconds` - The first part of the seconds since 1980-01-06T00:00:00
+/// * `fraction` - The second part of the seconds since 1980-01-06T00:00:00
+///
+/// # Returns
+///
+/// * The Julian Date, as `(day, fraction)`
+pub fn gps_seconds_to_julian(seconds: f64, fraction: f64) -> (f64, f64) {
+    seconds_to_julian(seconds, fraction, GPS_EPOCH_DAYS)
+}
+
+/// Converts a two-part Julian Date to GPS seconds.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the Julian Date
+/// * `fraction` - The second part of the Julian Date
+///
+/// # Returns
+///
+/// * The seconds since 1980-01-06T00:00:00
+pub fn julian_to_gps_seconds(day: f64, fraction: f64) -> f64 {
+    julian_to_seconds(day, fraction, GPS_EPOCH_DAYS)
+}
 
 
 // use std::time::{SystemTime, UNIX_EPOCH};
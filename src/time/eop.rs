@@ -0,0 +1,220 @@
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+
+use crate::time::conversions::get_leap_seconds_at_epoch;
+
+/// A single day of Earth orientation data.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EopEntry {
+    /// The epoch of the entry (MJD, UTC)
+    pub mjd: f64,
+    /// UT1 - UTC (seconds)
+    pub ut1_minus_utc: f64,
+    /// The x coordinate of the pole (arcseconds)
+    pub x_pole: f64,
+    /// The y coordinate of the pole (arcseconds)
+    pub y_pole: f64,
+    /// The celestial pole offset dX with respect to IAU 2000A nutation (milliarcseconds)
+    pub dx: f64,
+    /// The celestial pole offset dY with respect to IAU 2000A nutation (milliarcseconds)
+    pub dy: f64,
+}
+
+/// A table of Earth orientation data, e.g. from an IERS finals file, used to drive the UT1 timescale
+/// and the orientation of the Earth.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EopTable {
+    entries: Vec<EopEntry>,
+}
+
+impl EopTable {
+
+    /// Create a new EopTable. The entries are sorted by epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The entries of the table
+    ///
+    /// # Returns
+    ///
+    /// * `EopTable` - The table
+    pub fn new(mut entries: Vec<EopEntry>) -> Self {
+        entries.sort_by(|a, b| a.mjd.total_cmp(&b.mjd));
+        EopTable { entries }
+    }
+
+    /// Read an IERS finals file (`finals2000A.all`, `finals2000A.data` or `finals2000A.daily`), which holds
+    /// the Bulletin A values and predictions.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EopTable, Box<dyn std::error::Error>>` - The table
+    pub fn from_finals(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read Earth orientation file {}: {}", path, e))?;
+        EopTable::parse_finals(&content)
+    }
+
+    /// Parse the contents of an IERS finals file. The file has fixed columns, and the Bulletin A
+    /// polar motion, UT1 - UTC and celestial pole offsets are read. Rows without UT1 - UTC (beyond
+    /// the end of the predictions) are skipped, and missing pole offsets are taken to be zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text of the file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EopTable, Box<dyn std::error::Error>>` - The table
+    pub fn parse_finals(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut entries = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let field = |start: usize, end: usize| -> Result<Option<f64>, String> {
+                let text = line.get(start..end.min(line.len())).unwrap_or("").trim();
+                if text.is_empty() {
+                    return Ok(None);
+                }
+                text.parse().map(Some).map_err(|_| format!("Invalid value '{}' on line {} of Earth orientation file", text, idx + 1))
+            };
+
+            let mjd = field(7, 15)?.ok_or(format!("Missing MJD on line {} of Earth orientation file", idx + 1))?;
+            let Some(ut1_minus_utc) = field(58, 68)? else {
+                continue;
+            };
+            entries.push(EopEntry {
+                mjd,
+                ut1_minus_utc,
+                x_pole: field(18, 27)?.unwrap_or(0.0),
+                y_pole: field(37, 46)?.unwrap_or(0.0),
+                dx: field(97, 106)?.unwrap_or(0.0),
+                dy: field(116, 125)?.unwrap_or(0.0),
+            });
+        }
+        if entries.is_empty() {
+            return Err("No Earth orientation data found".into());
+        }
+        Ok(EopTable::new(entries))
+    }
+
+    /// The entries of the table, in order of epoch.
+    pub fn entries(&self) -> &[EopEntry] {
+        &self.entries
+    }
+
+    /// Interpolate the Earth orientation at an epoch. UT1 - TAI is interpolated rather than UT1 - UTC,
+    /// so that the steps at leap seconds are reproduced. Outside the table the nearest entry is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `mjd` - The epoch (MJD, UTC)
+    ///
+    /// # Returns
+    ///
+    /// * `Option<EopEntry>` - The interpolated entry, or None if the table is empty.
+    pub fn at(&self, mjd: f64) -> Option<EopEntry> {
+        let first = self.entries.first()?;
+        let last = self.entries.last()?;
+
+        let (before, after, weight) = if mjd <= first.mjd {
+            (first, first, 0.0)
+        } else if mjd >= last.mjd {
+            (last, last, 0.0)
+        } else {
+            let idx = self.entries.partition_point(|entry| entry.mjd <= mjd);
+            let (before, after) = (&self.entries[idx - 1], &self.entries[idx]);
+            (before, after, (mjd - before.mjd) / (after.mjd - before.mjd))
+        };
+        let lerp = |a: f64, b: f64| a + weight * (b - a);
+
+        let ut1_minus_tai = |entry: &EopEntry| entry.ut1_minus_utc - leap_seconds(entry.mjd);
+        Some(EopEntry {
+            mjd,
+            ut1_minus_utc: lerp(ut1_minus_tai(before), ut1_minus_tai(after)) + leap_seconds(mjd),
+            x_pole: lerp(before.x_pole, after.x_pole),
+            y_pole: lerp(before.y_pole, after.y_pole),
+            dx: lerp(before.dx, after.dx),
+            dy: lerp(before.dy, after.dy),
+        })
+    }
+
+    /// Interpolate UT1 - UTC at an epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `mjd` - The epoch (MJD, UTC)
+    ///
+    /// # Returns
+    ///
+    /// * `Option<f64>` - UT1 - UTC in seconds, or None if the table is empty.
+    pub fn ut1_minus_utc(&self, mjd: f64) -> Option<f64> {
+        self.at(mjd).map(|entry| entry.ut1_minus_utc)
+    }
+}
+
+fn leap_seconds(mjd: f64) -> f64 {
+    get_leap_seconds_at_epoch(mjd + 2400000.5)
+}
+
+lazy_static! {
+    static ref EOP_TABLE: RwLock<EopTable> = RwLock::new(EopTable::default());
+}
+
+/// Set the Earth orientation table used by the time system.
+///
+/// # Arguments
+///
+/// * `table` - The table
+pub fn set_eop_table(table: EopTable) {
+    *EOP_TABLE.write().unwrap() = table;
+}
+
+/// Get a copy of the Earth orientation table used by the time system.
+pub fn get_eop_table() -> EopTable {
+    EOP_TABLE.read().unwrap().clone()
+}
+
+/// Load an IERS finals file into the time system.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file
+pub fn load_eop(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    set_eop_table(EopTable::from_finals(path)?);
+    Ok(())
+}
+
+/// Get the Earth orientation at an epoch from the loaded table. With no table loaded
+/// UT1 is taken to be UTC and the pole offsets are zero.
+///
+/// # Arguments
+///
+/// * `jd` - The epoch (JD, UTC)
+///
+/// # Returns
+///
+/// * `EopEntry` - The interpolated Earth orientation
+pub fn get_earth_orientation(jd: f64) -> EopEntry {
+    let mjd = jd - 2400000.5;
+    EOP_TABLE.read().unwrap().at(mjd).unwrap_or(EopEntry { mjd, ..Default::default() })
+}
+
+/// Get UT1 - UTC at an epoch from the loaded Earth orientation table. With no table loaded
+/// UT1 is taken to be UTC, which is good to the 0.9 s that UTC is kept within.
+///
+/// # Arguments
+///
+/// * `jd` - The epoch (JD, UTC)
+///
+/// # Returns
+///
+/// * UT1 - UTC in seconds
+pub fn get_delta_ut1(jd: f64) -> f64 {
+    get_earth_orientation(jd).ut1_minus_utc
+}
@@ -1,3 +1,6 @@
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+
 pub const LEAP_SECONDS: [(f64, f64); 28] = [
         (2457754.5, 37.0),
         (2457204.5, 36.0),
@@ -27,4 +30,165 @@ pub const LEAP_SECONDS: [(f64, f64); 28] = [
         (2441683.5, 12.0),
         (2441499.5, 11.0),
         (2441317.5, 10.0),
-    ];
\ No newline at end of file
+    ];
+/// A table of TAI - UTC, from a NAIF leapseconds kernel or the built-in LEAP_SECONDS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeapSecondTable {
+    /// (JD of the start of the offset, TAI - UTC in seconds), newest first
+    entries: Vec<(f64, f64)>,
+}
+
+impl Default for LeapSecondTable {
+    fn default() -> Self {
+        LeapSecondTable::builtin()
+    }
+}
+
+impl LeapSecondTable {
+
+    /// The leap seconds built into the crate.
+    pub fn builtin() -> Self {
+        LeapSecondTable { entries: LEAP_SECONDS.to_vec() }
+    }
+
+    /// Create a new LeapSecondTable.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - (JD of the start of the offset, TAI - UTC in seconds), in any order
+    ///
+    /// # Returns
+    ///
+    /// * `LeapSecondTable` - The table
+    pub fn new(mut entries: Vec<(f64, f64)>) -> Self {
+        entries.sort_by(|a, b| b.0.total_cmp(&a.0));
+        LeapSecondTable { entries }
+    }
+
+    /// Read a NAIF leapseconds kernel, e.g. `latest_leapseconds.tls`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the kernel
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LeapSecondTable, Box<dyn std::error::Error>>` - The table
+    pub fn from_lsk(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read leapseconds kernel {}: {}", path, e))?;
+        LeapSecondTable::parse_lsk(&content)
+    }
+
+    /// Parse the contents of a NAIF leapseconds kernel. The offsets are read from the `DELTET/DELTA_AT`
+    /// assignment in the kernel's data sections, which pairs each offset with the date it took effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text of the kernel
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LeapSecondTable, Box<dyn std::error::Error>>` - The table
+    pub fn parse_lsk(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+
+        // only the data sections hold assignments; the text sections are comments
+        let mut data = String::new();
+        let mut in_data = false;
+        for line in content.lines() {
+            match line.trim() {
+                "\\begindata" => in_data = true,
+                "\\begintext" => in_data = false,
+                _ if in_data => {
+                    data.push_str(line);
+                    data.push('\n');
+                },
+                _ => {},
+            }
+        }
+
+        let start = data.find("DELTET/DELTA_AT").ok_or("No DELTET/DELTA_AT in leapseconds kernel")?;
+        let values = &data[start..];
+        let open = values.find('(').ok_or("Malformed DELTET/DELTA_AT in leapseconds kernel")?;
+        let close = values.find(')').ok_or("Malformed DELTET/DELTA_AT in leapseconds kernel")?;
+        let tokens: Vec<&str> = values[open + 1..close]
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .collect();
+
+        if tokens.is_empty() || tokens.len() % 2 == 1 {
+            return Err("DELTET/DELTA_AT must hold pairs of offsets and dates".into());
+        }
+
+        let mut entries = Vec::with_capacity(tokens.len() / 2);
+        for pair in tokens.chunks(2) {
+            let offset: f64 = pair[0].parse().map_err(|_| format!("Invalid leap second offset: {}", pair[0]))?;
+            let jd = parse_lsk_date(pair[1])?;
+            entries.push((jd, offset));
+        }
+        Ok(LeapSecondTable::new(entries))
+    }
+
+    /// The entries of the table, as (JD of the start of the offset, TAI - UTC in seconds), newest first.
+    pub fn entries(&self) -> &[(f64, f64)] {
+        &self.entries
+    }
+
+    /// Get TAI - UTC at an epoch. Zero before the first entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `jd` - The epoch (JD)
+    ///
+    /// # Returns
+    ///
+    /// * TAI - UTC in seconds
+    pub fn leap_seconds_at(&self, jd: f64) -> f64 {
+        self.entries.iter()
+            .find(|(start, _)| jd >= *start)
+            .map(|(_, leap_seconds)| *leap_seconds)
+            .unwrap_or(0.0)
+    }
+}
+
+/// Parse a date of the form `@1972-JAN-1` into the JD of 0h on that day.
+fn parse_lsk_date(token: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    let date = token.trim_start_matches('@');
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%b-%d")
+        .map_err(|_| format!("Invalid date in leapseconds kernel: {}", token))?;
+    let unix_epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    Ok(2440587.5 + (date - unix_epoch).num_days() as f64)
+}
+
+lazy_static! {
+    static ref LEAP_SECOND_TABLE: RwLock<LeapSecondTable> = RwLock::new(LeapSecondTable::builtin());
+}
+
+/// Set the leap second table used by the time system.
+///
+/// # Arguments
+///
+/// * `table` - The table
+pub fn set_leap_second_table(table: LeapSecondTable) {
+    *LEAP_SECOND_TABLE.write().unwrap() = table;
+}
+
+/// Get a copy of the leap second table used by the time system.
+pub fn get_leap_second_table() -> LeapSecondTable {
+    LEAP_SECOND_TABLE.read().unwrap().clone()
+}
+
+/// Load a NAIF leapseconds kernel into the time system, replacing the built-in table.
+///
+/// # Arguments
+///
+/// * `path` - The path to the kernel
+pub fn load_leap_seconds(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    set_leap_second_table(LeapSecondTable::from_lsk(path)?);
+    Ok(())
+}
+
+/// Get TAI - UTC at an epoch from the loaded leap second table.
+pub(crate) fn leap_seconds_at(jd: f64) -> f64 {
+    LEAP_SECOND_TABLE.read().unwrap().leap_seconds_at(jd)
+}
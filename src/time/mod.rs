@@ -7,7 +7,20 @@ pub use self::timeformat::TimeFormat;
 pub mod timescale;
 pub use self::timescale::TimeScale;
 
+pub mod timerange;
+pub use self::timerange::TimeRange;
+
+pub mod sidereal;
+pub use self::sidereal::{calc_earth_rotation_angle, calc_gmst, EARTH_ROTATION_RATE};
+
 pub mod leapseconds;
+pub use self::leapseconds::{LeapSecondTable, set_leap_second_table, get_leap_second_table, load_leap_seconds};
+
+pub mod eop;
+pub use self::eop::{EopEntry, EopTable, set_eop_table, get_eop_table, load_eop, get_earth_orientation, get_delta_ut1};
+
+pub mod fairhead_bretagnon;
+pub use self::fairhead_bretagnon::calc_tdb_minus_tt;
 
 pub mod conversions;
 pub use self::conversions::*; 
\ No newline at end of file
@@ -0,0 +1,42 @@
+use std::f64::consts::TAU;
+
+use crate::constants::ARCSEC_TO_RAD;
+
+/// The rate of the Earth rotation angle, in revolutions per UT1 day (IAU 2000).
+pub const EARTH_ROTATION_RATE: f64 = 1.002_737_811_911_354_5;
+
+/// Calculate the Earth rotation angle (IAU 2000), following the SOFA routine iauEra00.
+///
+/// # Arguments
+///
+/// * `day` - The first part of the epoch (UT1 Julian Date)
+/// * `fraction` - The second part of the epoch (UT1 Julian Date)
+///
+/// # Returns
+///
+/// * The Earth rotation angle (radians), in [0, 2π)
+pub fn calc_earth_rotation_angle(day: f64, fraction: f64) -> f64 {
+    let t = (day - 2451545.0) + fraction;
+    // the whole revolutions are dropped from each part before they are combined
+    let f = day.rem_euclid(1.0) + fraction.rem_euclid(1.0);
+    (TAU * (f + 0.7790572732640 + 0.00273781191135448 * t)).rem_euclid(TAU)
+}
+
+/// Calculate Greenwich mean sidereal time (IAU 2006), following the SOFA routine iauGmst06.
+///
+/// # Arguments
+///
+/// * `ut1_day` - The first part of the epoch (UT1 Julian Date)
+/// * `ut1_fraction` - The second part of the epoch (UT1 Julian Date)
+/// * `tt_day` - The first part of the epoch (TT Julian Date)
+/// * `tt_fraction` - The second part of the epoch (TT Julian Date)
+///
+/// # Returns
+///
+/// * Greenwich mean sidereal time (radians), in [0, 2π)
+pub fn calc_gmst(ut1_day: f64, ut1_fraction: f64, tt_day: f64, tt_fraction: f64) -> f64 {
+    // Julian centuries of TT since J2000
+    let t = ((tt_day - 2451545.0) + tt_fraction) / 36525.0;
+    let precession = 0.014506 + t * (4612.156534 + t * (1.3915817 + t * (-0.00000044 + t * (-0.000029956 + t * -0.0000000368))));
+    (calc_earth_rotation_angle(ut1_day, ut1_fraction) + precession * ARCSEC_TO_RAD).rem_euclid(TAU)
+}
@@ -1,17 +1,24 @@
-use std::ops::{AddAssign, Add, Sub};
-use chrono::{Utc, TimeZone, DateTime};
+use std::ops::{AddAssign, SubAssign, Add, Sub};
+use chrono::Utc;
 use crate::time::timescale::TimeScale;
 use crate::time::timeformat::TimeFormat;
 use crate::errors::TimeError;
 use serde::{Serialize, Deserialize};
 use crate::time::conversions::*;
-use crate::utils::find_closest_match;
+use crate::time::fairhead_bretagnon::calc_tdb_minus_tt;
+use crate::time::sidereal::{calc_earth_rotation_angle, calc_gmst};
 
 
 
+/// An instant, held as a two-part Julian Date so that it keeps sub-microsecond resolution
+/// through timescale conversions and arithmetic. The parts always refer to the JD, whatever
+/// the `format`, which only sets how the epoch is presented.
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Time {
-    pub epoch: f64,
+    /// The whole number of days of the Julian Date
+    pub day: f64,
+    /// The fraction of a day, in [0, 1)
+    pub fraction: f64,
     pub timescale: TimeScale,
     pub format: TimeFormat,
 }
@@ -22,9 +29,9 @@ impl Time {
     ///
     /// # Arguments
     ///
-    /// * `epoch` - The epoch of the time (JD or MJD).
+    /// * `epoch` - The epoch of the time (JD, MJD, decimal year, or Unix or GPS seconds).
     /// * `timescale` - The timescale of the time (UTC or TDB).
-    /// * `format` - The format of the time (JD or MJD).
+    /// * `format` - The format of the time (JD, MJD, DecimalYear, Unix or GPS).
     ///
     /// # Returns
     ///
@@ -39,45 +46,47 @@ impl Time {
     /// ```
 
     pub fn new(epoch: f64, timescale: &str, format: &str) -> Result<Self, TimeError> {
+        Time::from_parts(epoch, 0.0, timescale, format)
+    }
+
+    /// Create a new `Time` object from a two-part epoch, e.g. a whole day number and a fraction of a day.
+    /// The epoch is the sum of the two parts, which can be split in any way.
+    ///
+    /// # Arguments
+    ///
+    /// * `day` - The first part of the epoch (JD, MJD, decimal year, or Unix or GPS seconds).
+    /// * `fraction` - The second part of the epoch (JD, MJD, decimal year, or Unix or GPS seconds).
+    /// * `timescale` - The timescale of the time (UTC or TDB).
+    /// * `format` - The format of the time (JD, MJD, DecimalYear, Unix or GPS).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Time, TimeError>` - The time object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spacerocks::Time;
+    ///
+    /// let t = Time::from_parts(60000.0, 0.123456789012, "UTC", "MJD");
+    /// ```
+    pub fn from_parts(day: f64, fraction: f64, timescale: &str, format: &str) -> Result<Self, TimeError> {
      
-        let timescale = match timescale.to_uppercase().as_str() {
-            "UTC" => TimeScale::UTC,
-            "TDB" => TimeScale::TDB,
-            "TT" => TimeScale::TT,
-            "TAI" => TimeScale::TAI,
-            _ => {
-                let suggestion = find_closest_match(
-                    &timescale.to_uppercase(),
-                    TimeScale::variants()
-                ).map(|s| format!("Did you mean '{}'?", s.to_lowercase()))
-                 .unwrap_or_default();
-                return Err(TimeError::InvalidTimeScale(format!(
-                    "'{}'. {}",
-                    timescale.to_string(),
-                    suggestion
-                )));
-            }
-        };
+        let timescale: TimeScale = timescale.parse()?;
      
-        let format = match format.to_uppercase().as_str() {
-            "JD" => TimeFormat::JD,
-            "MJD" => TimeFormat::MJD,
-            _ => {
-                let suggestion = find_closest_match(
-                    &format.to_uppercase(),
-                    TimeFormat::variants()
-                ).map(|s| format!("Did you mean '{}'?", s.to_lowercase()))
-                 .unwrap_or_default();
-                return Err(TimeError::InvalidTimeFormat(format!(
-                    "'{}'. {}",
-                    format.to_string(),
-                    suggestion
-                )));
-            }
-        };
+        let format: TimeFormat = format.parse()?;
      
+        let (day, fraction) = match format {
+            TimeFormat::JD => normalize_two_part(day, fraction),
+            TimeFormat::MJD => normalize_two_part(day + 2400000.0, fraction + 0.5),
+            TimeFormat::DecimalYear => decimal_year_to_julian(day, fraction),
+            TimeFormat::Unix => unix_to_julian(day, fraction),
+            TimeFormat::GPS => gps_seconds_to_julian(day, fraction),
+        };
+
         Ok(Time {
-            epoch,
+            day,
+            fraction,
             timescale: timescale,
             format: format,
         })
@@ -96,20 +105,29 @@ impl Time {
     /// let t = Time::now();
     /// ```
     pub fn now() -> Self {
-        let now = Utc::now();
-        let x = now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let (day, fraction) = datetime_to_julian(&Utc::now());
         Time {
-            epoch: isot_to_julian(&x),
+            day,
+            fraction,
             timescale: TimeScale::UTC,
             format: TimeFormat::JD,
         }
     }
 
-    /// Create a new `Time` object from a 'fuzzy string'.
+    /// Create a new `Time` object from a 'fuzzy string'. The string can be
+    ///
+    /// * "now"
+    /// * "epoch timescale format", e.g. "2451545.0 utc jd" or "2024.5 tdb decimalyear". The format
+    ///   can be left out, in which case JD or MJD is inferred, and so can the timescale (UTC).
+    /// * an ISO 8601 date and time, e.g. "2024-12-11T12:34:56.789 tdb"
+    /// * a calendar date with a fractional day, e.g. "2024 12 11.52414 utc"
+    /// * an MPC packed date, e.g. "K24CB52414 tt"
+    ///
+    /// The timescale defaults to UTC, except for packed dates, which are usually orbit epochs in TT.
     ///
     /// # Arguments
     ///
-    /// * `s` - A string representing a time in the format "epoch timescale format".
+    /// * `s` - A string representing a time.
     ///
     /// # Returns
     ///
@@ -121,15 +139,61 @@ impl Time {
     /// let t = Time::from_fuzzy_str("2451545.0 UTC JD");
     /// ```
     pub fn from_fuzzy_str(s: &str) -> Result<Self, TimeError> {
-        let s = s.to_lowercase();
-        if s == "now" {
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        let Some(first) = tokens.first() else {
+            return Err(TimeError::ParseError("'': the string is empty".to_string()));
+        };
+        if tokens.len() == 1 && first.eq_ignore_ascii_case("now") {
             return Ok(Time::now());
         }
-        let mut parts = s.split_whitespace();
-        let epoch = parts.next().unwrap().parse::<f64>().unwrap();
-        let timescale = parts.next().unwrap();
-        let format = parts.next().unwrap();
-        Time::new(epoch, timescale, format)
+
+        let too_many = || TimeError::ParseError(format!("'{}': unexpected trailing text", s));
+        let is_digits = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+        let bytes = first.as_bytes();
+
+        // an ISO 8601 date, with the time of day in the same or the next token
+        if bytes.len() >= 10 && first.get(..4).is_some_and(is_digits) && bytes[4] == b'-' {
+            let (date, rest) = match tokens.get(1) {
+                Some(clock) if clock.contains(':') => (format!("{}T{}", first, clock), &tokens[2..]),
+                _ => (first.to_string(), &tokens[1..]),
+            };
+            if rest.len() > 1 {
+                return Err(too_many());
+            }
+            let timescale = rest.first().copied().unwrap_or("utc");
+            // a date with a fractional day, e.g. 2024-12-11.52414
+            if !date.contains(['T', 't']) && date.contains('.') {
+                return Time::from_calendar(&date, timescale);
+            }
+            return Time::from_iso(&date, timescale);
+        }
+
+        // a calendar date with a fractional day, e.g. 2024 12 11.52414
+        if tokens.len() >= 3 && is_digits(first) && is_digits(tokens[1]) {
+            if tokens.len() > 4 {
+                return Err(too_many());
+            }
+            let timescale = tokens.get(3).copied().unwrap_or("utc");
+            return Time::from_calendar(&tokens[..3].join(" "), timescale);
+        }
+
+        // an MPC packed date
+        if bytes.len() >= 5 && bytes[0].is_ascii_alphabetic() && first.get(1..3).is_some_and(is_digits) {
+            if tokens.len() > 2 {
+                return Err(too_many());
+            }
+            let timescale = tokens.get(1).copied().unwrap_or("tt");
+            return Time::from_mpc_packed(first, timescale);
+        }
+
+        let epoch = first.parse::<f64>()
+            .map_err(|_| TimeError::ParseError(format!("'{}': '{}' is not a recognised epoch", s, first)))?;
+        match tokens[1..] {
+            [] => Time::infer_time_format(epoch, None),
+            [timescale] => Time::infer_time_format(epoch, Some(timescale)),
+            [timescale, format] => Time::new(epoch, timescale, format),
+            _ => Err(too_many()),
+        }
     }
 
     /// Infer the time format from the epoch and create a new `Time` object.
@@ -180,7 +244,7 @@ impl Time {
         Time::new(epoch, timescale, format)
     }
 
-    /// Create a new `Time` object from an ISO 8601 string.
+    /// Create a new `Time` object from an ISO 8601 string in UTC.
     ///
     /// # Arguments
     ///
@@ -195,14 +259,103 @@ impl Time {
     /// ```
     /// let t = Time::from_isot("2021-01-01T00:00:00Z");
     /// ```
-    
     pub fn from_isot(isot: &str) -> Result<Self, TimeError> {
-        let epoch = isot_to_julian(isot);
-        Ok(Time {
-            epoch,
-            timescale: TimeScale::UTC,
-            format: TimeFormat::JD,
-        })
+        Time::from_iso(isot, "utc")
+    }
+
+    /// Create a new `Time` object from an ISO 8601 string, keeping every digit of the seconds.
+    /// The time may be separated from the date by `T` or a space, and may be left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `iso` - The ISO 8601 string, e.g. "2024-12-11T12:34:56.123456789".
+    /// * `timescale` - The timescale of the time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Time, TimeError>` - The time object, in JD format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let t = Time::from_iso("2024-12-11T12:34:56.123456789", "tdb");
+    /// ```
+    pub fn from_iso(iso: &str, timescale: &str) -> Result<Self, TimeError> {
+        let (day, fraction) = iso_to_julian(iso)?;
+        Time::from_parts(day, fraction, timescale, "jd")
+    }
+
+    /// Create a new `Time` object from a calendar date with a fractional day, as used in
+    /// the MPC 80-column observation format.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date, e.g. "2024 12 11.52414".
+    /// * `timescale` - The timescale of the time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Time, TimeError>` - The time object, in JD format.
+    pub fn from_calendar(date: &str, timescale: &str) -> Result<Self, TimeError> {
+        let (day, fraction) = calendar_date_to_julian(date)?;
+        Time::from_parts(day, fraction, timescale, "jd")
+    }
+
+    /// Create a new `Time` object from an MPC packed date, e.g. "K24CB" or "K24CB52414".
+    ///
+    /// # Arguments
+    ///
+    /// * `packed` - The packed date.
+    /// * `timescale` - The timescale of the time. MPC orbit epochs are in TT.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Time, TimeError>` - The time object, in JD format.
+    pub fn from_mpc_packed(packed: &str, timescale: &str) -> Result<Self, TimeError> {
+        let (day, fraction) = mpc_packed_to_julian(packed)?;
+        Time::from_parts(day, fraction, timescale, "jd")
+    }
+
+    /// Create a new `Time` object from a decimal year.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The decimal year, e.g. 2024.5.
+    /// * `timescale` - The timescale of the time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Time, TimeError>` - The time object, in DecimalYear format.
+    pub fn from_decimal_year(year: f64, timescale: &str) -> Result<Self, TimeError> {
+        Time::new(year, timescale, "decimalyear")
+    }
+
+    /// Create a new `Time` object from Unix seconds, in UTC.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - The seconds since 1970-01-01T00:00:00 UTC, with 86400 seconds to every day.
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - The time object, in Unix format.
+    pub fn from_unix(seconds: f64) -> Self {
+        let (day, fraction) = unix_to_julian(seconds, 0.0);
+        Time { day, fraction, timescale: TimeScale::UTC, format: TimeFormat::Unix }
+    }
+
+    /// Create a new `Time` object from GPS seconds, in the GPS timescale.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - The seconds since 1980-01-06T00:00:00 GPS.
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - The time object, in GPS format.
+    pub fn from_gps_seconds(seconds: f64) -> Self {
+        let (day, fraction) = gps_seconds_to_julian(seconds, 0.0);
+        Time { day, fraction, timescale: TimeScale::GPS, format: TimeFormat::GPS }
     }
 
 
@@ -218,28 +371,10 @@ impl Time {
     /// ```
     /// let tdb_time = Time::new(2456205.5, "tdb", "jd").unwrap();
     /// let utc_time = tdb_time.utc();  // Creates new Time object in UTC
-    /// assert!(tdb_time.epoch != utc_time.epoch);  // Epochs differ due to timescale conversion
+    /// assert!(tdb_time.epoch() != utc_time.epoch());  // Epochs differ due to timescale conversion
     /// ```
     pub fn utc(&self) -> Time {
-        let mut new_time = self.clone();
-        match self.timescale {
-            TimeScale::UTC => new_time, // Already UTC
-            TimeScale::TDB => {
-                new_time.epoch = tdb_to_utc(self.epoch);
-                new_time.timescale = TimeScale::UTC;
-                new_time
-            },
-            TimeScale::TT => {
-                new_time.epoch = tt_to_utc(self.epoch);
-                new_time.timescale = TimeScale::UTC;
-                new_time
-            },
-            TimeScale::TAI => {
-                new_time.epoch = tai_to_utc(self.epoch);
-                new_time.timescale = TimeScale::UTC;
-                new_time
-            },
-        }
+        self.in_timescale(TimeScale::UTC)
     }
 
     /// Create a new time object with TDB timescale
@@ -248,27 +383,7 @@ impl Time {
     ///
     /// * `Time` - New time object with the timescale set to TDB.
     pub fn tdb(&self) -> Time {
-        let mut new_time = self.clone();
-        match self.timescale {
-            TimeScale::UTC => {
-                new_time.epoch = utc_to_tdb(self.epoch);
-                new_time.timescale = TimeScale::TDB;
-                new_time
-            },
-            TimeScale::TDB => new_time, // Already TDB
-            TimeScale::TT => {
-                new_time.epoch = tt_to_tdb(self.epoch);
-                new_time.timescale = TimeScale::TDB;
-                new_time
-            },
-            TimeScale::TAI => {
-                // Convert TAI -> TT -> TDB
-                let tt = tai_to_tt(self.epoch);
-                new_time.epoch = tt_to_tdb(tt);
-                new_time.timescale = TimeScale::TDB;
-                new_time
-            },
-        }
+        self.in_timescale(TimeScale::TDB)
     }
 
     /// Create a new time object with TT timescale
@@ -277,25 +392,7 @@ impl Time {
     ///
     /// * `Time` - New time object with the timescale set to TT.
     pub fn tt(&self) -> Time {
-        let mut new_time = self.clone();
-        match self.timescale {
-            TimeScale::UTC => {
-                new_time.epoch = utc_to_tt(self.epoch);
-                new_time.timescale = TimeScale::TT;
-                new_time
-            },
-            TimeScale::TDB => {
-                new_time.epoch = tdb_to_tt(self.epoch);
-                new_time.timescale = TimeScale::TT;
-                new_time
-            },
-            TimeScale::TT => new_time, // Already TT
-            TimeScale::TAI => {
-                new_time.epoch = tai_to_tt(self.epoch);
-                new_time.timescale = TimeScale::TT;
-                new_time
-            },
-        }
+        self.in_timescale(TimeScale::TT)
     }
 
     /// Create a new time object with TAI timescale
@@ -304,26 +401,83 @@ impl Time {
     ///
     /// * `Time` - New time object with the timescale set to TAI.
     pub fn tai(&self) -> Time {
+        self.in_timescale(TimeScale::TAI)
+    }
+
+    /// Create a new time object with TCB timescale
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - New time object with the timescale set to TCB.
+    pub fn tcb(&self) -> Time {
+        self.in_timescale(TimeScale::TCB)
+    }
+
+    /// Create a new time object with TCG timescale
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - New time object with the timescale set to TCG.
+    pub fn tcg(&self) -> Time {
+        self.in_timescale(TimeScale::TCG)
+    }
+
+    /// Create a new time object with UT1 timescale. UT1 - UTC is taken from the loaded Earth orientation table.
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - New time object with the timescale set to UT1.
+    pub fn ut1(&self) -> Time {
+        self.in_timescale(TimeScale::UT1)
+    }
+
+    /// Create a new time object with GPS timescale
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - New time object with the timescale set to GPS.
+    pub fn gps(&self) -> Time {
+        self.in_timescale(TimeScale::GPS)
+    }
+
+    /// Create a new time object with the given timescale
+    ///
+    /// # Arguments
+    ///
+    /// * `timescale` - The timescale to convert the time to.
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - New time object with the timescale changed.
+    pub fn in_timescale(&self, timescale: TimeScale) -> Time {
         let mut new_time = self.clone();
-        match self.timescale {
-            TimeScale::UTC => {
-                new_time.epoch = utc_to_tai(self.epoch);
-                new_time.timescale = TimeScale::TAI;
-                new_time
-            },
-            TimeScale::TDB => {
-                // Convert TDB -> TT -> TAI
-                let tt = tdb_to_tt(self.epoch);
-                new_time.epoch = tt_to_tai(tt);
-                new_time.timescale = TimeScale::TAI;
-                new_time
-            },
-            TimeScale::TT => {
-                new_time.epoch = tt_to_tai(self.epoch);
-                new_time.timescale = TimeScale::TAI;
-                new_time
-            },
-            TimeScale::TAI => new_time, // Already TAI
+        new_time.change_timescale(timescale);
+        new_time
+    }
+
+    /// Create a new time object in TDB, including the topocentric terms of TDB - TT for an observer
+    /// on the Earth. These reach about 2 µs, and matter for precise timing from a ground station.
+    ///
+    /// # Arguments
+    ///
+    /// * `elong` - The observer's east longitude (radians)
+    /// * `u` - The observer's distance from the Earth's spin axis (km)
+    /// * `v` - The observer's distance north of the equatorial plane (km)
+    ///
+    /// # Returns
+    ///
+    /// * `Time` - New time object with the timescale set to TDB.
+    pub fn tdb_topocentric(&self, elong: f64, u: f64, v: f64) -> Time {
+        let (day, fraction) = self.tt().jd_parts();
+        // UT1 counted from midnight
+        let ut = self.ut1().fraction + 0.5;
+        let offset = calc_tdb_minus_tt(day, fraction, ut, elong, u, v);
+        let (day, fraction) = normalize_two_part(day, fraction + offset / 86400.0);
+        Time {
+            day,
+            fraction,
+            timescale: TimeScale::TDB,
+            format: self.format.clone(),
         }
     }
 
@@ -331,7 +485,7 @@ impl Time {
 
     /// Convert the time object to UTC timescale in place
     ///
-    /// Modifies the time object by converting its epoch and timescale to TT.
+    /// Modifies the time object by converting its epoch and timescale to UTC.
     ///
     /// # Example
     ///
@@ -341,92 +495,56 @@ impl Time {
     /// assert_eq!(time.timescale, TimeScale::UTC);
     /// ```
     pub fn to_utc(&mut self) -> &mut Self {
-        match self.timescale {
-            TimeScale::UTC => {}, // Already UTC
-            TimeScale::TDB => {
-                self.epoch = tdb_to_utc(self.epoch);
-                self.timescale = TimeScale::UTC;
-            },
-            TimeScale::TT => {
-                self.epoch = tt_to_utc(self.epoch);
-                self.timescale = TimeScale::UTC;
-            },
-            TimeScale::TAI => {
-                self.epoch = tai_to_utc(self.epoch);
-                self.timescale = TimeScale::UTC;
-            },
-        }
-        self
+        self.change_timescale(TimeScale::UTC)
     }
 
     /// Convert the time object to TDB timescale in place
     ///
     /// Modifies the time object by converting its epoch and timescale to TDB.
     pub fn to_tdb(&mut self) -> &mut Self {
-        match self.timescale {
-            TimeScale::UTC => {
-                self.epoch = utc_to_tdb(self.epoch);
-                self.timescale = TimeScale::TDB;
-            },
-            TimeScale::TDB => {}, // Already TDB
-            TimeScale::TT => {
-                self.epoch = tt_to_tdb(self.epoch);
-                self.timescale = TimeScale::TDB;
-            },
-            TimeScale::TAI => {
-                // Convert TAI -> TT -> TDB
-                let tt = tai_to_tt(self.epoch);
-                self.epoch = tt_to_tdb(tt);
-                self.timescale = TimeScale::TDB;
-            },
-        }
-        self
+        self.change_timescale(TimeScale::TDB)
     }
 
     /// Convert the time object to TT timescale in place
     ///
     /// Modifies the time object by converting its epoch and timescale to TT.
     pub fn to_tt(&mut self) -> &mut Self {
-        match self.timescale {
-            TimeScale::UTC => {
-                self.epoch = utc_to_tt(self.epoch);
-                self.timescale = TimeScale::TT;
-            },
-            TimeScale::TDB => {
-                self.epoch = tdb_to_tt(self.epoch);
-                self.timescale = TimeScale::TT;
-            },
-            TimeScale::TT => {}, // Already TT
-            TimeScale::TAI => {
-                self.epoch = tai_to_tt(self.epoch);
-                self.timescale = TimeScale::TT;
-            },
-        }
-        self
+        self.change_timescale(TimeScale::TT)
     }
 
     /// Convert the time object to TAI timescale in place
     ///
     /// Modifies the time object by converting its epoch and timescale to TAI.
     pub fn to_tai(&mut self) -> &mut Self {
-        match self.timescale {
-            TimeScale::UTC => {
-                self.epoch = utc_to_tai(self.epoch);
-                self.timescale = TimeScale::TAI;
-            },
-            TimeScale::TDB => {
-                // Convert TDB -> TT -> TAI
-                let tt = tdb_to_tt(self.epoch);
-                self.epoch = tt_to_tai(tt);
-                self.timescale = TimeScale::TAI;
-            },
-            TimeScale::TT => {
-                self.epoch = tt_to_tai(self.epoch);
-                self.timescale = TimeScale::TAI;
-            },
-            TimeScale::TAI => {}, // Already TAI
-        }
-        self
+        self.change_timescale(TimeScale::TAI)
+    }
+
+    /// Convert the time object to TCB timescale in place
+    ///
+    /// Modifies the time object by converting its epoch and timescale to TCB.
+    pub fn to_tcb(&mut self) -> &mut Self {
+        self.change_timescale(TimeScale::TCB)
+    }
+
+    /// Convert the time object to TCG timescale in place
+    ///
+    /// Modifies the time object by converting its epoch and timescale to TCG.
+    pub fn to_tcg(&mut self) -> &mut Self {
+        self.change_timescale(TimeScale::TCG)
+    }
+
+    /// Convert the time object to UT1 timescale in place
+    ///
+    /// Modifies the time object by converting its epoch and timescale to UT1.
+    pub fn to_ut1(&mut self) -> &mut Self {
+        self.change_timescale(TimeScale::UT1)
+    }
+
+    /// Convert the time object to GPS timescale in place
+    ///
+    /// Modifies the time object by converting its epoch and timescale to GPS.
+    pub fn to_gps(&mut self) -> &mut Self {
+        self.change_timescale(TimeScale::GPS)
     }
 
     // Getters for the time format of the time object
@@ -437,10 +555,7 @@ impl Time {
     ///
     /// * `f64` - The time as a JD.
     pub fn jd(&self) -> f64 {
-        match self.format {
-            TimeFormat::JD => self.epoch,
-            TimeFormat::MJD => self.epoch + 2400000.5, // Convert MJD to JD
-        }
+        self.day + self.fraction
     }
 
     /// Return the time as an MJD.
@@ -449,12 +564,70 @@ impl Time {
     ///
     /// * `f64` - The time as an MJD.
     pub fn mjd(&self) -> f64 {
+        (self.day - 2400000.0) + (self.fraction - 0.5)
+    }
+
+    /// Return the time as a decimal year.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The year and the elapsed fraction of it.
+    pub fn decimal_year(&self) -> f64 {
+        julian_to_decimal_year(self.day, self.fraction)
+    }
+
+    /// Return the time as Unix seconds, counted in the timescale of the time object.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The seconds since 1970-01-01T00:00:00, with 86400 seconds to every day.
+    pub fn unix(&self) -> f64 {
+        julian_to_unix(self.day, self.fraction)
+    }
+
+    /// Return the time as GPS seconds, counted in the timescale of the time object
+    /// (usually GPS).
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The seconds since 1980-01-06T00:00:00.
+    pub fn gps_seconds(&self) -> f64 {
+        julian_to_gps_seconds(self.day, self.fraction)
+    }
+
+    /// Return the epoch in the format of the time object.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The epoch as a JD, MJD, decimal year, or Unix or GPS seconds.
+    pub fn epoch(&self) -> f64 {
         match self.format {
-            TimeFormat::JD => self.epoch - 2400000.5, // Convert JD to MJD
-            TimeFormat::MJD => self.epoch,
+            TimeFormat::JD => self.jd(),
+            TimeFormat::MJD => self.mjd(),
+            TimeFormat::DecimalYear => self.decimal_year(),
+            TimeFormat::Unix => self.unix(),
+            TimeFormat::GPS => self.gps_seconds(),
         }
     }
 
+    /// Return the time as a two-part JD, without loss of precision.
+    ///
+    /// # Returns
+    ///
+    /// * `(f64, f64)` - The whole number of days and the fraction of a day.
+    pub fn jd_parts(&self) -> (f64, f64) {
+        (self.day, self.fraction)
+    }
+
+    /// Return the time as a two-part MJD, without loss of precision.
+    ///
+    /// # Returns
+    ///
+    /// * `(f64, f64)` - The whole number of days and the fraction of a day.
+    pub fn mjd_parts(&self) -> (f64, f64) {
+        normalize_two_part(self.day - 2400000.0, self.fraction - 0.5)
+    }
+
 
     /// Change the timescale of the time object.
     ///
@@ -466,12 +639,87 @@ impl Time {
     ///
     /// * `&mut Self` - A mutable reference to the time object, with the timescale changed.
     pub fn change_timescale(&mut self, timescale: TimeScale) -> &mut Self {
-        match timescale {
-            TimeScale::UTC => self.to_utc(),
-            TimeScale::TDB => self.to_tdb(),
-            TimeScale::TT => self.to_tt(),
-            TimeScale::TAI => self.to_tai(),
+        if self.timescale == timescale {
+            return self;
         }
+
+        // every conversion goes through TT
+        let (day, fraction) = (self.day, self.fraction);
+        let (day, fraction) = match self.timescale {
+            TimeScale::TT => (day, fraction),
+            TimeScale::TAI => tai_to_tt(day, fraction),
+            TimeScale::UTC => utc_to_tt(day, fraction),
+            TimeScale::TDB => tdb_to_tt(day, fraction),
+            TimeScale::TCG => tcg_to_tt(day, fraction),
+            TimeScale::TCB => {
+                let (day, fraction) = tcb_to_tdb(day, fraction);
+                tdb_to_tt(day, fraction)
+            },
+            TimeScale::UT1 => {
+                let (day, fraction) = ut1_to_utc(day, fraction);
+                utc_to_tt(day, fraction)
+            },
+            TimeScale::GPS => {
+                let (day, fraction) = gps_to_tai(day, fraction);
+                tai_to_tt(day, fraction)
+            },
+        };
+
+        (self.day, self.fraction) = match timescale {
+            TimeScale::TT => (day, fraction),
+            TimeScale::TAI => tt_to_tai(day, fraction),
+            TimeScale::UTC => tt_to_utc(day, fraction),
+            TimeScale::TDB => tt_to_tdb(day, fraction),
+            TimeScale::TCG => tt_to_tcg(day, fraction),
+            TimeScale::TCB => {
+                let (day, fraction) = tt_to_tdb(day, fraction);
+                tdb_to_tcb(day, fraction)
+            },
+            TimeScale::UT1 => {
+                let (day, fraction) = tt_to_utc(day, fraction);
+                utc_to_ut1(day, fraction)
+            },
+            TimeScale::GPS => {
+                let (day, fraction) = tt_to_tai(day, fraction);
+                tai_to_gps(day, fraction)
+            },
+        };
+        self.timescale = timescale;
+        self
+    }
+
+    /// Calculate the Earth rotation angle (IAU 2000) at the time.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The Earth rotation angle (radians), in [0, 2π).
+    pub fn earth_rotation_angle(&self) -> f64 {
+        let ut1 = self.ut1();
+        calc_earth_rotation_angle(ut1.day, ut1.fraction)
+    }
+
+    /// Calculate Greenwich mean sidereal time (IAU 2006) at the time.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - Greenwich mean sidereal time (radians), in [0, 2π).
+    pub fn gmst(&self) -> f64 {
+        let ut1 = self.ut1();
+        let tt = self.tt();
+        calc_gmst(ut1.day, ut1.fraction, tt.day, tt.fraction)
+    }
+
+    /// Calculate local mean sidereal time at the time.
+    ///
+    /// # Arguments
+    ///
+    /// * `lon` - The east longitude of the observer (radians).
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - Local mean sidereal time (radians), in [0, 2π).
+    pub fn local_sidereal_time(&self, lon: f64) -> f64 {
+        (self.gmst() + lon).rem_euclid(std::f64::consts::TAU)
     }
 
     /// Convert the time to a human-readable calendar date.
@@ -483,25 +731,53 @@ impl Time {
         jd_to_calendar(&self.utc().jd())
     }
 
-    /// Convert the time to an ISO 8601 string.
+    /// Convert the time to an ISO 8601 string in UTC, to the millisecond.
     ///
     /// # Returns
     ///
     /// * `String` - A string representing the time in ISO 8601 format.
     pub fn iso(&self) -> String {
-        // First ensure we're working with UTC time
-        let utc_time = self.clone().utc();
-        
-        // Convert JD to DateTime<Utc>
-        let days_since_epoch = utc_time.jd() - 2440587.5; // JD of Unix epoch (1970-01-01)
-        let seconds_since_epoch = (days_since_epoch * 86400.0) as i64;
-        
-        // Create DateTime from timestamp
-        let dt: DateTime<Utc> = Utc.timestamp_opt(seconds_since_epoch, 0)
-            .unwrap();  // Safe to unwrap as our JD calculations are valid
-            
-        // Format as ISO string
-        dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        format!("{}Z", self.utc().format_iso(3))
+    }
+
+    /// Format the time, in its own timescale, as an ISO 8601 string.
+    ///
+    /// # Arguments
+    ///
+    /// * `precision` - The number of decimal places of the seconds (at most 12).
+    ///
+    /// # Returns
+    ///
+    /// * `String` - A string of the form "2024-12-11T12:34:56.789".
+    pub fn format_iso(&self, precision: usize) -> String {
+        julian_to_iso(self.day, self.fraction, precision)
+    }
+
+    /// Format the time, in its own timescale, as a calendar date with a fractional day, as
+    /// used in the MPC 80-column observation format.
+    ///
+    /// # Arguments
+    ///
+    /// * `precision` - The number of decimal places of the day (at most 12).
+    ///
+    /// # Returns
+    ///
+    /// * `String` - A string of the form "2024 12 11.52414".
+    pub fn format_calendar(&self, precision: usize) -> String {
+        julian_to_calendar_date(self.day, self.fraction, precision)
+    }
+
+    /// Format the time, in its own timescale, as an MPC packed date.
+    ///
+    /// # Arguments
+    ///
+    /// * `precision` - The number of digits of the fraction of the day (at most 12).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, TimeError>` - The packed date, or an error for years outside 1000-3599.
+    pub fn format_mpc_packed(&self, precision: usize) -> Result<String, TimeError> {
+        julian_to_mpc_packed(self.day, self.fraction, precision)
     }
 
 }
@@ -510,7 +786,7 @@ impl Time {
 /// Implement the `Display` trait for `Time`.
 impl std::fmt::Display for Time {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{} {} {}", self.epoch, self.timescale, self.format)
+        write!(f, "{} {} {}", self.epoch(), self.timescale, self.format)
     }
 }
 
@@ -518,11 +794,13 @@ impl std::fmt::Display for Time {
 impl Sub<&Time> for &Time {
     type Output = f64;
 
+    /// The difference between two times in days. The whole days and the fractions are
+    /// differenced separately, so nearby times keep their full precision.
     fn sub(self, other: &Time) -> f64 {
         if self.timescale != other.timescale {
             panic!("Cannot subtract timescales: {} and {}", self.timescale, other.timescale);
         }
-        self.epoch - other.epoch
+        (self.day - other.day) + (self.fraction - other.fraction)
     }
 }
 
@@ -530,11 +808,7 @@ impl Sub<f64> for Time {
     type Output = Time;
 
     fn sub(self, dt: f64) -> Time {
-        Time {
-            epoch: self.epoch - dt,
-            timescale: self.timescale.clone(),
-            format: self.format.clone(),
-        }
+        self + (-dt)
     }
 
 }
@@ -542,12 +816,9 @@ impl Sub<f64> for Time {
 impl Add<f64> for Time {
     type Output = Time;
 
-    fn add(self, dt: f64) -> Time {
-        Time {
-            epoch: self.epoch + dt,
-            timescale: self.timescale.clone(),
-            format: self.format.clone(),
-        }
+    fn add(mut self, dt: f64) -> Time {
+        self += dt;
+        self
     }
 
 }
@@ -555,10 +826,14 @@ impl Add<f64> for Time {
 
 impl AddAssign<f64> for Time {
     fn add_assign(&mut self, dt: f64) {
-        self.epoch += dt;
+        // add the whole days and the fraction separately, so that a small dt isn't rounded away
+        let whole = dt.trunc();
+        (self.day, self.fraction) = normalize_two_part(self.day + whole, self.fraction + (dt - whole));
     }
 }
 
-
-
-
+impl SubAssign<f64> for Time {
+    fn sub_assign(&mut self, dt: f64) {
+        *self += -dt;
+    }
+}
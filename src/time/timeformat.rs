@@ -1,42 +1,65 @@
 use serde::{Serialize, Deserialize};
 
+use crate::errors::TimeError;
+use crate::utils::find_closest_match;
 
+/// How the epoch of a `Time` is presented. The epoch is always held as a two-part JD.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[derive(Default)]
 pub enum TimeFormat {
     #[default]
     JD,
     MJD,
+    /// The year and the elapsed fraction of it, e.g. 2024.5
+    DecimalYear,
+    /// Seconds since 1970-01-01T00:00:00, with 86400 seconds to every day
+    Unix,
+    /// Seconds since 1980-01-06T00:00:00, the start of GPS time
+    GPS,
 }
 
 impl TimeFormat {
 
     pub fn variants() -> &'static [&'static str] {
-        &["JD", "MJD"]
-    }
-
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s {
-            "JD" => Some(TimeFormat::JD),
-            "MJD" => Some(TimeFormat::MJD),
-            _ => None,
-        }
+        &["JD", "MJD", "DECIMALYEAR", "UNIX", "GPS"]
     }
 
     pub fn to_str(&self) -> &str {
         match self {
             TimeFormat::JD => "JD",
             TimeFormat::MJD => "MJD",
+            TimeFormat::DecimalYear => "DECIMALYEAR",
+            TimeFormat::Unix => "UNIX",
+            TimeFormat::GPS => "GPS",
         }
     }
 }
 
-impl std::fmt::Display for TimeFormat {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            TimeFormat::JD => write!(f, "JD"),
-            TimeFormat::MJD => write!(f, "MJD"),
+impl std::str::FromStr for TimeFormat {
+    type Err = TimeError;
+
+    /// Parse a time format, case-insensitively. Unknown formats return an error with a suggestion.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "JD" => Ok(TimeFormat::JD),
+            "MJD" => Ok(TimeFormat::MJD),
+            "DECIMALYEAR" => Ok(TimeFormat::DecimalYear),
+            "UNIX" => Ok(TimeFormat::Unix),
+            "GPS" => Ok(TimeFormat::GPS),
+            _ => {
+                let suggestion = find_closest_match(
+                    &s.to_uppercase(),
+                    TimeFormat::variants()
+                ).map(|s| format!("Did you mean '{}'?", s.to_lowercase()))
+                 .unwrap_or_default();
+                Err(TimeError::InvalidTimeFormat(format!("'{}'. {}", s, suggestion)))
+            }
         }
     }
 }
 
+impl std::fmt::Display for TimeFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_str())
+    }
+}
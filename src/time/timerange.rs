@@ -0,0 +1,216 @@
+use std::f64::consts::TAU;
+
+use crate::time::Time;
+use crate::time::timescale::TimeScale;
+use crate::time::sidereal::EARTH_ROTATION_RATE;
+use crate::constants::ARCSEC_TO_RAD;
+
+/// The length of a mean sidereal day in UT1 days, including the precession of the equinox.
+const SIDEREAL_DAY: f64 = 1.0 / (EARTH_ROTATION_RATE + 4612.156534 * ARCSEC_TO_RAD / TAU / 36525.0);
+
+/// Evenly spaced epochs. The spacing is uniform in the timescale of the grid, and each epoch is
+/// returned in the timescale and format of the start time. `TimeRange` is an iterator, like
+/// `std::ops::Range`, and can be walked from either end.
+///
+/// # Example
+///
+/// ```
+/// use spacerocks::time::{Time, TimeRange};
+///
+/// let start = Time::new(2460000.5, "utc", "jd").unwrap();
+/// let stop = Time::new(2460001.5, "utc", "jd").unwrap();
+/// let epochs: Vec<Time> = TimeRange::from_step(&start, &stop, 0.25).unwrap().collect();
+/// assert_eq!(epochs.len(), 5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeRange {
+    /// The first epoch, in the timescale of the grid
+    start: Time,
+    /// The spacing of the epochs (days)
+    step: f64,
+    /// The timescale the epochs are returned in
+    timescale: TimeScale,
+    front: usize,
+    back: usize,
+}
+
+impl TimeRange {
+
+    /// Create a new TimeRange from a first epoch, a spacing and a number of epochs.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first epoch. The spacing is uniform in its timescale.
+    /// * `step` - The spacing of the epochs (days).
+    /// * `count` - The number of epochs.
+    ///
+    /// # Returns
+    ///
+    /// * `TimeRange` - The range.
+    pub fn new(start: &Time, step: f64, count: usize) -> Self {
+        TimeRange { start: start.clone(), step, timescale: start.timescale.clone(), front: 0, back: count }
+    }
+
+    /// Create a new TimeRange stepping from one time towards another. The stop time is included
+    /// if it falls on the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first epoch. The spacing is uniform in its timescale.
+    /// * `stop` - The last epoch, which may be in any timescale.
+    /// * `step` - The spacing of the epochs (days). It is negative to step backwards.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TimeRange, Box<dyn std::error::Error>>` - The range.
+    pub fn from_step(start: &Time, stop: &Time, step: f64) -> Result<Self, Box<dyn std::error::Error>> {
+        if !step.is_finite() || step == 0.0 {
+            return Err(format!("Invalid step {} for a time range", step).into());
+        }
+        let span = &stop.in_timescale(start.timescale.clone()) - start;
+        let steps = span / step;
+        if steps < -1e-9 {
+            return Err("The step of a time range must point from the start to the stop".into());
+        }
+        // allow for rounding when the stop falls on the grid
+        let count = (steps + 1e-9).floor() as usize + 1;
+        Ok(TimeRange::new(start, step, count))
+    }
+
+    /// Create a new TimeRange with a number of epochs spread evenly from one time to another,
+    /// both included.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first epoch. The spacing is uniform in its timescale.
+    /// * `stop` - The last epoch, which may be in any timescale.
+    /// * `count` - The number of epochs.
+    ///
+    /// # Returns
+    ///
+    /// * `TimeRange` - The range.
+    pub fn from_count(start: &Time, stop: &Time, count: usize) -> Self {
+        let span = &stop.in_timescale(start.timescale.clone()) - start;
+        let step = if count > 1 { span / (count - 1) as f64 } else { 0.0 };
+        TimeRange::new(start, step, count)
+    }
+
+    /// Create a new TimeRange of the UTC midnights from one time to another, both included.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the range. The epochs are returned in its timescale and format.
+    /// * `stop` - The end of the range, which may be in any timescale.
+    ///
+    /// # Returns
+    ///
+    /// * `TimeRange` - The range.
+    pub fn utc_midnights(start: &Time, stop: &Time) -> Self {
+        let utc = start.utc();
+        // UTC midnight falls half way through a Julian day
+        let day = if utc.fraction <= 0.5 { utc.day } else { utc.day + 1.0 };
+        let mut first = utc.clone();
+        (first.day, first.fraction) = (day, 0.5);
+
+        let span = &stop.utc() - &first;
+        let count = if span < 0.0 { 0 } else { span.floor() as usize + 1 };
+        TimeRange { start: first, step: 1.0, timescale: start.timescale.clone(), front: 0, back: count }
+    }
+
+    /// Create a new TimeRange at a fixed local mean sidereal time on each day, one mean
+    /// sidereal day apart, from one time to another. This places a meridian at the same
+    /// right ascension at every epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The start of the range. The epochs are returned in its timescale and format.
+    /// * `stop` - The end of the range, which may be in any timescale.
+    /// * `lon` - The east longitude of the observer (radians).
+    /// * `lst` - The local mean sidereal time of the epochs (radians).
+    ///
+    /// # Returns
+    ///
+    /// * `TimeRange` - The range.
+    pub fn sidereal(start: &Time, stop: &Time, lon: f64, lst: f64) -> Self {
+        let mut first = start.ut1();
+        // two passes, as GMST drifts slightly from the rotation rate
+        for _ in 0..2 {
+            let offset = (lst - first.local_sidereal_time(lon) + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+            first += offset / TAU * SIDEREAL_DAY;
+        }
+        if &first - &start.ut1() < -1e-9 {
+            first += SIDEREAL_DAY;
+        }
+
+        let span = &stop.ut1() - &first;
+        let count = if span < 0.0 { 0 } else { (span / SIDEREAL_DAY + 1e-9).floor() as usize + 1 };
+        let mut range = TimeRange::new(&first, SIDEREAL_DAY, count);
+        range.timescale = start.timescale.clone();
+        range.start.format = start.format.clone();
+        range
+    }
+
+    /// The spacing of the epochs (days), in the timescale of the grid.
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// The timescale the epochs are returned in.
+    pub fn timescale(&self) -> &TimeScale {
+        &self.timescale
+    }
+
+    /// Get an epoch of the range, counted from the front of what remains.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the epoch.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Time>` - The epoch, or None if the index is past the end.
+    pub fn get(&self, index: usize) -> Option<Time> {
+        let index = self.front.checked_add(index)?;
+        (index < self.back).then(|| self.epoch(index))
+    }
+
+    fn epoch(&self, index: usize) -> Time {
+        let mut epoch = self.start.clone();
+        epoch += index as f64 * self.step;
+        epoch.in_timescale(self.timescale.clone())
+    }
+}
+
+impl Iterator for TimeRange {
+    type Item = Time;
+
+    fn next(&mut self) -> Option<Time> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.epoch(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back.saturating_sub(self.front);
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Time> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for TimeRange {
+    fn next_back(&mut self) -> Option<Time> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.epoch(self.back))
+    }
+}
+
+impl ExactSizeIterator for TimeRange {}
@@ -1,19 +1,26 @@
 use serde::{Serialize, Deserialize};
 
+use crate::errors::TimeError;
+use crate::utils::find_closest_match;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[derive(Default)]
 pub enum TimeScale {
     #[default]
     UTC,
     TDB,
-    TT, 
+    TT,
     TAI,
+    TCB,
+    TCG,
+    UT1,
+    GPS,
 }
 
 impl TimeScale {
 
     pub fn variants() -> &'static [&'static str] {
-        &["UTC", "TDB", "TT", "TAI"] 
+        &["UTC", "TDB", "TT", "TAI", "TCB", "TCG", "UT1", "GPS"]
     }
 
     pub fn to_str(&self) -> &str {
@@ -22,18 +29,42 @@ impl TimeScale {
             TimeScale::TDB => "TDB",
             TimeScale::TT => "TT",
             TimeScale::TAI => "TAI",
+            TimeScale::TCB => "TCB",
+            TimeScale::TCG => "TCG",
+            TimeScale::UT1 => "UT1",
+            TimeScale::GPS => "GPS",
         }
     }
 }
 
-impl std::fmt::Display for TimeScale {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            TimeScale::UTC => write!(f, "UTC"),
-            TimeScale::TDB => write!(f, "TDB"),
-            TimeScale::TT => write!(f, "TT"),
-            TimeScale::TAI => write!(f, "TAI"),
+impl std::str::FromStr for TimeScale {
+    type Err = TimeError;
+
+    /// Parse a timescale, case-insensitively. Unknown timescales return an error with a suggestion.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "UTC" => Ok(TimeScale::UTC),
+            "TDB" => Ok(TimeScale::TDB),
+            "TT" => Ok(TimeScale::TT),
+            "TAI" => Ok(TimeScale::TAI),
+            "TCB" => Ok(TimeScale::TCB),
+            "TCG" => Ok(TimeScale::TCG),
+            "UT1" => Ok(TimeScale::UT1),
+            "GPS" => Ok(TimeScale::GPS),
+            _ => {
+                let suggestion = find_closest_match(
+                    &s.to_uppercase(),
+                    TimeScale::variants()
+                ).map(|s| format!("Did you mean '{}'?", s.to_lowercase()))
+                 .unwrap_or_default();
+                Err(TimeError::InvalidTimeScale(format!("'{}'. {}", s, suggestion)))
+            }
         }
     }
 }
 
+impl std::fmt::Display for TimeScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_str())
+    }
+}
@@ -3,6 +3,7 @@ use crate::StateVector;
 use crate::SpaceRock;
 use crate::Observer;
 
+#[deprecated(since = "0.2.0", note = "use solve_for_light_time, which propagates the rock and accounts for the motion of the origin")]
 pub fn correct_for_ltt(rock: &SpaceRock, observer: &Observer) -> StateVector {
     // calculates the observer-centric state vector of a rock, accounting for light-time travel
 
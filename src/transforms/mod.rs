@@ -5,8 +5,12 @@ pub mod calc_conic_anomaly_from_mean_anomaly;
     pub use self::calc_conic_anomaly_from_mean_anomaly::calc_conic_anomaly_from_mean_anomaly;
 
 pub mod correct_for_ltt;
+    #[allow(deprecated)]
     pub use self::correct_for_ltt::correct_for_ltt;
 
+pub mod solve_for_light_time;
+    pub use self::solve_for_light_time::solve_for_light_time;
+
 pub mod calc_mean_anomaly_from_conic_anomaly;
     pub use self::calc_mean_anomaly_from_conic_anomaly::calc_mean_anomaly_from_conic_anomaly;
 
@@ -0,0 +1,49 @@
+use nalgebra::Vector3;
+
+use crate::constants::SPEED_OF_LIGHT;
+use crate::structs::StateVector;
+use crate::{Observer, SpaceRock, Time};
+
+/// Solve for the light travel time from a rock to an observer, by propagating the rock back to the
+/// epoch at which the light was emitted until the travel time converges. The propagation is left to
+/// the caller, so that the same solver serves two-body orbits, n-body simulations and ephemerides.
+///
+/// The rock and the observer share an origin, which may move. The light travels in the barycentric
+/// frame, so the origin's own motion over the light travel time is taken out using its barycentric
+/// velocity.
+///
+/// # Arguments
+///
+/// * `rock` - The rock, at the epoch of the observation
+/// * `observer` - The observer, at the epoch of the observation
+/// * `origin_velocity` - The barycentric velocity of the common origin (au/day), zero for the SSB
+/// * `propagate` - Gives the state of the rock at an epoch, in the origin and reference plane of the observer
+/// * `tol` - The tolerance on the light travel time (days)
+/// * `max_iter` - The maximum number of iterations
+///
+/// # Returns
+///
+/// * The position and velocity of the rock at emission relative to the observer at reception, and the light travel time (days)
+pub fn solve_for_light_time<F>(rock: &SpaceRock, observer: &Observer, origin_velocity: &Vector3<f64>, mut propagate: F, tol: f64, max_iter: usize) -> Result<(StateVector, f64), Box<dyn std::error::Error>>
+where
+    F: FnMut(&Time) -> Result<SpaceRock, Box<dyn std::error::Error>>,
+{
+    let epoch = observer.epoch();
+    let mut ltt = (rock.position - observer.position()).norm() / SPEED_OF_LIGHT;
+
+    for _ in 0..max_iter {
+        let emitted = propagate(&(epoch.clone() - ltt))?;
+        let d_pos = emitted.position - observer.position() - origin_velocity * ltt;
+        let new_ltt = d_pos.norm() / SPEED_OF_LIGHT;
+
+        if (new_ltt - ltt).abs() < tol {
+            let emitted = propagate(&(epoch.clone() - new_ltt))?;
+            let d_pos = emitted.position - observer.position() - origin_velocity * new_ltt;
+            let d_vel = emitted.velocity - observer.velocity();
+            return Ok((StateVector::new(d_pos, d_vel), new_ltt));
+        }
+        ltt = new_ltt;
+    }
+
+    Err(format!("Light travel time to {} did not converge", rock.name).into())
+}
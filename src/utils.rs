@@ -1,3 +1,6 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+use std::f64::consts::PI;
 use strsim::damerau_levenshtein;
 
 pub fn find_closest_match<'a>(input: &'a str, valid_options: &'a [&'a str]) -> Option<&'a str> {
@@ -7,4 +10,11 @@ pub fn find_closest_match<'a>(input: &'a str, valid_options: &'a [&'a str]) -> O
             damerau_levenshtein(&input.to_uppercase(), &a)
         })
         .copied()
-}
\ No newline at end of file
+}
+
+/// Draw a standard normal deviate with the Box-Muller transform.
+pub fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
@@ -0,0 +1,8 @@
+use spacerocks::{Observer, SpaceRock, Time};
+use spacerocks::observing::Observatory;
+
+/// An observer at 1 au from the SSB, on a near-circular orbit, which needs no kernels.
+pub fn craft(epoch: &Time, reference_plane: &str) -> Observer {
+    let craft = SpaceRock::from_xyz("craft", 1.0, 0.0, 0.0, 0.0, 0.017, 0.0, epoch.clone(), reference_plane, "SSB").unwrap();
+    Observer { spacerock: craft.clone(), observatory: Observatory::from_spacerock(craft) }
+}
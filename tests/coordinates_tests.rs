@@ -0,0 +1,148 @@
+use spacerocks::{SpaceRock, ReferencePlane, Time};
+use spacerocks::coordinates::{calc_mean_obliquity, calc_nutation, calc_precession_matrix, calc_precession_nutation_matrix, calc_cio_locator, calc_celestial_to_intermediate_matrix, calc_celestial_to_terrestrial_matrix, calc_polar_motion_matrix, calc_tio_locator, calc_itrs_to_gcrs_state};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Matrix3;
+
+    fn assert_matrix_close(a: &Matrix3<f64>, b: &Matrix3<f64>, tol: f64) {
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((a[(i, j)] - b[(i, j)]).abs() < tol, "element ({}, {}): {} vs {}", i, j, a[(i, j)], b[(i, j)]);
+            }
+        }
+    }
+
+    // reference values from the SOFA test suite (t_sofa_c.c)
+    #[test]
+    fn test_precession_nutation_against_sofa() {
+        assert!((calc_mean_obliquity(2400000.5, 54388.0) - 0.4090749229387258204).abs() < 1e-14);
+
+        let (dpsi, deps) = calc_nutation(2400000.5, 53736.0);
+        assert!((dpsi - -0.9630912025820308797e-5).abs() < 1e-13);
+        assert!((deps - 0.4063238496887249798e-4).abs() < 1e-13);
+
+        let expected = Matrix3::new(
+            0.9999995505176007047, 0.8695404617348208406e-3, 0.3779735201865589104e-3,
+            -0.8695404723772031414e-3, 0.9999996219496027161, -0.1361752497080270143e-6,
+            -0.3779734956678254024e-3, -0.1924880847894457113e-6, 0.9999999285679971958,
+        );
+        assert_matrix_close(&calc_precession_matrix(2400000.5, 50123.9999), &expected, 1e-12);
+
+        let expected = Matrix3::new(
+            0.9999995832794205484, 0.8372382772630962111e-3, 0.3639684771140623099e-3,
+            -0.8372533744743683605e-3, 0.9999996486492861646, 0.4132905944611019498e-4,
+            -0.3639337469629464969e-3, -0.4163377605910663999e-4, 0.9999999329094260057,
+        );
+        assert_matrix_close(&calc_precession_nutation_matrix(2400000.5, 50123.9999), &expected, 1e-12);
+
+        let s = calc_cio_locator(2400000.5, 53736.0, 0.5791308486706011000e-3, 0.4020579816732961219e-4);
+        assert!((s - -0.1220032213076463117e-7).abs() < 1e-18);
+
+        let expected = Matrix3::new(
+            0.9999998323037159379, 0.5581121329587613787e-9, -0.5791308487740529749e-3,
+            -0.2384253169452306581e-7, 0.9999999991917467827, -0.4020579392895682558e-4,
+            0.5791308482835292617e-3, 0.4020580099454020310e-4, 0.9999998314954628695,
+        );
+        assert_matrix_close(&calc_celestial_to_intermediate_matrix(2400000.5, 53736.0), &expected, 1e-12);
+
+        assert!((calc_tio_locator(2400000.5, 52541.0) - -0.6216698469981019309e-11).abs() < 1e-22);
+
+        let expected = Matrix3::new(
+            0.9999999999999674721, -0.1367174580728846989e-10, 0.2550602379999972345e-6,
+            0.1414624947957029801e-10, 0.9999999999982695317, -0.1860359246998866389e-5,
+            -0.2550602379741215021e-6, 0.1860359247002414021e-5, 0.9999999999982370039,
+        );
+        assert_matrix_close(&calc_polar_motion_matrix(2.55060238e-7, 1.860359247e-6, -0.1367174580728891460e-10), &expected, 1e-15);
+
+        let expected = Matrix3::new(
+            -0.1810332128305897282, 0.9834769806938592296, 0.6555550962998436505e-4,
+            -0.9834768134136214897, -0.1810332203649130832, 0.5749800844905594110e-3,
+            0.5773474024748545878e-3, 0.3961816829632690581e-4, 0.9999998325501747785,
+        );
+        let c2t = calc_celestial_to_terrestrial_matrix(2400000.5, 53736.0, 2400000.5, 53736.0, 2.55060238e-7, 1.860359247e-6);
+        assert_matrix_close(&c2t, &expected, 1e-12);
+    }
+
+    #[test]
+    fn test_frames_of_date() {
+        let epoch = Time::new(2460000.5, "tt", "jd").unwrap();
+        let t = epoch.clone();
+
+        // the inertial frames do not need an epoch, the frames of date do
+        assert!(ReferencePlane::from_str("TOD").is_err());
+        assert_eq!(ReferencePlane::from_str_at("J2000", &epoch).unwrap(), ReferencePlane::J2000);
+        let tod = ReferencePlane::from_str_at("tod", &epoch).unwrap();
+        assert_eq!(tod, ReferencePlane::TrueOfDate(t.clone()));
+        assert_eq!(tod.to_string(), "TOD");
+        assert_eq!(tod.epoch(), Some(&t));
+
+        let mod_matrix = ReferencePlane::MeanOfDate(t.clone()).get_rotation_matrix();
+        let tod_matrix = tod.get_rotation_matrix();
+        let cirs_matrix = ReferencePlane::CIRS(t.clone()).get_rotation_matrix();
+        let tirs_matrix = ReferencePlane::TIRS(t.clone()).get_rotation_matrix();
+
+        // the mean and true equinoxes differ by the nutation in right ascension
+        let (dpsi, _) = calc_nutation(t.day, t.fraction);
+        let eps = calc_mean_obliquity(t.day, t.fraction);
+        let offset = (tod_matrix * mod_matrix.transpose())[(1, 0)];
+        assert!((offset.abs() - (dpsi * eps.cos()).abs()).abs() < 1e-8);
+
+        // CIRS and TIRS share the pole of the true equator of date
+        for j in 0..3 {
+            assert!((cirs_matrix[(2, j)] - tod_matrix[(2, j)]).abs() < 1e-15);
+            assert!((tirs_matrix[(2, j)] - tod_matrix[(2, j)]).abs() < 1e-15);
+        }
+
+        // TIRS is the true equator of date turned by the Greenwich sidereal time
+        let gst = t.gmst() + dpsi * eps.cos();
+        let (s, c) = gst.sin_cos();
+        let rz = Matrix3::new(c, s, 0.0, -s, c, 0.0, 0.0, 0.0, 1.0);
+        assert_matrix_close(&tirs_matrix, &(rz * tod_matrix), 1e-8);
+
+        // the true of date matrix is the precession matrix followed by nutation
+        assert_matrix_close(&tod_matrix, &calc_precession_nutation_matrix(t.day, t.fraction), 1e-15);
+
+        // at J2000 the ecliptic of date is the J2000 ecliptic, up to the frame bias
+        let j2000 = Time::new(2451545.0, "tt", "jd").unwrap();
+        let eclipdate = ReferencePlane::EclipticOfDate(j2000).get_rotation_matrix();
+        assert_matrix_close(&eclipdate, &ReferencePlane::ECLIPJ2000.get_rotation_matrix(), 1e-6);
+    }
+
+    #[test]
+    fn test_change_to_frame_of_date() {
+        let epoch = Time::new(2460000.5, "utc", "jd").unwrap();
+        let mut rock = SpaceRock::from_xyz("rock", 1.2, -0.4, 0.3, 0.001, 0.015, -0.002, epoch.clone(), "J2000", "SSB").unwrap();
+        let original = rock.clone();
+
+        for plane in ["MOD", "TOD", "CIRS", "TIRS", "ECLIPDATE", "ECLIPJ2000", "J2000"] {
+            rock.change_reference_plane(plane).unwrap();
+            assert_eq!(rock.reference_plane.as_str(), plane);
+            assert!((rock.position.norm() - original.position.norm()).abs() < 1e-14);
+        }
+        assert!((rock.position - original.position).norm() < 1e-14);
+        assert!((rock.velocity - original.velocity).norm() < 1e-14);
+
+        // the frame of date is fixed at the epoch of the rock
+        let rock = SpaceRock::from_xyz("rock", 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch.clone(), "TOD", "SSB").unwrap();
+        assert_eq!(rock.reference_plane, ReferencePlane::TrueOfDate(epoch));
+    }
+
+    #[test]
+    fn test_velocity_in_turning_frame() {
+        // a point at rest in the ITRS moves with the Earth in J2000
+        let epoch = Time::new(2460000.5, "utc", "jd").unwrap();
+        let mut rock = SpaceRock::from_xyz("station", 3e-5, -2e-5, 2.5e-5, 0.0, 0.0, 0.0, epoch.clone(), "ITRS", "SSB").unwrap();
+        let (position, velocity) = calc_itrs_to_gcrs_state(&epoch, &rock.position);
+        rock.change_reference_plane("J2000").unwrap();
+        assert!((rock.position - position).norm() < 1e-18);
+        assert!((rock.velocity - velocity).norm() < 1e-18);
+
+        // and is at rest again in TIRS and the ITRS, which differ only by polar motion
+        rock.change_reference_plane("TIRS").unwrap();
+        assert!(rock.velocity.norm() < 1e-9 * velocity.norm());
+        rock.change_reference_plane("ITRS").unwrap();
+        assert!(rock.velocity.norm() < 1e-12 * velocity.norm());
+    }
+}
@@ -0,0 +1,299 @@
+use spacerocks::SpaceRock;
+use spacerocks::Simulation;
+use spacerocks::Time;
+use spacerocks::constants::{GRAVITATIONAL_CONSTANT, KM_TO_AU};
+use spacerocks::DynamicalClass;
+use spacerocks::dynamics::{calc_impact_probability, sample_virtual_asteroids};
+use spacerocks::transforms::calc_true_anomaly_from_mean_anomaly;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn epoch() -> Time {
+        Time::new(2451545.0, "TDB", "JD").unwrap()
+    }
+
+    fn rock(name: &str, q: f64, e: f64, inc: f64, arg: f64, node: f64, true_anomaly: f64) -> SpaceRock {
+        SpaceRock::from_kepler(name, q, e, inc, arg, node, true_anomaly, epoch(), "ECLIPJ2000", "SUN").unwrap()
+    }
+
+    // brute force the minimum distance between two orbits by dense sampling
+    fn brute_force_moid(rock_1: &SpaceRock, rock_2: &SpaceRock, n: usize) -> f64 {
+        let sample = |rock: &SpaceRock| -> Vec<nalgebra::Vector3<f64>> {
+            let e = rock.e();
+            let nu_max = if e < 1.0 { PI } else { (-1.0 / e).acos() * 0.999 };
+            (0..n).map(|idx| {
+                let nu = -nu_max + 2.0 * nu_max * (idx as f64) / (n as f64);
+                let r = SpaceRock::from_kepler("s", rock.q(), e, rock.inc(), rock.arg(), rock.node(), nu, epoch(), "ECLIPJ2000", "SUN").unwrap();
+                r.position
+            }).collect()
+        };
+        let p1 = sample(rock_1);
+        let p2 = sample(rock_2);
+        let mut best = f64::INFINITY;
+        for a in &p1 {
+            for b in &p2 {
+                best = best.min((a - b).norm());
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_moid_coplanar_circles() {
+        let inner = rock("inner", 1.0, 0.0, 0.0, 0.0, 0.0, 0.3);
+        let outer = rock("outer", 1.5, 0.0, 0.0, 0.0, 0.0, 2.0);
+        let moid = inner.moid(&outer).unwrap();
+        assert!((moid.distance - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_moid_intersecting_orbits() {
+        // two circles of the same radius in different planes cross at the line of nodes
+        let a = rock("a", 1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        let b = rock("b", 1.0, 0.0, 0.4, 0.0, 1.0, 0.0);
+        let moid = a.moid(&b).unwrap();
+        assert!(moid.distance < 1e-8);
+
+        // an eccentric orbit crossing a circle in the same plane
+        let c = rock("c", 0.5, 0.6, 0.0, 0.7, 0.0, 0.0);
+        let moid = a.moid(&c).unwrap();
+        assert!(moid.distance < 1e-8);
+    }
+
+    #[test]
+    fn test_moid_hyperbolic() {
+        let earth_like = rock("earth", 1.0, 0.0167, 0.01, 1.8, 0.3, 0.0);
+        let interstellar = rock("interstellar", 0.25, 1.2, 2.15, 4.2, 0.42, 0.0);
+        let moid = earth_like.moid(&interstellar).unwrap();
+        let brute = brute_force_moid(&earth_like, &interstellar, 1000);
+        assert!(moid.distance <= brute + 1e-9);
+        assert!((moid.distance - brute).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_moid_elliptical() {
+        let a = rock("a", 0.98, 0.0167, 0.01, 1.8, 0.3, 0.0);
+        let b = rock("b", 0.75, 0.19, 0.058, 2.2, 3.5, 1.0);
+        let moid = a.moid(&b).unwrap();
+        let brute = brute_force_moid(&a, &b, 1000);
+        assert!(moid.distance <= brute + 1e-9);
+        assert!((moid.distance - brute).abs() < 1e-3);
+
+        // the reported anomalies should reproduce the distance
+        let pa = SpaceRock::from_kepler("pa", a.q(), a.e(), a.inc(), a.arg(), a.node(), moid.true_anomaly_1, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        let pb = SpaceRock::from_kepler("pb", b.q(), b.e(), b.inc(), b.arg(), b.node(), moid.true_anomaly_2, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        assert!(((pa.position - pb.position).norm() - moid.distance).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_moid_frame_mismatch() {
+        let a = rock("a", 1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        let mut b = rock("b", 1.5, 0.1, 0.1, 0.0, 0.0, 0.0);
+        b.change_reference_plane("J2000").unwrap();
+        assert!(a.moid(&b).is_err());
+    }
+
+    const EARTH_MASS: f64 = 3.0034896149157645e-6;
+
+    // a sun and an earth on a circular orbit, and a rock that passes the earth after about 10 days,
+    // offset perpendicular to the plane by `miss`
+    fn encounter(miss: f64) -> (Simulation, SpaceRock) {
+        let mut sim = Simulation::new(&epoch(), "ECLIPJ2000", "SUN").unwrap();
+
+        let mut sun = SpaceRock::from_xyz("sun", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        sun.set_mass(1.0);
+        sim.add(sun).unwrap();
+
+        let vcirc = (GRAVITATIONAL_CONSTANT * (1.0 + EARTH_MASS)).sqrt();
+        let mut earth = SpaceRock::from_xyz("earth", 1.0, 0.0, 0.0, 0.0, vcirc, 0.0, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        earth.set_mass(EARTH_MASS);
+        sim.add(earth).unwrap();
+
+        let u = 0.002;
+        let rock = SpaceRock::from_xyz("rock", 1.0 - 10.0 * u, 0.0, miss, u, vcirc, 0.0, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        (sim, rock)
+    }
+
+    #[test]
+    fn test_sample_virtual_asteroids() {
+        let (_, rock) = encounter(0.0);
+        let sigmas = [1e-3, 2e-3, 3e-3, 1e-5, 2e-5, 3e-5];
+        let covariance = nalgebra::Matrix6::from_fn(|i, j| if i == j { sigmas[i] * sigmas[i] } else { 0.0 });
+
+        let vas = sample_virtual_asteroids(&rock, &covariance, 4000, Some(42)).unwrap();
+        assert_eq!(vas.len(), 4000);
+        assert_eq!(vas[7].name, "rock_7");
+
+        // the same seed gives the same draws
+        let again = sample_virtual_asteroids(&rock, &covariance, 4000, Some(42)).unwrap();
+        assert_eq!(vas[123].position, again[123].position);
+
+        let n = vas.len() as f64;
+        let mean_x = vas.iter().map(|va| va.position.x - rock.position.x).sum::<f64>() / n;
+        let var_z = vas.iter().map(|va| (va.position.z - rock.position.z).powi(2)).sum::<f64>() / n;
+        let var_vy = vas.iter().map(|va| (va.velocity.y - rock.velocity.y).powi(2)).sum::<f64>() / n;
+        assert!(mean_x.abs() < 5.0 * sigmas[0] / n.sqrt());
+        assert!((var_z.sqrt() / sigmas[2] - 1.0).abs() < 0.05);
+        assert!((var_vy.sqrt() / sigmas[4] - 1.0).abs() < 0.05);
+
+        let singular = nalgebra::Matrix6::zeros();
+        assert!(sample_virtual_asteroids(&rock, &singular, 10, None).is_err());
+    }
+
+    #[test]
+    fn test_impact_probability() {
+        let radius = 6378.137 * KM_TO_AU;
+        let covariance = nalgebra::Matrix6::identity() * 1e-20;
+        let end = epoch() + 30.0;
+
+        // a rock that misses by twice the earth's radius still hits, because of gravitational focusing
+        let (sim, rock) = encounter(2.0 * radius);
+        let vas = sample_virtual_asteroids(&rock, &covariance, 8, Some(1)).unwrap();
+        let result = calc_impact_probability(&sim, &vas, &end, "earth", radius).unwrap();
+        assert_eq!(result.probability, 1.0);
+        assert_eq!(result.n_virtual_asteroids, 8);
+        assert_eq!(result.impactors.len(), 8);
+        for impactor in &result.impactors {
+            assert!(impactor.impact_parameter > radius);
+            assert!(impactor.impact_parameter < impactor.capture_radius);
+            assert!((impactor.epoch.jd() - epoch().jd() - 10.0).abs() < 1.0);
+        }
+
+        // a rock that misses by a wide margin never hits
+        let (sim, rock) = encounter(0.02);
+        let vas = sample_virtual_asteroids(&rock, &covariance, 8, Some(1)).unwrap();
+        let result = calc_impact_probability(&sim, &vas, &end, "earth", radius).unwrap();
+        assert_eq!(result.probability, 0.0);
+        assert!(result.impactors.is_empty());
+
+        assert!(calc_impact_probability(&sim, &[], &end, "earth", radius).is_err());
+    }
+
+    // estimate the collision rate by counting close pairs among random positions on the two orbits
+    fn monte_carlo_collision_probability(rock_1: &SpaceRock, rock_2: &SpaceRock, n: usize, d: f64) -> (f64, f64) {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut sample = |rock: &SpaceRock| -> Vec<(nalgebra::Vector3<f64>, nalgebra::Vector3<f64>)> {
+            (0..n).map(|_| {
+                let mean_anomaly = rng.gen_range(0.0..2.0 * PI);
+                let nu = calc_true_anomaly_from_mean_anomaly(rock.e(), mean_anomaly).unwrap();
+                let r = SpaceRock::from_kepler("s", rock.q(), rock.e(), rock.inc(), rng.gen_range(0.0..2.0 * PI), rng.gen_range(0.0..2.0 * PI), nu, epoch(), "ECLIPJ2000", "SUN").unwrap();
+                (r.position, r.velocity)
+            }).collect()
+        };
+        let s1 = sample(rock_1);
+        let s2 = sample(rock_2);
+
+        let mut speed = 0.0;
+        let mut speed_squared = 0.0;
+        for (x1, v1) in &s1 {
+            for (x2, v2) in &s2 {
+                if (x1 - x2).norm() < d {
+                    let u = (v1 - v2).norm();
+                    speed += u;
+                    speed_squared += u * u;
+                }
+            }
+        }
+        let volume = 4.0 / 3.0 * PI * d.powi(3);
+        let rate = PI * speed / ((n * n) as f64 * volume);
+        (rate * KM_TO_AU * KM_TO_AU * 365.25, speed_squared / speed / KM_TO_AU / 86400.0)
+    }
+
+    #[test]
+    fn test_collision_probability() {
+        let a = rock("a", 2.1, 0.15, 0.12, 0.0, 0.0, 0.0);
+        let b = rock("b", 2.0, 0.3, 0.3, 0.0, 0.0, 0.0);
+
+        let p = a.collision_probability(&b).unwrap();
+        let (p_mc, v_mc) = monte_carlo_collision_probability(&a, &b, 3000, 0.1);
+        assert!((p.intrinsic_probability / p_mc - 1.0).abs() < 0.1);
+        assert!((p.impact_velocity / v_mc - 1.0).abs() < 0.05);
+
+        // main-belt pairs have probabilities of a few 1e-18 km^-2 yr^-1 and impact speeds of a few km/s
+        assert!(p.intrinsic_probability > 1e-19 && p.intrinsic_probability < 1e-16);
+        assert!(p.impact_velocity > 1.0 && p.impact_velocity < 10.0);
+
+        let reversed = b.collision_probability(&a).unwrap();
+        assert!((reversed.intrinsic_probability / p.intrinsic_probability - 1.0).abs() < 1e-12);
+
+        // orbits that never reach the same heliocentric distance can't collide
+        let far = rock("far", 30.0, 0.05, 0.1, 0.0, 0.0, 0.0);
+        assert_eq!(a.collision_probability(&far).unwrap().intrinsic_probability, 0.0);
+
+        let hyperbolic = rock("hyperbolic", 2.0, 1.5, 0.3, 0.0, 0.0, 0.0);
+        assert!(a.collision_probability(&hyperbolic).is_err());
+    }
+
+    #[test]
+    fn test_collision_probability_frame_mismatch() {
+        let a = rock("a", 2.1, 0.15, 0.12, 0.0, 0.0, 0.0);
+        let mut b = rock("b", 2.0, 0.3, 0.3, 0.0, 0.0, 0.0);
+        b.change_reference_plane("J2000").unwrap();
+        assert!(a.collision_probability(&b).is_err());
+        assert!(b.collision_probability(&a).is_err());
+    }
+
+    fn rock_from_a(name: &str, a: f64, e: f64, inc_deg: f64) -> SpaceRock {
+        rock(name, a * (1.0 - e), e, inc_deg.to_radians(), 0.3, 1.1, 0.5)
+    }
+
+    #[test]
+    fn test_tisserand() {
+        // a circular orbit at the planet's distance in the planet's plane has T = 3
+        let jupiter_like = rock_from_a("jupiter_like", 5.20288700, 0.0, 0.0);
+        assert!((jupiter_like.tisserand("jupiter").unwrap() - 3.0).abs() < 1e-9);
+        assert!((jupiter_like.tisserand("Jupiter Barycenter").unwrap() - 3.0).abs() < 1e-9);
+
+        // 67P/Churyumov-Gerasimenko, T_J = 2.75
+        let cg = rock_from_a("67P", 3.4628, 0.6410, 7.04);
+        assert!((cg.tisserand("jupiter").unwrap() - 2.75).abs() < 0.01);
+
+        // the value doesn't depend on the frame the rock is expressed in
+        let mut cg_equatorial = cg.clone();
+        cg_equatorial.change_reference_plane("J2000").unwrap();
+        assert!((cg_equatorial.tisserand("jupiter").unwrap() - cg.tisserand("jupiter").unwrap()).abs() < 1e-12);
+
+        assert!(cg.tisserand("vulcan").is_err());
+    }
+
+    #[test]
+    fn test_dynamical_class() {
+        let cases = [
+            ("Atira", 0.741, 0.322, 25.6, DynamicalClass::Atira),
+            ("Apophis", 0.9224, 0.1914, 3.34, DynamicalClass::Aten),
+            ("Icarus", 1.078, 0.827, 22.8, DynamicalClass::Apollo),
+            ("Encke", 2.22, 0.848, 11.8, DynamicalClass::Apollo),
+            ("Eros", 1.458, 0.2226, 10.8, DynamicalClass::Amor),
+            ("Ceres", 2.77, 0.0785, 10.6, DynamicalClass::MainBelt),
+            ("Hilda", 3.97, 0.14, 7.8, DynamicalClass::Hilda),
+            ("Hektor", 5.26, 0.02, 18.2, DynamicalClass::JupiterTrojan),
+            ("Tempel 1", 3.145, 0.51, 10.5, DynamicalClass::JupiterFamilyComet),
+            ("Chiron", 13.7, 0.38, 6.9, DynamicalClass::Centaur),
+            ("Stephan-Oterma", 11.2, 0.859, 18.0, DynamicalClass::HalleyTypeComet),
+            // the near-Earth cuts come first, for comets too
+            ("Halley", 17.8, 0.967, 162.3, DynamicalClass::Apollo),
+            // T_J < 3, but the orbit stays well inside Jupiter's
+            ("Inclined asteroid", 3.0, 0.3, 40.0, DynamicalClass::MainBelt),
+            ("Hale-Bopp", 186.0, 0.995, 89.4, DynamicalClass::LongPeriodComet),
+            ("Arrokoth", 44.6, 0.04, 2.45, DynamicalClass::ClassicalTNO),
+            ("Eris", 67.8, 0.44, 44.0, DynamicalClass::ScatteredTNO),
+            ("Sedna", 506.0, 0.85, 11.9, DynamicalClass::DetachedTNO),
+        ];
+        for (name, a, e, inc, class) in cases {
+            assert_eq!(rock_from_a(name, a, e, inc).dynamical_class().unwrap(), class, "{}", name);
+        }
+
+        let oumuamua = rock("Oumuamua", 0.255, 1.2, 2.15, 4.2, 0.42, 0.0);
+        assert_eq!(oumuamua.dynamical_class().unwrap(), DynamicalClass::Interstellar);
+
+        // a long-period comet left slightly hyperbolic by the planets is not interstellar
+        let comet = rock("Comet", 3.0, 1.002, 1.2, 0.3, 2.0, 0.0);
+        assert_eq!(comet.dynamical_class().unwrap(), DynamicalClass::LongPeriodComet);
+        assert!(DynamicalClass::Apollo.is_near_earth());
+        assert_eq!(DynamicalClass::JupiterFamilyComet.to_string(), "Jupiter-family Comet");
+    }
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use spacerocks::{Ephemeris, Observer, SpaceRock, Time};
+use spacerocks::ephemeris::{ChebyshevSegment, calc_chebyshev_nodes};
+use spacerocks::observing::CorrectionLevel;
+use common::craft;
+
+use nalgebra::Vector3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch() -> Time {
+        Time::new(2460000.5, "tdb", "jd").unwrap()
+    }
+
+    #[test]
+    fn test_chebyshev_segment() {
+        // a cubic is fit exactly, with its derivative
+        let f = |t: f64| Vector3::new(t.powi(3) - 2.0 * t, 0.5 * t * t, 1.0);
+        let df = |t: f64| Vector3::new(3.0 * t * t - 2.0, t, 0.0);
+        let samples: Vec<_> = calc_chebyshev_nodes(-1.0, 3.0, 8).into_iter().map(f).collect();
+        let segment = ChebyshevSegment::from_samples(-1.0, 3.0, &samples);
+        for t in [-1.0, -0.3, 0.0, 1.7, 3.0] {
+            let (position, velocity) = segment.state(t);
+            assert!((position - f(t)).norm() < 1e-12);
+            assert!((velocity - df(t)).norm() < 1e-11);
+            assert!((segment.position(t) - position).norm() < 1e-15);
+        }
+        assert!(segment.contains(0.0));
+        assert!(!segment.contains(3.1));
+    }
+
+    #[test]
+    fn test_ephemeris_from_spacerock() {
+        let epoch = epoch();
+        let rock = SpaceRock::from_kepler("rock", 0.8, 0.6, 0.3, 1.0, 2.0, 0.5, epoch.clone(), "J2000", "SSB").unwrap();
+        let end = epoch.clone() + 200.0;
+        let ephemeris = Ephemeris::from_spacerock(&rock, &epoch, &end, 1e-10).unwrap();
+        assert!(ephemeris.segments.len() > 1);
+        // the span starts early by the light travel time
+        assert!(ephemeris.start() < epoch.tdb().jd() && ephemeris.start() > epoch.tdb().jd() - 0.05);
+
+        for dt in [0.0, 0.37, 13.2, 77.7, 150.01, 200.0] {
+            let time = epoch.clone() + dt;
+            let exact = rock.analytic_at(&time).unwrap();
+            let (position, velocity) = ephemeris.state(time.tdb().jd()).unwrap();
+            assert!((position - exact.position).norm() < 1e-10);
+            assert!((velocity - exact.velocity).norm() < 1e-8);
+
+            let at = ephemeris.at(&time).unwrap();
+            assert_eq!(at.name, "rock");
+            assert!((at.position - exact.position).norm() < 1e-10);
+        }
+
+        assert!(ephemeris.position(epoch.tdb().jd() - 1.0).is_err());
+        assert!(ephemeris.position(epoch.tdb().jd() + 201.0).is_err());
+        assert!(Ephemeris::from_spacerock(&rock, &end, &epoch, 1e-10).is_err());
+    }
+
+    #[test]
+    fn test_ephemeris_observations() {
+        let epoch = epoch();
+        let end = epoch.clone() + 60.0;
+        let observatory = craft(&epoch, "J2000").observatory;
+        let mut rock = SpaceRock::from_xyz("rock", 2.2, 0.5, 0.1, -0.002, 0.01, 0.001, epoch.clone(), "J2000", "SSB").unwrap();
+        rock.set_absolute_magnitude(16.0);
+
+        let observer_ephemeris = Ephemeris::from_observatory(&observatory, &epoch, &end, "J2000", "SSB", 1e-10).unwrap();
+        let rock_ephemeris = Ephemeris::from_spacerock(&rock, &epoch, &end, 1e-10).unwrap();
+
+        // including the start and end of the span
+        for dt in [0.0, 1.5, 20.25, 59.0, 60.0] {
+            let time = epoch.clone() + dt;
+            let observer: Observer = observatory.at(&time, "J2000", "SSB").unwrap();
+            let mut target = rock.analytic_at(&time).unwrap();
+            let exact = target.observe(&observer).unwrap();
+
+            let (ra, dec) = rock_ephemeris.calc_radec(&observer_ephemeris, time.tdb().jd()).unwrap();
+            assert!((ra - exact.ra()).abs() < 1e-9);
+            assert!((dec - exact.dec()).abs() < 1e-9);
+
+            let observation = rock_ephemeris.observe(&observer, &CorrectionLevel::Astrometric).unwrap();
+            assert!((observation.ra() - exact.ra()).abs() < 1e-9);
+            assert!((observation.mag().unwrap() - exact.mag().unwrap()).abs() < 1e-6);
+        }
+
+        // the body and the observer must share a reference plane
+        let ecliptic = Ephemeris::from_observatory(&observatory, &epoch, &end, "ECLIPJ2000", "SSB", 1e-10).unwrap();
+        assert!(rock_ephemeris.calc_radec(&ecliptic, epoch.tdb().jd() + 1.0).is_err());
+    }
+}
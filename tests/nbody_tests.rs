@@ -0,0 +1,137 @@
+use spacerocks::SpaceRock;
+use spacerocks::Simulation;
+use spacerocks::Time;
+use spacerocks::constants::GRAVITATIONAL_CONSTANT;
+use spacerocks::nbody::calc_b_plane;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    const EARTH_MASS: f64 = 3.0034896149157645e-6;
+
+    fn epoch() -> Time {
+        Time::new(2451545.0, "TDB", "JD").unwrap()
+    }
+
+    // a sun, an earth on a circular orbit, and a test particle that flies past the earth
+    fn flyby_simulation() -> Simulation {
+        let mut sim = Simulation::new(&epoch(), "ECLIPJ2000", "SUN").unwrap();
+
+        let mut sun = SpaceRock::from_xyz("sun", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        sun.set_mass(1.0);
+        sim.add(sun).unwrap();
+
+        let vcirc = (GRAVITATIONAL_CONSTANT * (1.0 + EARTH_MASS)).sqrt();
+        let mut earth = SpaceRock::from_xyz("earth", 1.0, 0.0, 0.0, 0.0, vcirc, 0.0, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        earth.set_mass(EARTH_MASS);
+        sim.add(earth).unwrap();
+
+        // the rock starts just inside the earth's orbit, moving slowly outwards, and passes it after about 10 days
+        let u = 0.002;
+        let rock = SpaceRock::from_xyz("rock", 1.0 - 10.0 * u, 0.0, 0.02, u, vcirc, 0.0, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        sim.add(rock).unwrap();
+        sim
+    }
+
+    fn distance(sim: &Simulation) -> f64 {
+        (sim.get_particle("rock").unwrap().position - sim.get_particle("earth").unwrap().position).norm()
+    }
+
+    #[test]
+    fn test_close_approaches() {
+        let mut sim = flyby_simulation();
+        let end = epoch() + 30.0;
+        let approaches = sim.close_approaches(&end, "rock", "earth", 0.05).unwrap();
+        assert_eq!(approaches.len(), 1);
+        assert!((sim.epoch.jd() - end.jd()).abs() < 1e-8);
+
+        let approach = &approaches[0];
+        assert_eq!(approach.body, "earth");
+        assert!(approach.distance < 0.03);
+        assert!((approach.xi.hypot(approach.zeta) - approach.impact_parameter).abs() < 1e-12);
+
+        // the interpolated closest approach should agree with integrating straight to it
+        let tca = approach.epoch.jd();
+        let mut check = flyby_simulation();
+        check.integrate(&approach.epoch);
+        assert!((distance(&check) - approach.distance).abs() < 1e-8);
+
+        for offset in [-1e-3, 1e-3] {
+            let mut check = flyby_simulation();
+            check.integrate(&Time::new(tca + offset, "TDB", "JD").unwrap());
+            assert!(distance(&check) > approach.distance);
+        }
+
+        // nothing comes within the threshold if it is smaller than the miss distance
+        let mut sim = flyby_simulation();
+        let approaches = sim.close_approaches(&end, "rock", "earth", 0.5 * approach.distance).unwrap();
+        assert!(approaches.is_empty());
+    }
+
+    #[test]
+    fn test_close_approaches_backwards() {
+        let mut sim = flyby_simulation();
+        let end = epoch() + 30.0;
+        let forward = sim.close_approaches(&end, "rock", "earth", 0.05).unwrap();
+        let backward = sim.close_approaches(&epoch(), "rock", "earth", 0.05).unwrap();
+        assert_eq!(backward.len(), 1);
+        assert!((forward[0].epoch.jd() - backward[0].epoch.jd()).abs() < 1e-6);
+        assert!((forward[0].distance - backward[0].distance).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_close_approaches_errors() {
+        let mut sim = flyby_simulation();
+        assert!(sim.close_approaches(&(epoch() + 1.0), "rock", "pluto", 0.05).is_err());
+        assert!(sim.close_approaches(&(epoch() + 1.0), "earth", "rock", 0.05).is_err());
+    }
+
+    #[test]
+    fn test_b_plane_hyperbola() {
+        // the impact parameter is related to the periapsis distance through gravitational focusing
+        let mu = GRAVITATIONAL_CONSTANT * EARTH_MASS;
+        let q: f64 = 1e-4;
+        let vp = 2.0 * (mu / q).sqrt();
+        let position = Vector3::new(q, 0.0, 0.0);
+        let velocity = Vector3::new(0.0, vp, 0.0);
+        let body_velocity = Vector3::new(0.0, 0.0172, 0.0);
+
+        let (v_infinity, b, xi, zeta) = calc_b_plane(&position, &velocity, &body_velocity, mu);
+        assert!((v_infinity * v_infinity - (vp * vp - 2.0 * mu / q)).abs() < 1e-15);
+        assert!((b - q * (1.0 + 2.0 * mu / (q * v_infinity * v_infinity)).sqrt()).abs() < 1e-15);
+        assert!((xi.hypot(zeta) - b).abs() < 1e-15);
+
+        // a bound orbit has no target plane
+        let velocity = Vector3::new(0.0, 0.5 * vp, 0.0);
+        let (v_infinity, b, _, _) = calc_b_plane(&position, &velocity, &body_velocity, mu);
+        assert_eq!(v_infinity, 0.0);
+        assert!(b.is_nan());
+    }
+
+    #[test]
+    fn test_analytic_propagation_matches_integration() {
+        let mut sim = Simulation::new(&epoch(), "ECLIPJ2000", "SUN").unwrap();
+        let mut sun = SpaceRock::from_xyz("sun", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        sun.set_mass(1.0);
+        sim.add(sun).unwrap();
+        let rock = SpaceRock::from_xyz("rock", 0.9, 0.3, 0.05, -0.006, 0.019, 0.001, epoch(), "ECLIPJ2000", "SUN").unwrap();
+        sim.add(rock.clone()).unwrap();
+
+        for dt in [-40.0, 25.0, 300.0] {
+            let later = epoch() + dt;
+            let mut sim = sim.clone();
+            sim.integrate(&later);
+            let integrated = sim.get_particle("rock").unwrap();
+            let analytic = rock.analytic_at(&later).unwrap();
+            assert!((analytic.position - integrated.position).norm() < 1e-9);
+            assert!((analytic.velocity - integrated.velocity).norm() < 1e-11);
+        }
+
+        // propagating to the same epoch changes nothing
+        let same = rock.analytic_at(&epoch()).unwrap();
+        assert!((same.position - rock.position).norm() < 1e-15);
+        assert!((same.velocity - rock.velocity).norm() < 1e-15);
+    }
+}
@@ -0,0 +1,517 @@
+mod common;
+
+use spacerocks::{Observer, ReferencePlane, SpaceRock, Simulation, Time};
+use spacerocks::observing::{Observatory, ObsCodeTable, SiteKind, get_obscode_table, set_obscode_table};
+use spacerocks::observing::{Observation, calc_airmass, calc_parallactic_angle, calc_geometry};
+use spacerocks::observing::{Field, calc_rocks_in_field};
+use spacerocks::observing::{Atmosphere, CorrectionLevel, calc_aberration, calc_light_deflection, calc_refraction, calc_refraction_constants};
+use spacerocks::constants::{EQUAT_RAD, FLATTEN, GRAVITATIONAL_CONSTANT, KM_TO_AU, MU_BARY, M_TO_AU, SPEED_OF_LIGHT};
+use spacerocks::transforms::solve_for_light_time;
+use spacerocks::time::{EopEntry, EopTable, set_eop_table};
+use common::craft;
+
+// an excerpt of the MPC ObsCodes.html, with a made-up site at the end
+const OBSCODES_HTML: &str = r#"<html><head><title>List Of Observatory Codes</title></head>
+<body><pre>
+Code  Long.   cos      sin    Name
+000   0.0000 0.62411 +0.77873 Greenwich
+247                           Roving Observer
+250                           Hubble Space Telescope
+695 248.4009 0.84950 +0.52642 Kitt Peak
+C51                           WISE
+Z99 359.978740.595468+0.800687Clixby Observatory, Cleethorpes
+ZZZ  10.0000 0.70000 +0.71000 Smith &amp; Jones Backyard
+</pre></body></html>"#;
+
+const OBSCODES_JSON: &str = r#"{
+    "000": {"Longitude": 0.0, "cos": 0.62411, "sin": 0.77873, "Name": "Greenwich"},
+    "250": {"Longitude": null, "cos": null, "sin": null, "Name": "Hubble Space Telescope"},
+    "ZZZ": {"Longitude": "20.0", "cos": "0.5", "sin": "0.8", "Name": "Relocated"}
+}"#;
+
+const OBSCODES_API_JSON: &str = r#"[
+    {"obscode": "247", "name": "Roving Observer", "longitude": "", "rhocosphi": "", "rhosinphi": "", "observations_type": "roving"},
+    {"obscode": "C57", "name": "TESS", "longitude": "", "rhocosphi": "", "rhosinphi": "", "observations_type": "satellite"},
+    {"obscode": "I41", "name": "Palomar Mountain--ZTF", "longitude": "243.140220", "rhocosphi": "0.836325", "rhosinphi": "0.546877", "observations_type": "optical"}
+]"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+    use std::sync::{Mutex, MutexGuard};
+
+    // the Earth orientation and observatory code tables are global, so the tests which set them, and
+    // those whose results depend on them, take turns
+    static GLOBAL_TABLES: Mutex<()> = Mutex::new(());
+
+    fn lock_global_tables() -> MutexGuard<'static, ()> {
+        GLOBAL_TABLES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    #[test]
+    fn test_native_geocentric_state() {
+        let _tables = lock_global_tables();
+        let epoch = Time::new(2460000.5, "utc", "jd").unwrap();
+        let observatory = Observatory::from_obscode("695").unwrap();
+        let (lon, lat, rho) = (observatory.lon().unwrap(), observatory.lat().unwrap(), observatory.rho().unwrap());
+        let radius = rho * EQUAT_RAD * M_TO_AU;
+
+        set_eop_table(EopTable::default());
+        let (position, velocity) = observatory.geocentric_state(&epoch, &ReferencePlane::J2000).unwrap();
+        assert!((position.norm() - radius).abs() < 1e-15);
+        assert!(position.dot(&velocity).abs() < 1e-12 * radius * velocity.norm());
+
+        // the Earth turns once a sidereal day
+        let speed = std::f64::consts::TAU * 1.00273781191135448 * radius * lat.cos();
+        assert!((velocity.norm() - speed).abs() < 1e-6 * speed);
+
+        // rotating back into the ITRS recovers the station coordinates
+        let itrs = ReferencePlane::ITRS(epoch.clone()).get_rotation_matrix() * position;
+        let expected = Vector3::new(lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()) * radius;
+        assert!((itrs - expected).norm() < 1e-15);
+
+        // the velocity matches a finite difference of the position
+        let dt = 1.0 / 86400.0;
+        let (ahead, _) = observatory.geocentric_state(&(epoch.clone() + dt), &ReferencePlane::J2000).unwrap();
+        let (behind, _) = observatory.geocentric_state(&(epoch.clone() - dt), &ReferencePlane::J2000).unwrap();
+        assert!(((ahead - behind) / (2.0 * dt) - velocity).norm() < 1e-6 * speed);
+
+        // UT1 - UTC turns the station with the Earth
+        set_eop_table(EopTable::new(vec![EopEntry { mjd: 60000.0, ut1_minus_utc: -0.5, ..Default::default() }]));
+        let (shifted, _) = observatory.geocentric_state(&epoch, &ReferencePlane::J2000).unwrap();
+        assert!(((shifted - position).norm() - 0.5 / 86400.0 * speed).abs() < 1e-3 * speed / 86400.0);
+
+        // and polar motion tilts it, by at most the angle of the pole times the radius
+        set_eop_table(EopTable::new(vec![EopEntry { mjd: 60000.0, x_pole: 0.3, ..Default::default() }]));
+        let (shifted, _) = observatory.geocentric_state(&epoch, &ReferencePlane::J2000).unwrap();
+        set_eop_table(EopTable::default());
+        let tilt = 0.3 / 206264.806 * radius;
+        assert!((shifted - position).norm() > 0.1 * tilt && (shifted - position).norm() < 1.01 * tilt);
+
+        let observatory = Observatory::from_name("JWST");
+        assert!(observatory.geocentric_state(&epoch, &ReferencePlane::J2000).is_err());
+    }
+
+    #[test]
+    fn test_geodetic_observatory() {
+        let _tables = lock_global_tables();
+        use std::f64::consts::FRAC_PI_2;
+
+        // on the ellipsoid at the equator and at the pole
+        let equator = Observatory::from_geodetic("equator", 1.0, 0.0, 0.0);
+        assert!((equator.rho().unwrap() - 1.0).abs() < 1e-15);
+        assert!(equator.lat().unwrap().abs() < 1e-15);
+        assert_eq!(equator.lon(), Some(1.0));
+        assert_eq!(equator.name(), "equator");
+
+        let pole = Observatory::from_geodetic("pole", 0.0, FRAC_PI_2, 100.0);
+        assert!((pole.rho().unwrap() - (1.0 - FLATTEN + 100.0 / EQUAT_RAD)).abs() < 1e-12);
+        assert!((pole.lat().unwrap() - FRAC_PI_2).abs() < 1e-12);
+
+        // on the ellipsoid, tan(geocentric latitude) = (1 - f)^2 tan(geodetic latitude)
+        let lat: f64 = 0.7;
+        let site = Observatory::from_geodetic("site", 0.0, lat, 0.0);
+        assert!((site.lat().unwrap() - ((1.0 - FLATTEN).powi(2) * lat.tan()).atan()).abs() < 1e-14);
+
+        // Kitt Peak, close to the MPC parallax constants of code 695
+        let kitt_peak = Observatory::from_geodetic("kitt peak", (-111.5997_f64).to_radians(), 31.9634_f64.to_radians(), 2120.0);
+        let mpc = Observatory::from_obscode("695").unwrap();
+        for observatory in [&kitt_peak, &mpc] {
+            assert!((observatory.rho().unwrap() * observatory.lat().unwrap().cos() - 0.849504).abs() < 1e-4);
+            assert!((observatory.rho().unwrap() * observatory.lat().unwrap().sin() - 0.526425).abs() < 1e-4);
+        }
+
+        // the geodetic coordinates come back out
+        for (lat, height) in [(-0.6, 2400.0), (0.0, -50.0), (1.2, 4200.0), (-FRAC_PI_2, 0.0)] {
+            let (lon, lat_out, height_out) = Observatory::from_geodetic("site", -2.0, lat, height).geodetic().unwrap();
+            assert_eq!(lon, -2.0);
+            assert!((lat_out - lat).abs() < 1e-12);
+            assert!((height_out - height).abs() < 1e-6);
+        }
+        assert!(Observatory::from_name("JWST").geodetic().is_none());
+    }
+
+    #[test]
+    fn test_obscode_parsing() {
+        let table = ObsCodeTable::parse_text(OBSCODES_HTML).unwrap();
+        assert_eq!(table.len(), 7);
+
+        let kitt_peak = table.get("695").unwrap();
+        assert_eq!(kitt_peak.name, "Kitt Peak");
+        assert_eq!(kitt_peak.kind, SiteKind::Ground);
+        assert!((kitt_peak.lon - 248.4009_f64.to_radians()).abs() < 1e-12);
+        assert_eq!((kitt_peak.rho_cos_lat, kitt_peak.rho_sin_lat), (0.84950, 0.52642));
+
+        assert_eq!(table.get("247").unwrap().kind, SiteKind::Roving);
+        assert_eq!(table.get("250").unwrap().kind, SiteKind::Space);
+        assert_eq!(table.get("c51").unwrap().name, "WISE");
+        assert_eq!(table.get("Z99").unwrap().rho_sin_lat, 0.800687);
+        assert_eq!(table.get("ZZZ").unwrap().name, "Smith & Jones Backyard");
+        assert!(ObsCodeTable::parse_text("<html></html>").is_err());
+
+        let json = ObsCodeTable::parse_json(OBSCODES_JSON).unwrap();
+        assert_eq!(json.len(), 3);
+        assert_eq!(json.get("000").unwrap().rho_sin_lat, 0.77873);
+        assert_eq!(json.get("250").unwrap().kind, SiteKind::Space);
+        assert!((json.get("ZZZ").unwrap().lon - 20.0_f64.to_radians()).abs() < 1e-15);
+
+        let api = ObsCodeTable::parse_json(OBSCODES_API_JSON).unwrap();
+        assert_eq!(api.get("247").unwrap().kind, SiteKind::Roving);
+        assert_eq!(api.get("C57").unwrap().kind, SiteKind::Space);
+        assert_eq!(api.get("I41").unwrap().rho_cos_lat, 0.836325);
+        assert!(ObsCodeTable::parse_json(r#"{"000": {"Longitude": "east"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_obscode_tables() {
+        let _tables = lock_global_tables();
+        // later tables override earlier ones, and missing codes fall back to the built-in table
+        let mut table = ObsCodeTable::parse_text(OBSCODES_HTML).unwrap();
+        table.merge(ObsCodeTable::parse_json(OBSCODES_JSON).unwrap());
+        assert_eq!(table.get("ZZZ").unwrap().name, "Relocated");
+        assert_eq!(table.get("695").unwrap().name, "Kitt Peak");
+        assert_eq!(table.get("I41").unwrap().name, "");
+        assert!(table.get("I41").is_some());
+
+        table.set_builtin_fallback(false);
+        assert!(table.get("I41").is_none());
+
+        let builtin = ObsCodeTable::builtin();
+        assert!(builtin.len() > 2000);
+        assert!(!builtin.builtin_fallback());
+        assert!((builtin.get("695").unwrap().lon - 4.335490888003045).abs() < 1e-15);
+
+        // from_obscode consults the loaded table first
+        let loaded = get_obscode_table();
+        assert!(Observatory::from_obscode("ZZZ").is_err());
+        set_obscode_table(ObsCodeTable::parse_text(OBSCODES_HTML).unwrap());
+        let site = Observatory::from_obscode("zzz").unwrap();
+        assert_eq!(site.name(), "ZZZ");
+        assert!((site.lon().unwrap() - 10.0_f64.to_radians()).abs() < 1e-15);
+        assert!((site.rho().unwrap() - (0.7_f64.powi(2) + 0.71_f64.powi(2)).sqrt()).abs() < 1e-15);
+        assert!(Observatory::from_obscode("I41").is_ok());
+        assert_eq!(Observatory::from_obscode("250"), Err("Observatory is in space and has no fixed position"));
+        assert_eq!(Observatory::from_obscode("247"), Err("Observatory is roving and has no fixed position"));
+        set_obscode_table(loaded);
+    }
+
+    #[test]
+    fn test_mpc_second_lines() {
+        let satellite = "     K10G04E  s2010 04 01.00140 1 - 5634.1734 - 2466.2657 + 2155.7809   NEOCPC51";
+        assert_eq!(satellite.len(), 80);
+        let observatory = Observatory::from_mpc_second_line(satellite).unwrap();
+        let expected = Vector3::new(-5634.1734, -2466.2657, 2155.7809) * KM_TO_AU;
+        assert_eq!(observatory, Observatory::from_satellite_position("C51", expected));
+        assert_eq!(observatory.name(), "C51");
+        assert!(observatory.lat().is_none());
+
+        let satellite_au = "     K10G04E  s2010 04 01.00140 2 +0.00001230 -0.00004560 +0.00007890        250";
+        let observatory = Observatory::from_mpc_second_line(satellite_au).unwrap();
+        assert_eq!(observatory, Observatory::from_satellite_position("250", Vector3::new(0.0000123, -0.0000456, 0.0000789)));
+
+        let roving = "     K08Q00L  v2008 08 24.37222 1 203.749440 +19.828990  4170                247";
+        assert_eq!(roving.len(), 80);
+        let (lon, lat, height) = Observatory::from_mpc_second_line(roving).unwrap().geodetic().unwrap();
+        assert!((lon - 203.74944_f64.to_radians()).abs() < 1e-12);
+        assert!((lat - 19.82899_f64.to_radians()).abs() < 1e-12);
+        assert!((height - 4170.0).abs() < 1e-6);
+
+        assert!(Observatory::from_mpc_second_line("     K10G04E  C2010 04 01.00140 1 - 5634.1734 - 2466.2657 + 2155.7809   NEOCPC51").is_err());
+        assert!(Observatory::from_mpc_second_line("     K10G04E  s2010 04 01.00140 3 - 5634.1734 - 2466.2657 + 2155.7809   NEOCPC51").is_err());
+        assert!(Observatory::from_mpc_second_line("     K10G04E  s2010 04 01.00140 1 - 56x4.1734 - 2466.2657 + 2155.7809   NEOCPC51").is_err());
+        assert!(Observatory::from_mpc_second_line("short").is_err());
+
+        // truncated lines would lose the end of a coordinate or the observatory code
+        assert!(Observatory::from_mpc_second_line(&satellite[..66]).is_err());
+        assert!(Observatory::from_mpc_second_line(&satellite[..78]).is_err());
+        assert!(Observatory::from_mpc_second_line(&roving[..61]).is_err());
+    }
+
+    #[test]
+    fn test_spacerock_observatory() {
+        let epoch = Time::new(2451545.0, "tdb", "jd").unwrap();
+        let later = Time::new(2451575.0, "tdb", "jd").unwrap();
+        let vcirc = GRAVITATIONAL_CONSTANT.sqrt();
+        let craft = SpaceRock::from_xyz("craft", 1.0, 0.0, 0.0, 0.0, vcirc, 0.0, epoch.clone(), "ECLIPJ2000", "SUN").unwrap();
+        let observatory = Observatory::from_spacerock(craft.clone());
+        assert_eq!(observatory.name(), "craft");
+
+        // propagated along its orbit, in the requested reference plane
+        let observer = observatory.at(&later, "ECLIPJ2000", "SUN").unwrap();
+        assert_eq!(observer.epoch(), later);
+        let expected = craft.analytic_at(&later).unwrap();
+        assert!((observer.position() - expected.position).norm() < 1e-12);
+        assert!((observer.position().norm() - 1.0).abs() < 1e-10);
+
+        let observer = observatory.at(&later, "J2000", "SUN").unwrap();
+        assert_eq!(observer.reference_plane(), "J2000");
+        assert!((observer.position().norm() - 1.0).abs() < 1e-10);
+        assert!(observatory.at(&later, "ECLIPJ2000", "MARS").is_err());
+
+        // through a simulation with only the sun it follows the same circular orbit
+        let mut sim = Simulation::new(&epoch, "ECLIPJ2000", "SUN").unwrap();
+        let mut sun = SpaceRock::from_xyz("sun", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch.clone(), "ECLIPJ2000", "SUN").unwrap();
+        sun.set_mass(1.0);
+        sim.add(sun).unwrap();
+        let observer = observatory.at_with_simulation(&later, &sim, "ECLIPJ2000", "SUN").unwrap();
+        let angle = vcirc * 30.0;
+        assert!((observer.position() - Vector3::new(angle.cos(), angle.sin(), 0.0)).norm() < 1e-8);
+        assert_eq!(observer.epoch(), later);
+    }
+
+    // reference values from the SOFA test suite (t_sofa_c.c)
+    #[test]
+    fn test_apparent_place_against_sofa() {
+        let p = Vector3::new(-0.763276255, -0.608633767, -0.216735543);
+        let e = Vector3::new(0.76700421, 0.605629598, 0.211937094);
+        let p1 = calc_light_deflection(0.00028574, &p, &p, &e, 8.91276983, 3e-10);
+        assert!((p1 - Vector3::new(-0.7632762548968159627, -0.6086337670823762701, -0.2167355431320546947)).norm() < 1e-12);
+
+        // iauLdsun is iauLd for the Sun, with a limiter of 1e-6 inside 1 au
+        let e = Vector3::new(-0.973644023, -0.20925523, -0.0907169552);
+        let p1 = calc_light_deflection(1.0, &p, &p, &e, 0.999809214, 1e-6);
+        assert!((p1 - Vector3::new(-0.7632762580731413169, -0.6086337635262647900, -0.2167355419322321302)).norm() < 1e-12);
+
+        let pnat = Vector3::new(-0.76321968546737951, -0.60869453983060384, -0.21676408580639883);
+        let v = Vector3::new(2.1044018893653786e-5, -8.9108923304429319e-5, -3.8633714797716569e-5);
+        let ppr = calc_aberration(&pnat, &v, 0.99980921395708788, 0.99999999506209258);
+        assert!((ppr - Vector3::new(-0.7631631094219556269, -0.6087553082505590832, -0.2167926269368471279)).norm() < 1e-12);
+
+        let (a, b) = calc_refraction_constants(800.0, 10.0, 0.9, 0.4);
+        assert!((a - 0.2264949956241415009e-3).abs() < 1e-15);
+        assert!((b - -0.2598658261729343970e-6).abs() < 1e-18);
+    }
+
+    #[test]
+    fn test_refraction() {
+        let (a, b) = Atmosphere::default().refraction_constants();
+        assert!(a > 0.0 && b < 0.0);
+
+        // at 45 degrees from the zenith the refraction is about A + B, close to a minute of arc
+        let z = 45.0_f64.to_radians();
+        let refracted = calc_refraction(&Vector3::new(z.sin(), 0.0, z.cos()), a, b);
+        let dz = z - refracted.z.acos();
+        assert!((refracted.norm() - 1.0).abs() < 1e-9);
+        assert!((dz - (a + b)).abs() < 1e-6);
+        assert!(dz > 55.0 / 206264.8 && dz < 62.0 / 206264.8);
+
+        // the zenith is not refracted, and the azimuth is kept
+        let zenith = calc_refraction(&Vector3::new(0.0, 0.0, 1.0), a, b);
+        assert!((zenith - Vector3::new(0.0, 0.0, 1.0)).norm() < 1e-9);
+        assert!((refracted.y).abs() < 1e-15);
+
+        // the air is thinner on a mountain
+        assert!(Atmosphere::standard(4200.0).pressure < 0.7 * Atmosphere::default().pressure);
+    }
+
+    #[test]
+    fn test_correction_levels() {
+        assert_eq!(CorrectionLevel::from_str("Apparent").unwrap(), CorrectionLevel::Apparent);
+        assert_eq!(CorrectionLevel::from_str("observed").unwrap(), CorrectionLevel::Observed(Atmosphere::default()));
+        assert_eq!(CorrectionLevel::Astrometric.to_string(), "astrometric");
+        assert!(CorrectionLevel::from_str("topocentric").is_err());
+
+        // the astrometric level is the light-time corrected observation
+        let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+        let observer = craft(&epoch, "J2000");
+        let mut rock = SpaceRock::from_xyz("rock", 2.0, 1.0, 0.5, -0.003, 0.009, 0.001, epoch.clone(), "J2000", "SSB").unwrap();
+        let observation = rock.observe(&observer).unwrap();
+        assert_eq!(rock.observe_with(&observer, &CorrectionLevel::Astrometric).unwrap(), observation);
+
+        // frames of date only match when their epochs do
+        let mut tod_observer = observer.clone();
+        tod_observer.spacerock.change_reference_plane_to(&ReferencePlane::TrueOfDate(epoch.clone())).unwrap();
+        let mut tod_rock = rock.clone();
+        tod_rock.change_reference_plane_to(&ReferencePlane::TrueOfDate(epoch.clone() + 1.0)).unwrap();
+        assert!(tod_rock.observe(&tod_observer).is_err());
+    }
+
+    #[test]
+    fn test_light_time() {
+        let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+        let observer = craft(&epoch, "ECLIPJ2000");
+
+        // a fast rock passing close to the observer
+        let rock = SpaceRock::from_xyz("rock", 1.01, 0.002, 0.001, 0.01, 0.005, 0.002, epoch.clone(), "ECLIPJ2000", "SSB").unwrap();
+        let (state, ltt) = solve_for_light_time(&rock, &observer, &Vector3::zeros(), |e| rock.analytic_at(e), 1e-14, 20).unwrap();
+        let emitted = rock.analytic_at(&(epoch.clone() - ltt)).unwrap();
+        assert!((state.position.norm() - ltt * SPEED_OF_LIGHT).abs() < 1e-12);
+        assert!((state.position - (emitted.position - observer.position())).norm() < 1e-12);
+        assert!((state.velocity - (emitted.velocity - observer.velocity())).norm() < 1e-12);
+
+        // a moving origin shifts the emitting position by its motion over the light travel time
+        let origin_velocity = Vector3::new(0.0, 0.0, 1e-4);
+        let (moved, moved_ltt) = solve_for_light_time(&rock, &observer, &origin_velocity, |e| rock.analytic_at(e), 1e-14, 20).unwrap();
+        assert!((moved.position.norm() - moved_ltt * SPEED_OF_LIGHT).abs() < 1e-12);
+        assert!((moved.position - state.position).norm() > 0.5 * 1e-4 * ltt);
+
+        // through a simulation with only a barycentric sun the observation is the two-body one
+        let mut sim = Simulation::new(&epoch, "ECLIPJ2000", "SSB").unwrap();
+        let mut sun = SpaceRock::from_xyz("sun", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch.clone(), "ECLIPJ2000", "SSB").unwrap();
+        sun.set_mass(MU_BARY / GRAVITATIONAL_CONSTANT);
+        sim.add(sun).unwrap();
+        sim.add(rock.clone()).unwrap();
+        let simulated = sim.observe("rock", &observer, &CorrectionLevel::Astrometric).unwrap();
+        let analytic = rock.clone().observe(&observer).unwrap();
+        assert!((simulated.ra() - analytic.ra()).abs() < 1e-10);
+        assert!((simulated.dec() - analytic.dec()).abs() < 1e-10);
+        assert!(sim.observe("nothing", &observer, &CorrectionLevel::Astrometric).is_err());
+
+        // the motion of a custom origin comes from SPICE, so an unknown body is an error
+        let mut custom = rock.clone();
+        custom.origin = spacerocks::Origin::new_custom(1e-9, "NOWHERE");
+        let mut custom_observer = observer.clone();
+        custom_observer.spacerock.origin = custom.origin.clone();
+        assert!(custom.observe(&custom_observer).is_err());
+    }
+
+    #[test]
+    fn test_horizontal_coordinates() {
+        let _tables = lock_global_tables();
+        let epoch = Time::new(2451545.0, "utc", "jd").unwrap();
+        let (lon, lat) = (-70.7_f64.to_radians(), -30.2_f64.to_radians());
+        let observatory = Observatory::from_geodetic("cerro", lon, lat, 2200.0);
+        let place = SpaceRock::from_xyz("cerro", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch.clone(), "J2000", "SSB").unwrap();
+        let observer = Observer { spacerock: place.clone(), observatory };
+        let lst = epoch.local_sidereal_time(lon);
+        let arcmin = 1.0_f64 / 60.0;
+
+        // at J2000 a star at the local sidereal time and the latitude is at the zenith
+        let zenith = Observation::from_astrometry(epoch.clone(), lst, lat, None, observer.clone());
+        let (alt, _) = zenith.calc_altaz().unwrap();
+        assert!(alt.to_degrees() > 90.0 - arcmin);
+        assert!(zenith.calc_hour_angle().unwrap().abs() < arcmin.to_radians());
+        assert!((zenith.calc_airmass(None).unwrap() - 1.0).abs() < 1e-3);
+
+        // on the meridian, 30 degrees north of the zenith
+        let north = Observation::from_astrometry(epoch.clone(), lst, lat + 30.0_f64.to_radians(), None, observer.clone());
+        let (alt, az) = north.calc_altaz().unwrap();
+        assert!((alt.to_degrees() - 60.0).abs() < arcmin);
+        assert!(az.to_degrees() < arcmin || az.to_degrees() > 360.0 - arcmin);
+        assert!((north.calc_parallactic_angle().unwrap().abs() - std::f64::consts::PI).abs() < arcmin.to_radians());
+
+        // three hours west of the meridian, the parallactic angle is positive
+        let west = Observation::from_astrometry(epoch.clone(), lst - 45.0_f64.to_radians(), -20.0_f64.to_radians(), None, observer.clone());
+        assert!((west.calc_hour_angle().unwrap().to_degrees() - 45.0).abs() < arcmin);
+        let (_, az) = west.calc_altaz().unwrap();
+        assert!(az.to_degrees() > 180.0 && az.to_degrees() < 360.0);
+        assert!(west.calc_parallactic_angle().unwrap() > 0.0);
+
+        // refraction raises the target and lowers the airmass
+        let atmosphere = Atmosphere::standard(2200.0);
+        let (refracted, _) = west.calc_refracted_altaz(&atmosphere).unwrap();
+        assert!(refracted > west.calc_altaz().unwrap().0);
+        assert!(west.calc_airmass(Some(&atmosphere)).unwrap() < west.calc_airmass(None).unwrap());
+
+        assert!((calc_airmass(30.0_f64.to_radians()) - 1.995).abs() < 0.01);
+        assert!(calc_airmass(-0.1).is_infinite());
+        assert_eq!(calc_parallactic_angle(0.0, 0.0, 0.3), 0.0);
+
+        // a spacecraft has no horizon
+        let craft = Observer { spacerock: place.clone(), observatory: Observatory::from_spacerock(place) };
+        let observation = Observation::from_astrometry(epoch, lst, lat, None, craft);
+        assert!(observation.calc_altaz().is_err());
+        assert!(observation.calc_hour_angle().is_err());
+    }
+
+    #[test]
+    fn test_geometry() {
+        use nalgebra::Vector3;
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+        // the target at quadrature: east of the observer, moving towards the north pole
+        let target = (Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 0.01));
+        let observer = Vector3::new(1.0, 0.0, 0.0);
+        let sun = (Vector3::zeros(), Vector3::zeros());
+        let moon = Vector3::new(1.0, 0.0, 0.001);
+
+        let geometry = calc_geometry((&target.0, &target.1), &observer, (&sun.0, &sun.1), Some(&moon), &ReferencePlane::J2000);
+        assert!((geometry.heliocentric_distance - 2.0_f64.sqrt()).abs() < 1e-12);
+        assert!((geometry.observer_distance - 1.0).abs() < 1e-12);
+        assert!((geometry.phase_angle - FRAC_PI_4).abs() < 1e-12);
+        assert!((geometry.solar_elongation - FRAC_PI_2).abs() < 1e-12);
+        assert!((geometry.lunar_elongation.unwrap() - FRAC_PI_2).abs() < 1e-12);
+        assert!((geometry.sun_position_angle - FRAC_PI_2).abs() < 1e-12);
+        assert!(geometry.velocity_position_angle.abs() < 1e-12);
+        assert!((geometry.out_of_plane_angle - FRAC_PI_4).abs() < 1e-12);
+
+        // the same vectors in the ecliptic give the same geometry
+        let to_ecliptic = ReferencePlane::ECLIPJ2000.get_rotation_matrix();
+        let ecliptic = calc_geometry((&(to_ecliptic * target.0), &(to_ecliptic * target.1)), &(to_ecliptic * observer), (&sun.0, &sun.1), None, &ReferencePlane::ECLIPJ2000);
+        assert!((ecliptic.phase_angle - geometry.phase_angle).abs() < 1e-12);
+        assert!((ecliptic.sun_position_angle - geometry.sun_position_angle).abs() < 1e-12);
+        assert!((ecliptic.galactic_latitude - geometry.galactic_latitude).abs() < 1e-12);
+        assert!(ecliptic.lunar_elongation.is_none());
+
+        // at opposition the phase angle vanishes, and the galactic north pole is at latitude 90 degrees
+        let (ra, dec) = (192.85948_f64.to_radians(), 27.12825_f64.to_radians());
+        let pole = Vector3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+        let target = (observer * 2.0, Vector3::new(0.0, 0.01, 0.0));
+        let opposition = calc_geometry((&target.0, &target.1), &observer, (&sun.0, &sun.1), None, &ReferencePlane::J2000);
+        assert!(opposition.phase_angle.abs() < 1e-12);
+        assert!((opposition.solar_elongation - PI).abs() < 1e-12);
+        let galactic = calc_geometry((&(observer + pole), &target.1), &observer, (&sun.0, &sun.1), None, &ReferencePlane::J2000);
+        assert!((galactic.galactic_latitude - FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_field_shapes() {
+        let (ra, dec) = (1.0, 0.3);
+        let arcmin = (1.0_f64 / 60.0).to_radians();
+
+        let circle = Field::circle(ra, dec, 10.0 * arcmin);
+        assert!(circle.contains(ra, dec));
+        assert!(circle.contains(ra, dec + 9.9 * arcmin));
+        assert!(!circle.contains(ra, dec + 10.1 * arcmin));
+        assert!(!circle.contains(ra + std::f64::consts::PI, -dec));
+
+        // a narrow rectangle along the north, then turned to the east
+        let rectangle = Field::rectangle(ra, dec, 2.0 * arcmin, 20.0 * arcmin, 0.0);
+        assert!(rectangle.contains(ra, dec + 9.0 * arcmin));
+        assert!(!rectangle.contains(ra + 9.0 * arcmin / dec.cos(), dec));
+        let turned = Field::rectangle(ra, dec, 2.0 * arcmin, 20.0 * arcmin, std::f64::consts::FRAC_PI_2);
+        assert!(!turned.contains(ra, dec + 9.0 * arcmin));
+        assert!(turned.contains(ra + 9.0 * arcmin / dec.cos(), dec));
+        assert!((rectangle.bounding_radius() - 101.0_f64.sqrt() * arcmin).abs() < 1e-7);
+
+        // a triangle pointing north
+        let triangle = Field::polygon(ra, dec, vec![(-5.0 * arcmin, 0.0), (5.0 * arcmin, 0.0), (0.0, 10.0 * arcmin)], 0.0);
+        assert!(triangle.contains(ra, dec + 5.0 * arcmin));
+        assert!(!triangle.contains(ra, dec - 1.0 * arcmin));
+        assert!(!triangle.contains(ra + 4.0 * arcmin / dec.cos(), dec + 8.0 * arcmin));
+    }
+
+    #[test]
+    fn test_rocks_in_field() {
+        let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+        let observer = craft(&epoch, "J2000");
+        let observatory = observer.observatory.clone();
+
+        // rocks spread along a line on the sky, at an earlier epoch and in the ecliptic
+        let start = epoch.clone() - 10.0;
+        let rocks: Vec<SpaceRock> = (0..20).map(|i| {
+            let mut rock = SpaceRock::from_xyz(&format!("rock{}", i), 2.5, 0.05 * i as f64, 0.1, 0.0, 0.011, 0.0, epoch.clone(), "J2000", "SSB").unwrap();
+            rock.analytic_propagate(&start).unwrap();
+            rock.change_reference_plane("ECLIPJ2000").unwrap();
+            rock
+        }).collect();
+
+        let mut target = rocks[7].analytic_at(&epoch).unwrap();
+        target.change_reference_plane("J2000").unwrap();
+        let expected = target.observe(&observer).unwrap();
+
+        let field = Field::circle(expected.ra(), expected.dec(), 0.01);
+        let found = calc_rocks_in_field(&rocks, &field, &observatory, &epoch).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 7);
+        assert!((found[0].1.ra() - expected.ra()).abs() < 1e-12);
+        assert!((found[0].1.dec() - expected.dec()).abs() < 1e-12);
+
+        // a wide field holds the neighbours too
+        let wide = Field::circle(expected.ra(), expected.dec(), 0.1);
+        let found = calc_rocks_in_field(&rocks, &wide, &observatory, &epoch).unwrap();
+        assert!(found.len() > 1 && found.len() < rocks.len());
+        assert!(found.iter().all(|(_, observation)| wide.contains(observation.ra(), observation.dec())));
+    }
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use spacerocks::{Band, PhaseFunction, Properties, SpaceRock, Time};
+use spacerocks::observing::{CorrectionLevel, Observation};
+use spacerocks::photometry::{Colors, calc_band_conversion, calc_band_offset, calc_hg_phase, calc_hg1g2_phase, calc_hg12_phase, calc_magnitude, calc_phase_angle};
+use common::craft;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basis_functions() {
+        // at zero phase every system gives the reduced magnitude
+        assert!(calc_hg_phase(0.0, 0.15).abs() < 1e-12);
+        assert!(calc_hg1g2_phase(0.0, 0.3, 0.4).abs() < 1e-12);
+        assert!(calc_hg12_phase(0.0, 0.5).abs() < 1e-12);
+
+        // the splines pass through their nodes
+        let phi1_30 = 3.3486016e-1_f64;
+        let phi2_60 = 3.1755495e-1_f64;
+        let phi3_4 = 2.3174230e-1_f64;
+        assert!((calc_hg1g2_phase(30.0_f64.to_radians(), 1.0, 0.0) + 2.5 * phi1_30.log10()).abs() < 1e-9);
+        assert!((calc_hg1g2_phase(60.0_f64.to_radians(), 0.0, 1.0) + 2.5 * phi2_60.log10()).abs() < 1e-9);
+        assert!((calc_hg1g2_phase(4.0_f64.to_radians(), 0.0, 0.0) + 2.5 * phi3_4.log10()).abs() < 1e-9);
+
+        // the linear pieces join the splines at 7.5 degrees
+        let joint = 7.5_f64.to_radians();
+        let below = calc_hg1g2_phase(joint - 1e-9, 0.6, 0.3);
+        let above = calc_hg1g2_phase(joint + 1e-9, 0.6, 0.3);
+        assert!((below - above).abs() < 1e-7);
+
+        // H,G12* is H,G1,G2 with fixed G1 and G2, and fainter with phase
+        let alpha = 20.0_f64.to_radians();
+        assert!((calc_hg12_phase(alpha, 0.4) - calc_hg1g2_phase(alpha, 0.84293649 * 0.4, 0.53513350 * 0.6)).abs() < 1e-12);
+        assert!(calc_hg12_phase(alpha, 0.4) > 0.0);
+        assert!(calc_hg_phase(alpha, 0.15) > calc_hg_phase(alpha, 0.5));
+
+        // a rock seen from behind reflects no light towards the observer
+        assert_eq!(calc_hg_phase(std::f64::consts::PI, 0.15), f64::INFINITY);
+
+        // opposition and quadrature at 1 au
+        assert!(calc_phase_angle(2.0, 1.0, 1.0).abs() < 1e-12);
+        assert!((calc_phase_angle(2.0_f64.sqrt(), 1.0, 1.0) - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_magnitudes() {
+        let (r, delta, alpha) = (2.5, 1.6, 15.0_f64.to_radians());
+
+        let mut properties = Properties { absolute_magnitude: Some(15.0), ..Default::default() };
+        assert_eq!(PhaseFunction::infer(&properties), Some(PhaseFunction::HG));
+        let hg = calc_magnitude(&properties, &PhaseFunction::HG, r, delta, alpha).unwrap();
+        assert!((hg - (15.0 + 5.0 * (r * delta).log10() + calc_hg_phase(alpha, 0.15))).abs() < 1e-12);
+
+        properties.g12 = Some(0.5);
+        assert_eq!(PhaseFunction::infer(&properties), Some(PhaseFunction::HG12));
+        properties.phase_function = Some(PhaseFunction::Linear);
+        assert!(calc_magnitude(&properties, &PhaseFunction::Linear, r, delta, alpha).is_err());
+        properties.phase_coefficient = Some(0.04);
+        let linear = calc_magnitude(&properties, &PhaseFunction::Linear, r, delta, alpha).unwrap();
+        assert!((linear - (15.0 + 5.0 * (r * delta).log10() + 0.6)).abs() < 1e-12);
+
+        let comet = Properties { m1: Some(10.0), k1: Some(10.0), m2: Some(15.0), k2: Some(5.0), ..Default::default() };
+        assert_eq!(PhaseFunction::infer(&comet), Some(PhaseFunction::CometTotal));
+        let total = calc_magnitude(&comet, &PhaseFunction::CometTotal, r, delta, alpha).unwrap();
+        assert!((total - (10.0 + 5.0 * delta.log10() + 10.0 * r.log10())).abs() < 1e-12);
+        let nuclear = calc_magnitude(&comet, &PhaseFunction::CometNuclear, r, delta, alpha).unwrap();
+        assert!((nuclear - (15.0 + 5.0 * delta.log10() + 5.0 * r.log10())).abs() < 1e-12);
+
+        assert_eq!(PhaseFunction::infer(&Properties::default()), None);
+        assert_eq!(PhaseFunction::from_str("hg12").unwrap(), PhaseFunction::HG12);
+        assert!(PhaseFunction::from_str("HG3").is_err());
+    }
+
+    #[test]
+    fn test_observed_magnitudes() {
+        let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+        let observer = craft(&epoch, "ECLIPJ2000");
+
+        // at opposition, an absolute magnitude without a slope no longer panics
+        let mut rock = SpaceRock::from_xyz("rock", 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch.clone(), "ECLIPJ2000", "SSB").unwrap();
+        rock.properties = Some(Properties { absolute_magnitude: Some(15.0), ..Default::default() });
+        let observation = rock.observe(&observer).unwrap();
+        let delta = observation.range().unwrap();
+        let expected = 15.0 + 5.0 * (2.0 * delta).log10();
+        assert!((observation.mag().unwrap() - expected).abs() < 1e-3);
+
+        // comets get total magnitudes
+        let mut comet = SpaceRock::from_xyz("comet", 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch.clone(), "ECLIPJ2000", "SSB").unwrap();
+        comet.set_total_magnitude(10.0, 10.0);
+        assert_eq!(comet.phase_function(), Some(PhaseFunction::CometTotal));
+        let observation = comet.observe(&observer).unwrap();
+        assert!((observation.mag().unwrap() - (10.0 + 5.0 * delta.log10() + 10.0 * 2.0_f64.log10())).abs() < 1e-3);
+
+        // no photometric parameters, no magnitude
+        let mut plain = SpaceRock::from_xyz("plain", 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch, "ECLIPJ2000", "SSB").unwrap();
+        assert!(plain.observe(&observer).unwrap().mag().is_none());
+    }
+
+    #[test]
+    fn test_bands() {
+        let colors = Colors { g_r: 0.65, r_i: 0.2, i_z: 0.05, z_y: 0.01 };
+
+        // the colours are recovered from the offsets
+        let offset = |band: Band| calc_band_offset(&band, &colors);
+        assert_eq!(offset(Band::V), 0.0);
+        assert!((offset(Band::G) - offset(Band::R) - 0.65).abs() < 1e-12);
+        assert!((offset(Band::R) - offset(Band::I) - 0.2).abs() < 1e-12);
+        assert!((offset(Band::I) - offset(Band::Z) - 0.05).abs() < 1e-12);
+        assert!((offset(Band::Z) - offset(Band::Y) - 0.01).abs() < 1e-12);
+        assert!((offset(Band::W) - (offset(Band::G) + offset(Band::R) + offset(Band::I)) / 3.0).abs() < 1e-12);
+
+        // V - r from g - r (Jester et al. 2005), and a solar-coloured body is brighter in G than in V
+        assert!((offset(Band::R) + 0.41 * 0.65 - 0.01).abs() < 1e-12);
+        assert!(calc_band_offset(&Band::Gaia, &Colors::default()) < 0.0);
+
+        // conversions go both ways
+        let r = calc_band_conversion(18.0, &Band::V, &Band::R, &colors);
+        assert!((calc_band_conversion(r, &Band::R, &Band::V, &colors) - 18.0).abs() < 1e-12);
+        let z = calc_band_conversion(r, &Band::R, &Band::Z, &colors);
+        assert!((z - calc_band_conversion(18.0, &Band::V, &Band::Z, &colors)).abs() < 1e-12);
+
+        assert_eq!(Band::from_str("g").unwrap(), Band::G);
+        assert_eq!(Band::from_str("G").unwrap(), Band::Gaia);
+        assert!(Band::from_str("u").is_err());
+
+        // observations carry their band, and convert with the colours of the rock
+        let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+        let observer = craft(&epoch, "ECLIPJ2000");
+        let mut rock = SpaceRock::from_xyz("rock", 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, epoch.clone(), "ECLIPJ2000", "SSB").unwrap();
+        rock.set_absolute_magnitude(15.0);
+        rock.set_colors(colors);
+        let v = rock.observe(&observer).unwrap();
+        assert_eq!(v.band, Some(Band::V));
+        let i = rock.observe_in_band(&observer, &CorrectionLevel::Astrometric, &Band::I).unwrap();
+        assert_eq!(i.band, Some(Band::I));
+        assert!((i.mag().unwrap() - v.mag().unwrap() - offset(Band::I)).abs() < 1e-12);
+
+        // a magnitude with no band cannot be converted
+        let unknown = Observation::from_astrometry(epoch, 0.0, 0.0, Some(20.0), observer);
+        assert!(unknown.to_band(&Band::R, &colors).is_err());
+    }
+}
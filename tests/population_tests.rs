@@ -0,0 +1,137 @@
+use spacerocks::Time;
+use spacerocks::population::{HDistribution, Histogram, Population, Perturber};
+use spacerocks::population::{NearEarthObjects, MainBelt, JupiterTrojans, ClassicalTnos, ResonantTnos, ScatteredTnos};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use std::f64::consts::{PI, TAU};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch() -> Time {
+        Time::new(2460000.5, "tdb", "jd").unwrap()
+    }
+
+    /// Wrap an angle into [-π, π).
+    fn wrap(angle: f64) -> f64 {
+        (angle + PI).rem_euclid(TAU) - PI
+    }
+
+    #[test]
+    fn test_h_distribution() {
+        assert!(HDistribution::power_law(0.5, 10.0, 5.0).is_err());
+        assert!(HDistribution::broken_power_law(vec![0.5, 0.3], vec![], 5.0, 10.0).is_err());
+        assert!(HDistribution::broken_power_law(vec![0.5, 0.3], vec![12.0], 5.0, 10.0).is_err());
+
+        // a single power law with slope 0.5 holds ten times as many objects per magnitude fainter
+        let single = HDistribution::power_law(0.5, 5.0, 10.0).unwrap();
+        let fraction = (10.0_f64.powf(0.5 * 2.0) - 1.0) / (10.0_f64.powf(0.5 * 5.0) - 1.0);
+        assert!((single.fraction_brighter(7.0) - fraction).abs() < 1e-12);
+        assert_eq!(single.fraction_brighter(4.0), 0.0);
+        assert!((single.fraction_brighter(11.0) - 1.0).abs() < 1e-12);
+
+        // draws follow the broken law
+        let broken = HDistribution::broken_power_law(vec![0.8, 0.2], vec![8.0], 4.0, 12.0).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        let draws: Vec<f64> = (0..20000).map(|_| broken.sample(&mut rng)).collect();
+        assert!(draws.iter().all(|h| (4.0..=12.0).contains(h)));
+        for h in [6.0, 8.0, 10.0] {
+            let below = draws.iter().filter(|x| **x < h).count() as f64 / draws.len() as f64;
+            assert!((below - broken.fraction_brighter(h)).abs() < 0.015);
+        }
+    }
+
+    #[test]
+    fn test_histogram() {
+        assert!(Histogram::new(vec![vec![0.0, 1.0, 0.5]], vec![1.0, 1.0]).is_err());
+        assert!(Histogram::new(vec![vec![0.0, 1.0, 2.0]], vec![1.0]).is_err());
+        assert!(Histogram::new(vec![vec![0.0, 1.0]], vec![0.0]).is_err());
+
+        // only the weighted bins of a 2-d histogram are drawn from
+        let histogram = Histogram::new(vec![vec![0.0, 1.0, 2.0], vec![10.0, 20.0, 30.0]], vec![0.0, 1.0, 3.0, 0.0]).unwrap();
+        assert_eq!(histogram.dimension(), 2);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut upper = 0;
+        for _ in 0..4000 {
+            let point = histogram.sample(&mut rng);
+            if point[0] < 1.0 {
+                assert!(point[1] >= 20.0);
+            } else {
+                assert!(point[1] < 20.0);
+                upper += 1;
+            }
+        }
+        assert!((upper as f64 / 4000.0 - 0.75).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_populations() {
+        let epoch = epoch();
+        let h = HDistribution::power_law(0.5, 15.0, 22.0).unwrap();
+
+        let neos = Population::NearEarthObjects(NearEarthObjects::default()).sample(200, &h, &epoch, Some(1)).unwrap();
+        assert!(neos.iter().all(|rock| rock.q() < 1.3 && rock.e() < 1.0));
+        assert!(neos.iter().all(|rock| (15.0..=22.0).contains(&rock.absolute_magnitude())));
+        assert_eq!(neos[0].name, "neo_0");
+
+        let belt = Population::MainBelt(MainBelt::default()).sample(200, &h, &epoch, Some(2)).unwrap();
+        assert!(belt.iter().all(|rock| (2.1..3.3).contains(&rock.a()) && rock.q() >= 1.3));
+
+        let classicals = Population::ClassicalTnos(ClassicalTnos::default()).sample(200, &h, &epoch, Some(3)).unwrap();
+        assert!(classicals.iter().all(|rock| (42.4..47.7).contains(&rock.a()) && rock.e() < 0.1));
+
+        let scattered = Population::ScatteredTnos(ScatteredTnos::default()).sample(200, &h, &epoch, Some(4)).unwrap();
+        assert!(scattered.iter().all(|rock| (30.0 - 1e-9..38.0 + 1e-9).contains(&rock.q()) && rock.a() > 50.0 - 1e-9));
+
+        // a seed gives the same population
+        let again = Population::MainBelt(MainBelt::default()).sample(200, &h, &epoch, Some(2)).unwrap();
+        assert_eq!(again, belt);
+
+        // H from the fourth axis of a histogram
+        let histogram = Histogram::new(vec![vec![1.0, 1.5], vec![0.3, 0.5], vec![0.0, 0.3], vec![25.0, 26.0]], vec![1.0]).unwrap();
+        let small = Population::NearEarthObjects(NearEarthObjects { histogram }).sample(20, &h, &epoch, Some(5)).unwrap();
+        assert!(small.iter().all(|rock| (25.0..26.0).contains(&rock.absolute_magnitude())));
+
+        // a histogram with no NEOs cannot be drawn from
+        let histogram = Histogram::new(vec![vec![2.0, 3.0], vec![0.0, 0.1], vec![0.0, 0.1]], vec![1.0]).unwrap();
+        assert!(Population::NearEarthObjects(NearEarthObjects { histogram }).sample(1, &h, &epoch, Some(6)).is_err());
+    }
+
+    #[test]
+    fn test_resonant_populations() {
+        let epoch = epoch();
+        let h = HDistribution::power_law(0.6, 5.0, 9.0).unwrap();
+
+        // plutinos librate about π
+        let plutinos = ResonantTnos::default();
+        let rocks = Population::ResonantTnos(plutinos).sample(200, &h, &epoch, Some(8)).unwrap();
+        let neptune = Perturber::Neptune.mean_longitude(&epoch);
+        for rock in rocks.iter() {
+            assert!((rock.a() - 39.4).abs() < 0.1);
+            let varpi = rock.node() + rock.arg();
+            let longitude = rock.mean_anomaly() + varpi;
+            let phi = 3.0 * longitude - 2.0 * neptune - varpi;
+            assert!(wrap(phi - plutinos.center).abs() <= plutinos.max_amplitude + 1e-9);
+        }
+
+        // trojans lead or trail Jupiter by 60 degrees
+        let trojans = JupiterTrojans::default();
+        let rocks = Population::JupiterTrojans(trojans).sample(400, &h, &epoch, Some(9)).unwrap();
+        let jupiter = Perturber::Jupiter.mean_longitude(&epoch);
+        let mut leading = 0;
+        for rock in rocks.iter() {
+            let offset = wrap(rock.mean_anomaly() + rock.node() + rock.arg() - jupiter);
+            assert!((offset.abs() - PI / 3.0).abs() <= trojans.max_amplitude + 1e-9);
+            if offset > 0.0 {
+                leading += 1;
+            }
+        }
+        assert!((leading as f64 / 400.0 - trojans.l4_fraction).abs() < 0.08);
+
+        assert!(Perturber::from_str("Neptune").is_ok());
+        assert!(Perturber::from_str("pluto").is_err());
+    }
+}
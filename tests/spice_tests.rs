@@ -1,10 +1,15 @@
 use spacerocks::SpiceKernel;
 use spacerocks::Time;
 use spacerocks::SpaceRock;
+use spacerocks::ReferencePlane;
+use spacerocks::observing::{Observatory, CorrectionLevel, VisibilityConstraints};
+use spacerocks::time::load_eop;
 
 
 static path_to_de440s: &str = "/Users/thomasruch/Gerdes/de440s.bsp";
 static path_to_leap: &str = "/Users/thomasruch/Gerdes/leap_seconds.tls";
+static path_to_pck: &str = "/Users/thomasruch/Gerdes/earth_latest_high_prec.bpc";
+static path_to_finals: &str = "/Users/thomasruch/Gerdes/finals2000A.all";
 
 
 // Note on these tests: When running cargo test --test spice_tests, the tests will run by default in parallel. This casues a SPICE error because the kernels are loaded in parallel. 
@@ -45,6 +50,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spice_kernel_bad_leap_seconds() {
+        let mut kernel = SpiceKernel::new();
+
+        // a leap second kernel that cannot be parsed is not loaded at all
+        assert!(kernel.load("does_not_exist.tls").is_err());
+        assert!(kernel.loaded_kernels().is_empty());
+    }
+
     #[test]
     fn test_spice_kernel_unload() {
         let mut kernel = SpiceKernel::new();
@@ -129,9 +143,106 @@ mod tests {
     //     kernel.unload();
     // }
 
+    #[test]
+    fn test_native_earth_rotation_against_spice() {
+        let mut kernel = SpiceKernel::new();
+        kernel.load(path_to_leap).unwrap();
+        kernel.load(path_to_pck).unwrap();
+        load_eop(path_to_finals).unwrap();
+
+        let observatory = Observatory::from_obscode("W84").unwrap();
+        for jd in [2459000.5, 2459500.25, 2460000.75] {
+            let epoch = Time::new(jd, "utc", "jd").unwrap();
+            let (position, velocity) = observatory.geocentric_state(&epoch, &ReferencePlane::J2000).unwrap();
+            let (spice_position, spice_velocity) = observatory.spice_geocentric_state(&epoch, "J2000").unwrap();
+
+            // agree to within a metre and a millimetre per second
+            assert!((position - spice_position).norm() < 1.0 / 1.495978707e11);
+            assert!((velocity - spice_velocity).norm() < 1e-3 * 86400.0 / 1.495978707e11);
+        }
+
+        kernel.unload();
+    }
+
+    #[test]
+    fn test_apparent_place() {
+        let mut kernel = SpiceKernel::new();
+        kernel.load(path_to_de440s).unwrap();
+        kernel.load(path_to_leap).unwrap();
+
+        let epoch = Time::new(2460000.5, "utc", "jd").unwrap();
+        let observer = Observatory::from_obscode("500").unwrap().at(&epoch, "J2000", "SSB").unwrap();
+        let mut rock = SpaceRock::from_xyz("rock", 2.0, 1.0, 0.5, -0.003, 0.009, 0.001, epoch.clone(), "J2000", "SSB").unwrap();
+
+        // aberration moves the target by at most about 20.5 arcseconds, deflection far less
+        let astrometric = rock.observe_with(&observer, &CorrectionLevel::Astrometric).unwrap();
+        let apparent = rock.observe_with(&observer, &CorrectionLevel::Apparent).unwrap();
+        let shift = ((apparent.ra() - astrometric.ra()) * astrometric.dec().cos()).hypot(apparent.dec() - astrometric.dec());
+        assert!(shift > 1e-6 && shift < 21.0 * PI / 180.0 / 3600.0);
+
+        // a space telescope cannot refract
+        let satellite = Observatory::from_satellite_position("250", nalgebra::Vector3::new(4.5e-5, 0.0, 0.0)).at(&epoch, "J2000", "SSB").unwrap();
+        assert!(rock.observe_with(&satellite, &CorrectionLevel::from_str("observed").unwrap()).is_err());
+
+        kernel.unload();
+    }
+
+    #[test]
+    fn test_rise_transit_set() {
+        let mut kernel = SpiceKernel::new();
+        kernel.load(path_to_de440s).unwrap();
+        kernel.load(path_to_leap).unwrap();
+
+        let start = Time::new(2460400.5, "utc", "jd").unwrap();
+        let observatory = Observatory::from_obscode("807").unwrap();
+        let rock = SpaceRock::from_xyz("rock", -1.5, 2.0, 0.1, -0.008, -0.006, 0.0005, start.clone(), "ECLIPJ2000", "SSB").unwrap();
+
+        let events = rock.rise_transit_set(&observatory, &start, 0.0).unwrap();
+        let (rise, transit, set) = (events.rise.unwrap(), events.transit.unwrap(), events.set.unwrap());
+        for (epoch, altitude) in [(&rise, 0.0), (&set, 0.0)] {
+            let mut target = rock.analytic_at(epoch).unwrap();
+            target.change_reference_plane("J2000").unwrap();
+            let observation = target.observe(&observatory.at(epoch, "J2000", "SSB").unwrap()).unwrap();
+            assert!((observation.calc_altaz().unwrap().0 - altitude).abs() < 1e-3);
+        }
+        let mut target = rock.analytic_at(&transit).unwrap();
+        target.change_reference_plane("J2000").unwrap();
+        let observation = target.observe(&observatory.at(&transit, "J2000", "SSB").unwrap()).unwrap();
+        assert!(observation.calc_hour_angle().unwrap().abs() < 1e-3);
+
+        // the windows are inside the night and above the altitude limit
+        let constraints = VisibilityConstraints::default();
+        for (open, close) in rock.observable_tonight(&observatory, &start, &constraints).unwrap() {
+            assert!(open.tdb().jd() < close.tdb().jd());
+        }
+
+        kernel.unload();
+    }
+
     #[test]
     fn test_spice_kernel_default() {
         let kernel: SpiceKernel = Default::default();
         assert!(kernel.loaded_files.is_empty());
     }
+
+    #[test]
+    fn test_observation_geometry() {
+        let mut kernel = SpiceKernel::new();
+        kernel.load(path_to_de440s).unwrap();
+        kernel.load(path_to_leap).unwrap();
+
+        let epoch = Time::new(2460400.5, "utc", "jd").unwrap();
+        let observatory = Observatory::from_obscode("807").unwrap();
+        let observer = observatory.at(&epoch, "J2000", "SSB").unwrap();
+        let mut rock = SpaceRock::from_xyz("rock", -1.5, 2.0, 0.1, -0.008, -0.006, 0.0005, epoch.clone(), "ECLIPJ2000", "SSB").unwrap();
+        rock.change_reference_plane("J2000").unwrap();
+
+        let observation = rock.observe(&observer).unwrap();
+        let geometry = observation.calc_geometry().unwrap();
+        assert!((geometry.observer_distance - observation.range().unwrap()).abs() < 1e-12);
+        assert!(geometry.phase_angle > 0.0 && geometry.phase_angle < std::f64::consts::PI);
+        assert!(geometry.lunar_elongation.is_some());
+
+        kernel.unload();
+    }
 }
\ No newline at end of file
@@ -0,0 +1,125 @@
+mod common;
+
+use spacerocks::{Band, Ephemeris, SpaceRock, Time};
+use spacerocks::observing::{Field, Observatory};
+use spacerocks::survey::{DetectionModel, Exposure, calc_trailing_losses, simulate_survey, simulate_survey_from_ephemerides};
+use common::craft;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arcsec(x: f64) -> f64 {
+        (x / 3600.0).to_radians()
+    }
+
+    fn exposure(epoch: &Time, field: Field, observatory: &Observatory) -> Exposure {
+        Exposure {
+            epoch: epoch.clone(),
+            field,
+            band: Band::R,
+            limiting_magnitude: 22.0,
+            seeing: arcsec(1.0),
+            exposure_time: 30.0,
+            observatory: observatory.clone(),
+        }
+    }
+
+    #[test]
+    fn test_trailing_losses() {
+        assert_eq!(calc_trailing_losses(0.0, 30.0, arcsec(1.0)), (0.0, 0.0));
+
+        // losses grow with the trail, and long trails are missed by a point-source filter
+        let mut last = (0.0, 0.0);
+        for rate in [0.1_f64, 1.0, 10.0, 100.0] {
+            let losses = calc_trailing_losses(rate.to_radians(), 30.0, arcsec(1.0));
+            assert!(losses.0 > last.0 && losses.1 > last.1);
+            last = losses;
+        }
+        assert!(last.1 > last.0);
+    }
+
+    #[test]
+    fn test_detection_model() {
+        let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+        let exposure = exposure(&epoch, Field::circle(0.0, 0.0, 0.01), &craft(&epoch, "J2000").observatory);
+        let model = DetectionModel::default();
+
+        // half the fill factor at the limit, for a stationary source
+        assert!((model.efficiency(22.0, 0.0, &exposure) - 0.45).abs() < 1e-12);
+        assert!((model.efficiency(18.0, 0.0, &exposure) - 0.9).abs() < 1e-12);
+        assert!(model.efficiency(24.0, 0.0, &exposure) < 1e-8);
+        assert!((model.signal_to_noise(22.0, 0.0, &exposure) - 5.0).abs() < 1e-12);
+
+        // fast movers are harder to detect, and rejected outside the rates of the model
+        let rate = 20.0_f64.to_radians();
+        assert!(model.efficiency(21.5, rate, &exposure) < model.efficiency(21.5, 0.0, &exposure));
+        let slow = DetectionModel { max_rate: 1.0_f64.to_radians(), ..model };
+        assert_eq!(slow.efficiency(18.0, rate, &exposure), 0.0);
+
+        // the astrometric error falls to the floor for bright sources
+        assert!((model.astrometric_error(1e6, &exposure) - model.astrometric_floor).abs() < 1e-12);
+        assert!(model.astrometric_error(5.0, &exposure) > arcsec(0.2));
+    }
+
+    #[test]
+    fn test_simulate_survey() {
+        let epoch = Time::new(2460000.5, "tdb", "jd").unwrap();
+        let observer = craft(&epoch, "J2000");
+        let observatory = observer.observatory.clone();
+
+        // a bright and a faint rock, close together on the sky
+        let mut rocks = Vec::new();
+        for (i, h) in [10.0, 25.0].iter().enumerate() {
+            let mut rock = SpaceRock::from_xyz(&format!("rock{}", i), 2.5, 0.01 * i as f64, 0.1, 0.0, 0.011, 0.0, epoch.clone(), "J2000", "SSB").unwrap();
+            rock.set_absolute_magnitude(*h);
+            rocks.push(rock);
+        }
+
+        let mut target = rocks[0].clone();
+        let expected = target.observe(&observer).unwrap();
+        let field = Field::circle(expected.ra(), expected.dec(), 0.05);
+        let exposures: Vec<Exposure> = (0..3).map(|_| exposure(&epoch, field.clone(), &observatory)).collect();
+        let model = DetectionModel { fill_factor: 1.0, ..DetectionModel::default() };
+
+        let detections = simulate_survey(&rocks, &exposures, &model, Some(42)).unwrap();
+        assert_eq!(detections.len(), 3);
+        for (i, detection) in detections.iter().enumerate() {
+            assert_eq!(detection.rock, 0);
+            assert_eq!(detection.exposure, i);
+            assert_eq!(detection.observation.band, Some(Band::R));
+            assert!((detection.observation.ra() - expected.ra()).abs() < arcsec(0.1));
+            assert!((detection.observation.dec() - expected.dec()).abs() < arcsec(0.1));
+            assert!(detection.observation.mag().unwrap() < expected.mag().unwrap());
+        }
+
+        // the same seed gives the same detections
+        assert_eq!(simulate_survey(&rocks, &exposures, &model, Some(42)).unwrap(), detections);
+
+        // rocks without magnitudes cannot be detected
+        let mut dark = rocks.clone();
+        dark[0].properties = None;
+        assert!(simulate_survey(&dark, &exposures, &model, Some(42)).is_err());
+
+        // ephemerides give the same detections, without propagating the rocks
+        let end = epoch.clone() + 1.0;
+        let rock_ephemerides: Vec<Ephemeris> = rocks.iter().map(|rock| Ephemeris::from_spacerock(rock, &epoch, &end, 1e-10).unwrap()).collect();
+        let observer_ephemeris = Ephemeris::from_observatory(&observatory, &epoch, &end, "J2000", "SSB", 1e-10).unwrap();
+        let from_ephemerides = simulate_survey_from_ephemerides(&rock_ephemerides, &observer_ephemeris, &exposures, &model, Some(42)).unwrap();
+        assert_eq!(from_ephemerides.len(), detections.len());
+        for (a, b) in from_ephemerides.iter().zip(detections.iter()) {
+            assert_eq!((a.rock, a.exposure), (b.rock, b.exposure));
+            assert!((a.observation.ra() - b.observation.ra()).abs() < 1e-9);
+            assert!((a.observation.dec() - b.observation.dec()).abs() < 1e-9);
+            assert!((a.observation.mag().unwrap() - b.observation.mag().unwrap()).abs() < 1e-6);
+        }
+
+        // the observer must be in J2000
+        let ecliptic = Ephemeris::from_observatory(&observatory, &epoch, &end, "ECLIPJ2000", "SSB", 1e-10).unwrap();
+        assert!(simulate_survey_from_ephemerides(&rock_ephemerides, &ecliptic, &exposures, &model, Some(42)).is_err());
+
+        // and of the observatory of the exposures
+        let elsewhere = exposure(&epoch, field.clone(), &Observatory::from_spacerock(rocks[0].clone()));
+        assert!(simulate_survey_from_ephemerides(&rock_ephemerides, &observer_ephemeris, &[elsewhere], &model, Some(42)).is_err());
+    }
+}
@@ -1,6 +1,72 @@
 #[cfg(test)]
 mod tests {
     use spacerocks::time::{Time, TimeFormat, TimeScale};
+    use spacerocks::time::{EopEntry, EopTable, set_eop_table, get_eop_table, calc_tdb_minus_tt};
+    use spacerocks::time::{LeapSecondTable, set_leap_second_table, get_leap_seconds_at_epoch};
+    use spacerocks::time::{TimeRange, calc_earth_rotation_angle, calc_gmst};
+
+    use std::sync::{Mutex, MutexGuard};
+
+    // the leap-second and Earth orientation tables are global, so the tests which set them, and those
+    // whose results depend on them, take turns
+    static GLOBAL_TABLES: Mutex<()> = Mutex::new(());
+
+    fn lock_global_tables() -> MutexGuard<'static, ()> {
+        GLOBAL_TABLES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // an excerpt of latest_leapseconds.tls, with a made-up leap second at the end
+    const LSK: &str = r#"KPL/LSK
+
+\begintext
+
+The DELTET/DELTA_AT values below are in ( seconds, date ) pairs.
+
+\begindata
+
+DELTET/DELTA_T_A       =   32.184
+DELTET/K               =    1.657D-3
+DELTET/EB              =    1.671D-2
+DELTET/M               = (  6.239996D0   1.99096871D-7 )
+
+    DELTET/DELTA_AT        = ( 10,   @1972-JAN-1
+                                11,   @1972-JUL-1
+                                12,   @1973-JAN-1
+                                13,   @1974-JAN-1
+                                14,   @1975-JAN-1
+                                15,   @1976-JAN-1
+                                16,   @1977-JAN-1
+                                17,   @1978-JAN-1
+                                18,   @1979-JAN-1
+                                19,   @1980-JAN-1
+                                20,   @1981-JUL-1
+                                21,   @1982-JUL-1
+                                22,   @1983-JUL-1
+                                23,   @1985-JUL-1
+                                24,   @1988-JAN-1
+                                25,   @1990-JAN-1
+                                26,   @1991-JAN-1
+                                27,   @1992-JUL-1
+                                28,   @1993-JUL-1
+                                29,   @1994-JUL-1
+                                30,   @1996-JAN-1
+                                31,   @1997-JUL-1
+                                32,   @1999-JAN-1
+                                33,   @2006-JAN-1
+                                34,   @2009-JAN-1
+                                35,   @2012-JUL-1
+                                36,   @2015-JUL-1
+                                37,   @2017-JAN-1
+                                38,   @2035-JAN-1 )
+
+\begintext
+"#;
+
+    /// Format a row of an IERS finals file, with the fixed columns of finals2000A.
+    fn finals_row(mjd: f64, x_pole: f64, y_pole: f64, ut1_minus_utc: f64, dx: f64, dy: f64) -> String {
+        format!("240101 {:8.2} I {:9.6}{:9.6} {:9.6}{:9.6}  I{:10.7}{:10.7} {:7.4}{:7.4}  I {:9.3}{:9.3} {:9.3}{:9.3}",
+                mjd, x_pole, 0.0001, y_pole, 0.0001, ut1_minus_utc, 0.00001, 0.3, 0.006, dx, 0.3, dy, 0.3)
+    }
     use spacerocks::errors::TimeError;
 
     // Time Creation
@@ -8,7 +74,7 @@ mod tests {
     fn test_time_creation() {
         let time = Time::new(2451545.0, &TimeScale::UTC.to_string(), &TimeFormat::JD.to_string())
             .unwrap();
-        assert_eq!(time.epoch, 2451545.0);
+        assert_eq!(time.epoch(), 2451545.0);
         assert_eq!(time.timescale, TimeScale::UTC);
         assert_eq!(time.format, TimeFormat::JD);
     }
@@ -91,22 +157,22 @@ mod tests {
     #[test]
     fn test_from_fuzzy_str() {
         let time1 = Time::from_fuzzy_str("2451545.0 utc jd").unwrap();
-        assert_eq!(time1.epoch, 2451545.0);
+        assert_eq!(time1.epoch(), 2451545.0);
         assert_eq!(time1.timescale, TimeScale::UTC);
         assert_eq!(time1.format, TimeFormat::JD);
 
         let time2 = Time::from_fuzzy_str("51544.5 utc mjd").unwrap();
-        assert_eq!(time2.epoch, 51544.5);
+        assert_eq!(time2.epoch(), 51544.5);
         assert_eq!(time2.timescale, TimeScale::UTC);
         assert_eq!(time2.format, TimeFormat::MJD);
 
         let time3 = Time::from_fuzzy_str("2451545.0 tdb jd").unwrap();
-        assert_eq!(time3.epoch, 2451545.0);
+        assert_eq!(time3.epoch(), 2451545.0);
         assert_eq!(time3.timescale, TimeScale::TDB);
         assert_eq!(time3.format, TimeFormat::JD);
 
         let time4 = Time::from_fuzzy_str("51544.5 tdb mjd").unwrap();
-        assert_eq!(time4.epoch, 51544.5);
+        assert_eq!(time4.epoch(), 51544.5);
         assert_eq!(time4.timescale, TimeScale::TDB);
         assert_eq!(time4.format, TimeFormat::MJD);
     }
@@ -160,4 +226,372 @@ mod tests {
         assert!(iso.contains("T"));   // Should have time separator
     }
 
-}
\ No newline at end of file
+    // Two-part epochs
+    #[test]
+    fn test_two_part_precision() {
+        let microsecond = 1e-6 / 86400.0;
+
+        // a microsecond survives construction from parts, in either format
+        let t = Time::from_parts(2460000.0, 0.25 + microsecond, "utc", "jd").unwrap();
+        assert_eq!(t.jd_parts(), (2460000.0, 0.25 + microsecond));
+        let t_mjd = Time::from_parts(59999.0, 0.75 + microsecond, "utc", "mjd").unwrap();
+        assert!((&t_mjd - &t).abs() * 86400.0 < 1e-9);
+        let (day, fraction) = t_mjd.mjd_parts();
+        assert_eq!(day, 59999.0);
+        assert!(((fraction - 0.75) * 86400.0 - 1e-6).abs() < 1e-9);
+
+        // adding and subtracting small steps is exact to well below a microsecond
+        let mut stepped = t.clone();
+        for _ in 0..1000 {
+            stepped += microsecond;
+        }
+        assert!(((&stepped - &t) * 86400.0 - 1e-3).abs() < 1e-9);
+        stepped -= 1000.0 * microsecond;
+        assert!((&stepped - &t).abs() * 86400.0 < 1e-9);
+
+        // a round trip through every timescale returns to the same instant
+        let round_trip = t.tdb().tt().tai().utc();
+        assert_eq!(round_trip.timescale, TimeScale::UTC);
+        assert!((&round_trip - &t).abs() * 86400.0 < 1e-9);
+
+        // times a microsecond apart stay a microsecond apart through UTC -> TDB
+        let later = t.clone() + microsecond;
+        assert!(((&later.tdb() - &t.tdb()) * 86400.0 - 1e-6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_timescale_offsets() {
+        // TT - UTC is 32.184 s plus the 32 leap seconds in effect at J2000
+        let utc = Time::new(2451545.0, "utc", "jd").unwrap();
+        let tt = utc.tt();
+        let (day, fraction) = tt.jd_parts();
+        assert_eq!(day, 2451545.0);
+        assert!((fraction * 86400.0 - 64.184).abs() < 1e-9);
+
+        // the normalized fraction is always in [0, 1)
+        let t = Time::new(2451545.0, "tdb", "jd").unwrap() - 1e-12;
+        assert_eq!(t.day, 2451544.0);
+        assert!(t.fraction < 1.0);
+    }
+
+    #[test]
+    fn test_new_timescales() {
+        // parsing, with suggestions
+        for scale in ["tcb", "tcg", "ut1", "gps"] {
+            let time = Time::new(2460000.5, scale, "jd").unwrap();
+            assert_eq!(time.timescale.to_str(), scale.to_uppercase());
+        }
+        let result = Time::new(2460000.5, "tcd", "jd");
+        assert!(result.unwrap_err().to_string().contains("Did you mean"));
+
+        // GPS runs 19 s behind TAI
+        let tai = Time::new(2460000.5, "tai", "jd").unwrap();
+        assert!(((&tai.gps().tai() - &tai) * 86400.0).abs() < 1e-9);
+        assert!(((tai.gps().fraction - tai.fraction) * 86400.0 + 19.0).abs() < 1e-9);
+
+        // TT -> TCG, TDB -> TCB and TCB -> TDB against the SOFA test values, which split the JD at 2453750.5
+        let sofa_fraction = |time: Time| (time.day - 2453750.5) + time.fraction;
+        let tt = Time::from_parts(2453750.5, 0.892482639, "tt", "jd").unwrap();
+        assert!((sofa_fraction(tt.tcg()) - 0.8924900312508587113).abs() < 1e-12);
+        let tdb = Time::from_parts(2453750.5, 0.892855137, "tdb", "jd").unwrap();
+        assert!((sofa_fraction(tdb.tcb()) - 0.8930195997253656716).abs() < 1e-12);
+        let tcb = Time::from_parts(2453750.5, 0.893019599, "tcb", "jd").unwrap();
+        assert!((sofa_fraction(tcb.tdb()) - 0.8928551362746343397).abs() < 1e-12);
+
+        // round trips through every timescale (UT1 is covered in test_ut1, which sets the global table)
+        let utc = Time::from_parts(2460000.5, 0.123456789, "utc", "jd").unwrap();
+        for scale in [TimeScale::TDB, TimeScale::TT, TimeScale::TAI, TimeScale::TCB, TimeScale::TCG, TimeScale::GPS] {
+            let round_trip = utc.in_timescale(scale).utc();
+            assert!((&round_trip - &utc).abs() * 86400.0 < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_tdb_minus_tt() {
+        // the SOFA test value for iauDtdb, including the topocentric terms
+        let dtdb = calc_tdb_minus_tt(2448939.5, 0.123, 0.76543, 5.0123, 5525.242, 3190.0);
+        assert!((dtdb + 0.1280368005936998991e-2).abs() < 1e-9);
+
+        // iauDtdb at other epochs, a century either side of J2000
+        let cases = [
+            ((2451545.0, 0.0, 0.0, 0.0, 0.0, 0.0), -9.93071989437944655e-05),
+            ((2415020.5, 0.25, 0.0, 0.0, 0.0, 0.0), -1.10277400626133316e-05),
+            ((2460000.5, 0.7, 0.3, 1.2, 4000.0, -4500.0), 1.29714708940877510e-03),
+            ((2488069.5, 0.5, 0.0, 0.0, 0.0, 0.0), -7.56152567186976104e-05),
+        ];
+        for ((day, fraction, ut, elong, u, v), expected) in cases {
+            assert!((calc_tdb_minus_tt(day, fraction, ut, elong, u, v) - expected).abs() < 1e-9);
+        }
+
+        // the topocentric terms are at the microsecond level
+        let utc = Time::new(2460000.5, "utc", "jd").unwrap();
+        let geocentric = utc.tdb();
+        let topocentric = utc.tdb_topocentric(0.0, 6378.0, 0.0);
+        let difference = (&topocentric - &geocentric).abs() * 86400.0;
+        assert!(difference > 1e-8 && difference < 3e-6);
+    }
+
+    #[test]
+    fn test_ut1() {
+        let _tables = lock_global_tables();
+        // without an Earth orientation table UT1 is UTC
+        let utc = Time::new(2460000.5, "utc", "jd").unwrap();
+        assert_eq!(utc.ut1().jd_parts(), utc.jd_parts());
+
+        // UT1 - UTC is interpolated between entries, across leap seconds
+        set_eop_table(EopTable::new(vec![
+            EopEntry { mjd: 57753.0, ut1_minus_utc: -0.4, ..Default::default() },
+            EopEntry { mjd: 57755.0, ut1_minus_utc: 0.6, ..Default::default() },
+        ]));
+        let table = get_eop_table();
+        assert!((table.ut1_minus_utc(57753.5).unwrap() + 0.4).abs() < 1e-12);
+        assert!((table.ut1_minus_utc(57754.5).unwrap() - 0.6).abs() < 1e-12);
+
+        let utc = Time::new(57754.5, "utc", "mjd").unwrap();
+        let ut1 = utc.ut1();
+        assert!(((ut1.fraction - utc.fraction) * 86400.0 - 0.6).abs() < 1e-9);
+        assert!((&ut1.utc() - &utc).abs() * 86400.0 < 1e-9);
+
+        set_eop_table(EopTable::default());
+    }
+
+    #[test]
+    fn test_leap_second_table() {
+        let _tables = lock_global_tables();
+        let table = LeapSecondTable::parse_lsk(LSK).unwrap();
+        assert_eq!(table.entries().len(), 29);
+
+        // the kernel agrees with the built-in table
+        let builtin = LeapSecondTable::builtin();
+        for (jd, leap_seconds) in builtin.entries() {
+            assert_eq!(table.leap_seconds_at(*jd), *leap_seconds);
+            assert_eq!(table.leap_seconds_at(jd - 1.0), builtin.leap_seconds_at(jd - 1.0));
+        }
+        assert_eq!(table.leap_seconds_at(2441317.0), 0.0);
+
+        // once loaded, the time system uses the kernel
+        let utc = Time::new(2464693.5, "utc", "jd").unwrap();  // 2036 January 1
+        assert_eq!(get_leap_seconds_at_epoch(utc.jd()), 37.0);
+        set_leap_second_table(table);
+        assert_eq!(get_leap_seconds_at_epoch(utc.jd()), 38.0);
+        assert!(((utc.tai().fraction - utc.fraction) * 86400.0 - 38.0).abs() < 1e-9);
+        set_leap_second_table(LeapSecondTable::builtin());
+
+        assert!(LeapSecondTable::parse_lsk("\\begindata\nDELTET/K = 1.657D-3\n").is_err());
+        assert!(LeapSecondTable::parse_lsk("\\begindata\nDELTET/DELTA_AT = ( 10, @1972-JAN-1 11 )\n").is_err());
+    }
+
+    #[test]
+    fn test_finals_table() {
+        let content = [
+            finals_row(60310.0, 0.05, 0.19, 0.0104385, 0.3, -0.1),
+            finals_row(60311.0, 0.06, 0.21, 0.0094385, 0.5, -0.3),
+            // a row beyond the end of the predictions, which has no UT1 - UTC
+            "240103  60312.00".to_string(),
+        ].join("\n");
+        let table = EopTable::parse_finals(&content).unwrap();
+        assert_eq!(table.entries().len(), 2);
+
+        let entry = table.at(60310.25).unwrap();
+        assert!((entry.x_pole - 0.0525).abs() < 1e-12);
+        assert!((entry.y_pole - 0.195).abs() < 1e-12);
+        assert!((entry.ut1_minus_utc - 0.0101885).abs() < 1e-12);
+        assert!((entry.dx - 0.35).abs() < 1e-12);
+        assert!((entry.dy + 0.15).abs() < 1e-12);
+
+        // outside the table the nearest entry is used
+        assert_eq!(table.at(60400.0).unwrap().x_pole, 0.06);
+
+        assert!(EopTable::parse_finals("240101  600x0.00").is_err());
+        assert!(EopTable::parse_finals("").is_err());
+    }
+
+    #[test]
+    fn test_iso_parsing_and_formatting() {
+        let time = Time::from_iso("2000-01-01T12:00:00", "utc").unwrap();
+        assert_eq!(time.jd(), 2451545.0);
+        assert_eq!(Time::from_iso("2000-01-01 12:00Z", "utc").unwrap(), time);
+        assert_eq!(Time::from_iso("2000-01-02", "utc").unwrap().jd(), 2451545.5);
+
+        // every digit survives the round trip
+        let time = Time::from_iso("2024-12-11T12:34:56.123456789", "tdb").unwrap();
+        assert_eq!(time.timescale, TimeScale::TDB);
+        assert_eq!(time.format_iso(9), "2024-12-11T12:34:56.123456789");
+        assert_eq!(time.format_iso(0), "2024-12-11T12:34:56");
+
+        // any number of decimals is accepted
+        let time = Time::from_iso("2024-12-11T12:34:56.1234567890123456789012", "tdb").unwrap();
+        assert_eq!(time.format_iso(9), "2024-12-11T12:34:56.123456789");
+
+        // rounding carries into the next day
+        let time = Time::from_iso("2024-12-31T23:59:59.9999", "utc").unwrap();
+        assert_eq!(time.format_iso(3), "2025-01-01T00:00:00.000");
+        assert_eq!(time.iso(), "2025-01-01T00:00:00.000Z");
+
+        for bad in ["2024-13-01", "2023-02-29", "2024-01-01T25:00:00", "2024-01-01T12:00:0x", "2024-01-01T12:00:00.5e3", "24-01-01", "2024-01-01T+1:00"] {
+            assert!(matches!(Time::from_iso(bad, "utc"), Err(TimeError::ParseError(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_calendar_and_packed_dates() {
+        let time = Time::from_calendar("2000 01 01.5", "utc").unwrap();
+        assert_eq!(time.jd(), 2451545.0);
+        assert_eq!(time.format_calendar(5), "2000 01 01.50000");
+        assert_eq!(Time::from_calendar("2000-01-01.5", "utc").unwrap(), time);
+
+        // the examples from the MPC's description of packed dates
+        let time = Time::from_mpc_packed("J981I73", "tt").unwrap();
+        assert_eq!(time.format_calendar(2), "1998 01 18.73");
+        let time = Time::from_mpc_packed("K01AM1357", "tt").unwrap();
+        assert_eq!(time.format_calendar(4), "2001 10 22.1357");
+        assert_eq!(time.format_mpc_packed(4).unwrap(), "K01AM1357");
+        assert_eq!(Time::from_mpc_packed("J9611", "tt").unwrap().format_mpc_packed(0).unwrap(), "J9611");
+        assert_eq!(Time::from_mpc_packed("K00115", "tt").unwrap().jd(), 2451545.0);
+
+        assert!(Time::new(2400000.5, "tt", "jd").unwrap().format_mpc_packed(0).is_ok());
+        assert!(Time::new(0.0, "tt", "jd").unwrap().format_mpc_packed(0).is_err());
+        for bad in ["K24", "K24D1", "K2421W", "K24215x", "1240101"] {
+            assert!(matches!(Time::from_mpc_packed(bad, "tt"), Err(TimeError::ParseError(_))), "{}", bad);
+        }
+        assert!(matches!(Time::from_calendar("2024 02 30.5", "utc"), Err(TimeError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_numeric_formats() {
+        let time = Time::from_decimal_year(2000.0, "tt").unwrap();
+        assert_eq!(time.jd(), 2451544.5);
+        // 2024 is a leap year, so half way through is the start of July 2
+        let time = Time::new(2024.5, "utc", "decimalyear").unwrap();
+        assert_eq!(time.format, TimeFormat::DecimalYear);
+        assert_eq!(time.format_iso(0), "2024-07-02T00:00:00");
+        assert!((time.epoch() - 2024.5).abs() < 1e-12);
+
+        let time = Time::from_unix(0.0);
+        assert_eq!(time.jd(), 2440587.5);
+        let time = Time::from_unix(1.7e9);
+        assert_eq!(time.format_iso(0), "2023-11-14T22:13:20");
+        assert_eq!(time.epoch(), 1.7e9);
+        assert_eq!(Time::new(-86400.5, "utc", "unix").unwrap().format_iso(1), "1969-12-30T23:59:59.5");
+
+        let time = Time::from_gps_seconds(0.0);
+        assert_eq!(time.jd(), 2444244.5);
+        // GPS was 15 s ahead of UTC in 2011
+        let time = Time::from_gps_seconds(1e9);
+        assert_eq!(time.format_iso(0), "2011-09-14T01:46:40");
+        assert_eq!(time.utc().format_iso(0), "2011-09-14T01:46:25");
+        assert!((time.utc().gps().epoch() - 1e9).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_fuzzy_formats() {
+        let time = Time::from_fuzzy_str("2024-12-11T12:34:56.789 tdb").unwrap();
+        assert_eq!(time.timescale, TimeScale::TDB);
+        assert_eq!(time.format_iso(3), "2024-12-11T12:34:56.789");
+        let time = Time::from_fuzzy_str("2024-12-11 12:34:56.789").unwrap();
+        assert_eq!(time.timescale, TimeScale::UTC);
+        assert_eq!(time.format_iso(3), "2024-12-11T12:34:56.789");
+
+        let time = Time::from_fuzzy_str("2024 12 11.52414").unwrap();
+        assert_eq!(time.format_calendar(5), "2024 12 11.52414");
+        assert_eq!(Time::from_fuzzy_str("2024-12-11.52414 tt").unwrap().format_calendar(5), "2024 12 11.52414");
+
+        let time = Time::from_fuzzy_str("K24CB").unwrap();
+        assert_eq!(time.timescale, TimeScale::TT);
+        assert_eq!(time.format_iso(0), "2024-12-11T00:00:00");
+
+        assert_eq!(Time::from_fuzzy_str("2451545.0").unwrap().format, TimeFormat::JD);
+        assert_eq!(Time::from_fuzzy_str("51544.5 tdb").unwrap().format, TimeFormat::MJD);
+        assert_eq!(Time::from_fuzzy_str("2024.5 utc decimalyear").unwrap().format, TimeFormat::DecimalYear);
+        assert_eq!(Time::from_fuzzy_str("1000000000 gps gps").unwrap(), Time::from_gps_seconds(1e9));
+
+        for bad in ["", "   ", "yesterday", "2451545.0 utc jd extra", "2024-12-11T12:34 utc extra", "2024 13 01.5", "K24CB tt extra"] {
+            assert!(matches!(Time::from_fuzzy_str(bad), Err(TimeError::ParseError(_))), "{:?}", bad);
+        }
+        assert!(matches!(Time::from_fuzzy_str("2451545.0 utc jdd"), Err(TimeError::InvalidTimeFormat(_))));
+
+        // non-ASCII text is an error, not a panic on a character boundary
+        for bad in ["K€12x", "K2€CB", "2024-€1-11T00:00", "2024-12-11T12:34:5€", "2024 12 1€.5", "€", "K24C€"] {
+            assert!(Time::from_fuzzy_str(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_time_range() {
+        let start = Time::new(2460000.5, "utc", "jd").unwrap();
+        let stop = Time::new(2460001.5, "utc", "jd").unwrap();
+
+        let epochs: Vec<Time> = TimeRange::from_step(&start, &stop, 0.25).unwrap().collect();
+        assert_eq!(epochs.len(), 5);
+        assert_eq!(epochs[0], start);
+        assert_eq!(epochs[4], stop);
+        assert_eq!(TimeRange::from_step(&start, &stop, 0.3).unwrap().len(), 4);
+
+        let backwards: Vec<Time> = TimeRange::from_step(&stop, &start, -0.25).unwrap().collect();
+        assert_eq!(backwards.len(), 5);
+        assert_eq!(backwards[4], start);
+        assert!(TimeRange::from_step(&start, &stop, -0.25).is_err());
+        assert!(TimeRange::from_step(&start, &stop, 0.0).is_err());
+
+        let range = TimeRange::from_count(&start, &stop, 3);
+        assert_eq!(range.step(), 0.5);
+        assert_eq!(range.get(1).unwrap().jd(), 2460001.0);
+        assert!(range.get(3).is_none());
+        assert_eq!(range.clone().next_back().unwrap(), stop);
+        assert_eq!(range.rev().map(|t| t.jd()).collect::<Vec<f64>>(), vec![2460001.5, 2460001.0, 2460000.5]);
+        assert_eq!(TimeRange::from_count(&start, &stop, 1).collect::<Vec<Time>>(), vec![start.clone()]);
+        assert_eq!(TimeRange::from_count(&start, &stop, 0).count(), 0);
+
+        // the grid is uniform in the timescale of the start, and the stop can be in any timescale
+        let tai = start.tai();
+        let epochs: Vec<Time> = TimeRange::from_step(&tai, &stop, 0.25).unwrap().collect();
+        assert_eq!(epochs.len(), 5);
+        assert!(epochs.iter().all(|t| t.timescale == TimeScale::TAI));
+        assert!(((&epochs[4] - &tai) - 1.0).abs() < 1e-15);
+
+        // a fine grid keeps its spacing to the nanosecond
+        let range = TimeRange::new(&start, 1.0 / 86400.0, 100_000);
+        let last = range.clone().last().unwrap();
+        assert!(((&last - &start) * 86400.0 - 99_999.0).abs() < 1e-9);
+        assert_eq!(range.skip(86400).next().unwrap().jd(), 2460001.5);
+    }
+
+    #[test]
+    fn test_epoch_grids() {
+        let _tables = lock_global_tables();
+        let start = Time::from_iso("2024-01-01T12:00:00", "tdb").unwrap();
+        let stop = Time::from_iso("2024-01-04T00:00:00", "utc").unwrap();
+        let midnights: Vec<Time> = TimeRange::utc_midnights(&start, &stop).collect();
+        assert_eq!(midnights.len(), 3);
+        assert!(midnights.iter().all(|t| t.timescale == TimeScale::TDB));
+        assert_eq!(midnights[0].utc().format_iso(3), "2024-01-02T00:00:00.000");
+        assert_eq!(midnights[2].utc().format_iso(3), "2024-01-04T00:00:00.000");
+        assert_eq!(TimeRange::utc_midnights(&stop, &start).count(), 0);
+
+        let (lon, lst) = (0.3, 1.0);
+        let start = Time::new(2460000.5, "utc", "jd").unwrap();
+        let stop = Time::new(2460030.5, "utc", "jd").unwrap();
+        let epochs: Vec<Time> = TimeRange::sidereal(&start, &stop, lon, lst).collect();
+        let first_offset = &epochs[0] - &start;
+        assert!((0.0..1.0).contains(&first_offset));
+        let last_offset = &stop - epochs.last().unwrap();
+        assert!((0.0..1.0).contains(&last_offset));
+        for epoch in &epochs {
+            assert_eq!(epoch.timescale, TimeScale::UTC);
+            assert!((epoch.local_sidereal_time(lon) - lst).abs() < 1e-8);
+        }
+        assert!(((&epochs[1] - &epochs[0]) * 86400.0 - 86164.0905).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sidereal_time() {
+        let _tables = lock_global_tables();
+        // values from the SOFA test suite (iauEra00 and iauGmst06)
+        assert!((calc_earth_rotation_angle(2400000.5, 54388.0) - 0.4022837240028158102).abs() < 1e-12);
+        assert!((calc_gmst(2400000.5, 53736.0, 2400000.5, 53736.0) - 1.754174971870091203).abs() < 1e-12);
+
+        let time = Time::new(53736.0, "ut1", "mjd").unwrap();
+        assert!((time.gmst() - calc_gmst(2400000.5, 53736.0, time.tt().day, time.tt().fraction)).abs() < 1e-12);
+        assert!((time.local_sidereal_time(-1.0) - (time.gmst() - 1.0).rem_euclid(std::f64::consts::TAU)).abs() < 1e-12);
+    }
+}